@@ -0,0 +1,240 @@
+use std::collections::BTreeMap;
+
+/// Sessions needed before affinity gets its full blending weight
+pub const COLD_START_SESSIONS: usize = 5;
+/// Maximum share of the final score that affinity can take
+pub const AFFINITY_WEIGHT: f64 = 0.4;
+/// Reading time treated as a complete read
+pub const FULL_READ_MS: i64 = 60_000;
+
+const AUTHOR_WEIGHT: f64 = 0.6;
+const TOPIC_WEIGHT: f64 = 0.4;
+const MIN_TERM_LEN: usize = 3;
+const STOPWORDS: &[&str] = &["the", "and", "for", "with", "this", "that", "are", "was", "not", "you", "all"];
+
+/// One past session of the reader, joined with the post that was read
+pub struct ReadHistoryEntry {
+    pub author: String,
+    pub content: String,
+    pub duration_ms: i64,
+}
+
+/// Reader affinity model (design.md §7)
+/// Personalizes the global feed from a reader's own attention history:
+/// affinity = 0.6*A + 0.4*S
+/// where:
+/// - A: Author affinity (completion-weighted reading time per author)
+/// - S: Topic similarity (cosine over content terms of posts read)
+///
+/// No follower graph is involved; everything comes from `attention_sessions`.
+/// Ordered maps keep float summation order, and therefore rankings, stable.
+pub struct ReaderAffinity {
+    sessions: usize,
+    authors: BTreeMap<String, f64>,
+    terms: BTreeMap<String, f64>,
+    terms_norm: f64,
+}
+
+impl ReaderAffinity {
+    pub fn from_history(history: &[ReadHistoryEntry]) -> Self {
+        let mut authors: BTreeMap<String, f64> = BTreeMap::new();
+        let mut terms: BTreeMap<String, f64> = BTreeMap::new();
+
+        for entry in history {
+            let completion = completion(entry.duration_ms);
+            if completion <= 0.0 {
+                continue;
+            }
+            *authors.entry(entry.author.clone()).or_insert(0.0) += completion;
+            for (term, count) in term_counts(&entry.content) {
+                *terms.entry(term).or_insert(0.0) += completion * count;
+            }
+        }
+
+        // Normalize author weights so the favourite author scores 1.0
+        let max_author = authors.values().cloned().fold(0.0, f64::max);
+        if max_author > 0.0 {
+            for weight in authors.values_mut() {
+                *weight /= max_author;
+            }
+        }
+
+        let terms_norm = norm(terms.values());
+
+        ReaderAffinity {
+            sessions: history.len(),
+            authors,
+            terms,
+            terms_norm,
+        }
+    }
+
+    /// Share of the final score given to affinity, ramping up to
+    /// `AFFINITY_WEIGHT` as the reader accumulates history
+    pub fn blend_weight(&self) -> f64 {
+        let confidence = (self.sessions as f64 / COLD_START_SESSIONS as f64).min(1.0);
+        AFFINITY_WEIGHT * confidence
+    }
+
+    pub fn is_cold(&self) -> bool {
+        self.authors.is_empty()
+    }
+
+    /// Affinity of this reader for a post, in [0, 1]
+    pub fn score(&self, author: &str, content: &str) -> f64 {
+        let author_score = self.authors.get(author).cloned().unwrap_or(0.0);
+        AUTHOR_WEIGHT * author_score + TOPIC_WEIGHT * self.topic_similarity(content)
+    }
+
+    fn topic_similarity(&self, content: &str) -> f64 {
+        if self.terms_norm == 0.0 {
+            return 0.0;
+        }
+        let candidate = term_counts(content);
+        let candidate_norm = norm(candidate.values());
+        if candidate_norm == 0.0 {
+            return 0.0;
+        }
+        let dot: f64 = candidate
+            .iter()
+            .filter_map(|(term, count)| self.terms.get(term).map(|w| w * count))
+            .sum();
+        dot / (self.terms_norm * candidate_norm)
+    }
+}
+
+/// Blend a normalized global score with reader affinity
+pub fn blend(global: f64, affinity: f64, weight: f64) -> f64 {
+    (1.0 - weight) * global + weight * affinity
+}
+
+fn completion(duration_ms: i64) -> f64 {
+    (duration_ms.max(0) as f64 / FULL_READ_MS as f64).min(1.0)
+}
+
+fn term_counts(content: &str) -> BTreeMap<String, f64> {
+    let mut counts = BTreeMap::new();
+    for raw in content.split(|c: char| !c.is_alphanumeric()) {
+        let term = raw.to_lowercase();
+        if term.chars().count() < MIN_TERM_LEN || STOPWORDS.contains(&term.as_str()) {
+            continue;
+        }
+        *counts.entry(term).or_insert(0.0) += 1.0;
+    }
+    counts
+}
+
+fn norm<'a>(values: impl Iterator<Item = &'a f64>) -> f64 {
+    values.map(|v| v * v).sum::<f64>().sqrt()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(author: &str, content: &str, duration_ms: i64) -> ReadHistoryEntry {
+        ReadHistoryEntry {
+            author: author.to_string(),
+            content: content.to_string(),
+            duration_ms,
+        }
+    }
+
+    fn approx(a: f64, b: f64) -> bool {
+        (a - b).abs() < 1e-9
+    }
+
+    #[test]
+    fn term_counts_drops_short_words_and_stopwords() {
+        let counts = term_counts("The Move VM, the move of Sui: and it's FAST fast!");
+
+        assert_eq!(counts.get("move"), Some(&2.0));
+        assert_eq!(counts.get("fast"), Some(&2.0));
+        assert_eq!(counts.get("sui"), Some(&1.0));
+        for dropped in ["the", "and", "vm", "it", "s", "of"] {
+            assert!(!counts.contains_key(dropped), "{} should be dropped", dropped);
+        }
+    }
+
+    #[test]
+    fn term_counts_is_empty_without_terms() {
+        assert!(term_counts("").is_empty());
+        assert!(term_counts("a an of to -- !!").is_empty());
+    }
+
+    #[test]
+    fn empty_or_unread_history_is_cold() {
+        assert!(ReaderAffinity::from_history(&[]).is_cold());
+
+        let skimmed = ReaderAffinity::from_history(&[entry("0xa", "sui move", 0), entry("0xb", "sui move", -5)]);
+        assert!(skimmed.is_cold());
+        assert_eq!(skimmed.score("0xa", "sui move"), 0.0);
+    }
+
+    #[test]
+    fn favourite_author_scores_full_author_weight() {
+        let affinity = ReaderAffinity::from_history(&[
+            entry("0xa", "", FULL_READ_MS),
+            entry("0xa", "", FULL_READ_MS / 2),
+            entry("0xb", "", FULL_READ_MS / 2),
+        ]);
+
+        assert!(approx(affinity.score("0xa", ""), AUTHOR_WEIGHT));
+        assert!(approx(affinity.score("0xb", ""), AUTHOR_WEIGHT / 3.0));
+        assert_eq!(affinity.score("0xc", ""), 0.0);
+    }
+
+    #[test]
+    fn reading_time_past_a_full_read_counts_once() {
+        let capped = ReaderAffinity::from_history(&[entry("0xa", "", FULL_READ_MS * 10), entry("0xb", "", FULL_READ_MS)]);
+
+        assert!(approx(capped.score("0xa", ""), capped.score("0xb", "")));
+    }
+
+    #[test]
+    fn score_combines_author_and_topic_similarity() {
+        let affinity = ReaderAffinity::from_history(&[entry("0xa", "sui move contracts", FULL_READ_MS)]);
+
+        // Same author and same terms: both components are 1.0
+        assert!(approx(affinity.score("0xa", "Sui Move contracts"), 1.0));
+        // Topic only
+        assert!(approx(affinity.score("0xz", "sui move contracts"), TOPIC_WEIGHT));
+        // Nothing in common
+        assert_eq!(affinity.score("0xz", "cooking pasta tonight"), 0.0);
+        // Partial overlap lands strictly between
+        let partial = affinity.score("0xz", "sui cooking");
+        assert!(partial > 0.0 && partial < TOPIC_WEIGHT);
+    }
+
+    #[test]
+    fn scores_are_deterministic() {
+        let history = vec![
+            entry("0xa", "sui move contracts", 30_000),
+            entry("0xb", "walrus storage on sui", 45_000),
+            entry("0xc", "move prover notes", 60_000),
+        ];
+        let first = ReaderAffinity::from_history(&history);
+        let second = ReaderAffinity::from_history(&history);
+
+        for (author, content) in [("0xa", "sui storage"), ("0xb", "move"), ("0xd", "prover contracts walrus")] {
+            assert_eq!(first.score(author, content).to_bits(), second.score(author, content).to_bits());
+        }
+    }
+
+    #[test]
+    fn blend_weight_ramps_up_to_affinity_weight() {
+        let sessions = |n: usize| (0..n).map(|_| entry("0xa", "", FULL_READ_MS)).collect::<Vec<_>>();
+
+        assert!(approx(ReaderAffinity::from_history(&sessions(1)).blend_weight(), AFFINITY_WEIGHT / COLD_START_SESSIONS as f64));
+        assert!(approx(ReaderAffinity::from_history(&sessions(COLD_START_SESSIONS)).blend_weight(), AFFINITY_WEIGHT));
+        assert!(approx(ReaderAffinity::from_history(&sessions(COLD_START_SESSIONS * 4)).blend_weight(), AFFINITY_WEIGHT));
+    }
+
+    #[test]
+    fn blend_mixes_global_and_affinity() {
+        assert_eq!(blend(0.8, 0.2, 0.0), 0.8);
+        assert_eq!(blend(0.8, 0.2, 1.0), 0.2);
+        assert!(approx(blend(1.0, 0.0, AFFINITY_WEIGHT), 1.0 - AFFINITY_WEIGHT));
+        assert!(approx(blend(0.5, 0.5, 0.3), 0.5));
+    }
+}
@@ -0,0 +1,286 @@
+// ============ CONSTANTS (from suiter::attention, see `base_reward`) ============
+
+/// 0.1 SUI in MIST
+pub const REWARD_BASE: u64 = 100_000_000;
+/// Reward time decay interval (10 minutes)
+pub const SESSION_TIMEOUT_SECS: i64 = 600;
+/// Sessions longer than an hour are not humanly plausible
+pub const MAX_SESSION_DURATION: i64 = 3600;
+/// 95% retention per 10 minutes, in basis points. `attention::end_session`
+/// passes 95 to a 10000-scaled pow, which would keep 0.95% after one
+/// interval; the documented intent is 0.95^intervals.
+pub const TIME_DECAY_RATE_BP: u64 = 9_500;
+
+// ============ HEARTBEATS ============
+
+/// How often clients are expected to heartbeat an open session
+pub const HEARTBEAT_INTERVAL_MS: i64 = 15_000;
+/// Gaps longer than this are not counted as reading time
+pub const MAX_HEARTBEAT_GAP_MS: i64 = 2 * HEARTBEAT_INTERVAL_MS;
+/// An active session without heartbeats for this long is abandoned
+pub const HEARTBEAT_TIMEOUT_MS: i64 = 4 * HEARTBEAT_INTERVAL_MS;
+/// Clock skew tolerated between client-reported and server-observed time
+pub const CLOCK_TOLERANCE_MS: i64 = 2_000;
+
+// ============ DIMINISHING RETURNS (design.md §8) ============
+
+/// Each repeat read of the same post earns half the previous one
+pub const PAIR_DECAY_BP: u64 = 5_000;
+/// Valid sessions per reader per day before rewards start to shrink
+pub const DAILY_FULL_REWARD_SESSIONS: i64 = 20;
+/// Retention per session beyond the daily allowance
+pub const DAILY_DECAY_BP: u64 = 8_000;
+
+// ============ REWARD SPLIT (design.md §6.1) ============
+
+pub const CREATOR_SHARE_PCT: i64 = 50;
+pub const READER_SHARE_PCT: i64 = 30;
+
+// ============ POOL THROTTLING ============
+
+/// Burn rate is averaged over this many trailing days
+pub const BURN_WINDOW_DAYS: i64 = 7;
+/// Rewards are paid in full while the pool has at least this much runway
+pub const THROTTLE_RUNWAY_DAYS: i64 = 30;
+
+// ============ SYBIL RISK ============
+
+/// Readers flagged at or above this risk earn nothing
+pub const RISK_BLOCK_THRESHOLD: f64 = 0.8;
+
+const BP: u64 = 10_000;
+
+/// Why a session was flagged invalid
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rejection {
+    SelfAttention,
+    ParallelSession,
+    HeartbeatTimeout,
+    ExceedsMaxDuration,
+    DurationExceedsElapsed,
+    InsufficientHeartbeats,
+    RiskFlagged,
+}
+
+impl Rejection {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Rejection::SelfAttention => "self_attention",
+            Rejection::ParallelSession => "parallel_session",
+            Rejection::HeartbeatTimeout => "heartbeat_timeout",
+            Rejection::ExceedsMaxDuration => "exceeds_max_duration",
+            Rejection::DurationExceedsElapsed => "duration_exceeds_elapsed",
+            Rejection::InsufficientHeartbeats => "insufficient_heartbeats",
+            Rejection::RiskFlagged => "risk_flagged",
+        }
+    }
+}
+
+/// Reading time credited for the gap since the previous heartbeat
+pub fn heartbeat_credit(gap_ms: i64) -> i64 {
+    if gap_ms <= 0 || gap_ms > MAX_HEARTBEAT_GAP_MS {
+        0
+    } else {
+        gap_ms
+    }
+}
+
+/// Validate a finished session against what the server observed.
+/// Returns the duration to credit, which never exceeds the heartbeat-covered
+/// time by more than one interval (the tail after the last heartbeat).
+pub fn check_session(claimed_ms: i64, elapsed_ms: i64, verified_ms: i64) -> Result<i64, Rejection> {
+    if elapsed_ms > MAX_SESSION_DURATION * 1000 || claimed_ms > MAX_SESSION_DURATION * 1000 {
+        return Err(Rejection::ExceedsMaxDuration);
+    }
+    if claimed_ms > elapsed_ms + CLOCK_TOLERANCE_MS {
+        return Err(Rejection::DurationExceedsElapsed);
+    }
+
+    let credited = claimed_ms.min(verified_ms + HEARTBEAT_INTERVAL_MS).max(0);
+    // Anything past the first interval must be backed by heartbeats
+    if claimed_ms > HEARTBEAT_INTERVAL_MS && credited < claimed_ms / 2 {
+        return Err(Rejection::InsufficientHeartbeats);
+    }
+
+    Ok(credited)
+}
+
+/// Reward = BASE × W_time × W_rep, the formula `attention::end_session`
+/// documents, with W_rep = sqrt(rep) / 100 capped at 1.0. The contract
+/// diverges from it twice: besides the decay rate above, it takes
+/// sqrt(rep) / 100 as basis points rather than as a fraction, so any
+/// reputation below 10_000 earns nothing on chain. Rewards are computed
+/// here, not read from the chain, so the documented intent is what's paid.
+pub fn base_reward(duration_ms: i64, reader_rep: i64) -> u64 {
+    let intervals = (duration_ms.max(0) / (SESSION_TIMEOUT_SECS * 1000)) as u64;
+    let time_weight = pow_bp(TIME_DECAY_RATE_BP, intervals);
+
+    // sqrt(rep) / 100, capped at 1.0
+    let rep_weight = (isqrt(reader_rep.max(0) as u64) * 100).min(BP);
+
+    REWARD_BASE * time_weight / BP * rep_weight / BP
+}
+
+/// Multiplier for a reader's `prior_pair` earlier valid sessions on the same
+/// post and `prior_today` valid sessions today, in basis points
+pub fn diminishing_factor(prior_pair: i64, prior_today: i64) -> u64 {
+    let pair = pow_bp(PAIR_DECAY_BP, prior_pair.max(0) as u64);
+    let over_daily = (prior_today - DAILY_FULL_REWARD_SESSIONS + 1).max(0) as u64;
+    let daily = pow_bp(DAILY_DECAY_BP, over_daily);
+
+    pair * daily / BP
+}
+
+/// How a claimed reward is divided
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RewardSplit {
+    pub creator: i64,
+    pub reader: i64,
+    pub pool: i64,
+}
+
+/// The pool's 20% is whatever the other shares leave, so it also takes the dust
+pub fn split_reward(reward: i64) -> RewardSplit {
+    let reward = reward.max(0);
+    let creator = reward * CREATOR_SHARE_PCT / 100;
+    let reader = reward * READER_SHARE_PCT / 100;
+
+    RewardSplit { creator, reader, pool: reward - creator - reader }
+}
+
+/// Reward multiplier for the pool's runway, in basis points: full rewards
+/// down to THROTTLE_RUNWAY_DAYS, then shrinking linearly to zero as the pool
+/// runs dry
+pub fn throttle_factor(available: i64, burn_per_day: i64) -> u64 {
+    if available <= 0 {
+        return 0;
+    }
+    if burn_per_day <= 0 {
+        return BP;
+    }
+
+    let factor = available as i128 * BP as i128 / (burn_per_day as i128 * THROTTLE_RUNWAY_DAYS as i128);
+    factor.min(BP as i128) as u64
+}
+
+/// Reward multiplier for a reader's Sybil risk score, in basis points
+pub fn risk_factor(risk: f64) -> u64 {
+    ((1.0 - risk.clamp(0.0, 1.0)) * BP as f64) as u64
+}
+
+pub fn apply_factor(reward: u64, factor_bp: u64) -> u64 {
+    reward * factor_bp / BP
+}
+
+fn pow_bp(base_bp: u64, exponent: u64) -> u64 {
+    let mut result = BP;
+    for _ in 0..exponent {
+        result = result * base_bp / BP;
+        if result == 0 {
+            break;
+        }
+    }
+    result
+}
+
+fn isqrt(n: u64) -> u64 {
+    (n as f64).sqrt() as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INTERVAL_MS: i64 = SESSION_TIMEOUT_SECS * 1000;
+
+    #[test]
+    fn heartbeat_credit_counts_plausible_gaps_only() {
+        assert_eq!(heartbeat_credit(HEARTBEAT_INTERVAL_MS), HEARTBEAT_INTERVAL_MS);
+        assert_eq!(heartbeat_credit(MAX_HEARTBEAT_GAP_MS), MAX_HEARTBEAT_GAP_MS);
+        assert_eq!(heartbeat_credit(MAX_HEARTBEAT_GAP_MS + 1), 0);
+        assert_eq!(heartbeat_credit(0), 0);
+        assert_eq!(heartbeat_credit(-1_000), 0);
+    }
+
+    #[test]
+    fn check_session_credits_heartbeat_backed_time() {
+        // Fully covered by heartbeats
+        assert_eq!(check_session(60_000, 60_000, 60_000), Ok(60_000));
+        // Short sessions need no heartbeats
+        assert_eq!(check_session(HEARTBEAT_INTERVAL_MS, HEARTBEAT_INTERVAL_MS, 0), Ok(HEARTBEAT_INTERVAL_MS));
+        // The tail after the last heartbeat is credited up to one interval
+        assert_eq!(check_session(60_000, 60_000, 30_000), Ok(30_000 + HEARTBEAT_INTERVAL_MS));
+        // Small clock skew is tolerated
+        assert_eq!(check_session(61_000, 60_000, 60_000), Ok(61_000));
+        assert_eq!(check_session(-5, 1_000, 0), Ok(0));
+    }
+
+    #[test]
+    fn check_session_rejects_implausible_sessions() {
+        let max_ms = MAX_SESSION_DURATION * 1000;
+        assert_eq!(check_session(max_ms + 1, max_ms + 1, max_ms), Err(Rejection::ExceedsMaxDuration));
+        assert_eq!(check_session(1_000, max_ms + 1, 1_000), Err(Rejection::ExceedsMaxDuration));
+        assert_eq!(check_session(60_000 + CLOCK_TOLERANCE_MS + 1, 60_000, 60_000), Err(Rejection::DurationExceedsElapsed));
+        assert_eq!(check_session(120_000, 120_000, 10_000), Err(Rejection::InsufficientHeartbeats));
+    }
+
+    #[test]
+    fn base_reward_decays_with_time_and_scales_with_reputation() {
+        // rep 10_000 gives the full reputation weight
+        assert_eq!(base_reward(0, 10_000), REWARD_BASE);
+        assert_eq!(base_reward(INTERVAL_MS - 1, 10_000), REWARD_BASE);
+        assert_eq!(base_reward(INTERVAL_MS, 10_000), REWARD_BASE * 95 / 100);
+        assert_eq!(base_reward(2 * INTERVAL_MS, 10_000), REWARD_BASE * 9_025 / 10_000);
+        // sqrt(2_500) / 100 = 0.5, and the weight caps at 1.0
+        assert_eq!(base_reward(0, 2_500), REWARD_BASE / 2);
+        assert_eq!(base_reward(0, 1_000_000), REWARD_BASE);
+        assert_eq!(base_reward(0, 0), 0);
+        assert_eq!(base_reward(-1, -1), 0);
+    }
+
+    #[test]
+    fn diminishing_factor_halves_repeat_reads_and_shrinks_past_the_daily_allowance() {
+        assert_eq!(diminishing_factor(0, 0), BP);
+        assert_eq!(diminishing_factor(1, 0), BP / 2);
+        assert_eq!(diminishing_factor(3, 0), BP / 8);
+        assert_eq!(diminishing_factor(0, DAILY_FULL_REWARD_SESSIONS - 1), BP);
+        assert_eq!(diminishing_factor(0, DAILY_FULL_REWARD_SESSIONS), DAILY_DECAY_BP);
+        assert_eq!(diminishing_factor(0, DAILY_FULL_REWARD_SESSIONS + 1), DAILY_DECAY_BP * DAILY_DECAY_BP / BP);
+        assert_eq!(diminishing_factor(1, DAILY_FULL_REWARD_SESSIONS), DAILY_DECAY_BP / 2);
+        assert_eq!(diminishing_factor(-3, -3), BP);
+        assert_eq!(diminishing_factor(100, 0), 0);
+    }
+
+    #[test]
+    fn split_reward_gives_the_pool_the_remainder() {
+        assert_eq!(split_reward(1_000), RewardSplit { creator: 500, reader: 300, pool: 200 });
+        // 999 * 50% = 499, 999 * 30% = 299; the pool takes the dust
+        assert_eq!(split_reward(999), RewardSplit { creator: 499, reader: 299, pool: 201 });
+        assert_eq!(split_reward(1), RewardSplit { creator: 0, reader: 0, pool: 1 });
+        assert_eq!(split_reward(0), RewardSplit { creator: 0, reader: 0, pool: 0 });
+        assert_eq!(split_reward(-10), RewardSplit { creator: 0, reader: 0, pool: 0 });
+
+        for reward in [1, 7, 999, 123_456_789] {
+            let split = split_reward(reward);
+            assert_eq!(split.creator + split.reader + split.pool, reward);
+        }
+    }
+
+    #[test]
+    fn throttle_factor_shrinks_linearly_below_the_runway() {
+        let burn = 1_000;
+        let full = burn * THROTTLE_RUNWAY_DAYS;
+
+        assert_eq!(throttle_factor(full * 2, burn), BP);
+        assert_eq!(throttle_factor(full, burn), BP);
+        assert_eq!(throttle_factor(full / 2, burn), BP / 2);
+        assert_eq!(throttle_factor(full / 10, burn), BP / 10);
+        assert_eq!(throttle_factor(1, burn), 0);
+        // A dry pool pays nothing, an idle one pays in full
+        assert_eq!(throttle_factor(0, burn), 0);
+        assert_eq!(throttle_factor(-5, burn), 0);
+        assert_eq!(throttle_factor(1, 0), BP);
+        // Balances near i64::MAX don't overflow
+        assert_eq!(throttle_factor(i64::MAX, i64::MAX), BP / THROTTLE_RUNWAY_DAYS as u64);
+    }
+}
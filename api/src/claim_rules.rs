@@ -0,0 +1,252 @@
+// ============ CONSTANTS (mirrors suiter::truth_claim) ============
+
+pub const MIN_REPUTATION_TO_VOTE: i64 = 50;
+pub const MIN_REPUTATION_TO_CLAIM: i64 = 100;
+/// 7 days
+pub const VOTING_PERIOD_SECS: i64 = 7 * 24 * 3600;
+/// 51% of the weighted vote needed to accept
+pub const CLAIM_THRESHOLD: i64 = 51;
+
+// ============ STAKES ============
+
+/// 0.1 SUI in MIST; what a claimer must put at risk
+pub const MIN_CLAIM_STAKE: i64 = 100_000_000;
+/// 1M SUI in MIST; the most one claimer or voter can stake
+pub const MAX_STAKE: i64 = 1_000_000_000_000_000;
+
+// ============ QUORUM ============
+
+/// Distinct voters needed for a claim to resolve either way
+pub const QUORUM_MIN_VOTERS: i64 = 3;
+/// Total reputation behind the votes needed for a claim to resolve
+pub const QUORUM_MIN_WEIGHT: i64 = 500;
+
+/// Votes on one claim, as raw counts and summed reputation snapshots
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Tally {
+    pub yes: i64,
+    pub no: i64,
+    pub weighted_yes: i64,
+    pub weighted_no: i64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    Accepted,
+    Rejected,
+    NoQuorum,
+}
+
+impl Outcome {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Outcome::Accepted => "accepted",
+            Outcome::Rejected => "rejected",
+            Outcome::NoQuorum => "no_quorum",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Outcome> {
+        match s {
+            "accepted" => Some(Outcome::Accepted),
+            "rejected" => Some(Outcome::Rejected),
+            "no_quorum" => Some(Outcome::NoQuorum),
+            _ => None,
+        }
+    }
+
+    /// Value stored in `truth_claims.accepted`; NULL when there was no quorum
+    pub fn accepted(&self) -> Option<bool> {
+        match self {
+            Outcome::Accepted => Some(true),
+            Outcome::Rejected => Some(false),
+            Outcome::NoQuorum => None,
+        }
+    }
+}
+
+impl Tally {
+    pub fn voters(&self) -> i64 {
+        self.yes + self.no
+    }
+
+    pub fn weight(&self) -> i64 {
+        self.weighted_yes + self.weighted_no
+    }
+
+    /// Weighted share of yes votes, in percent
+    pub fn yes_share(&self) -> f64 {
+        if self.weight() == 0 {
+            0.0
+        } else {
+            self.weighted_yes as f64 * 100.0 / self.weight() as f64
+        }
+    }
+
+    pub fn has_quorum(&self) -> bool {
+        self.voters() >= QUORUM_MIN_VOTERS && self.weight() >= QUORUM_MIN_WEIGHT
+    }
+
+    /// Accepted when yes holds at least CLAIM_THRESHOLD percent of the
+    /// reputation that voted, so many low-reputation accounts can't outvote
+    /// a few established ones
+    pub fn outcome(&self) -> Outcome {
+        if !self.has_quorum() {
+            Outcome::NoQuorum
+        } else if self.weighted_yes * 100 >= CLAIM_THRESHOLD * self.weight() {
+            Outcome::Accepted
+        } else {
+            Outcome::Rejected
+        }
+    }
+}
+
+/// Stake put on one side of a claim; the claimer's stake backs yes
+#[derive(Debug, Clone)]
+pub struct Stake {
+    pub address: String,
+    pub side: bool,
+    pub amount: i64,
+}
+
+/// How a claim's escrow is paid out
+#[derive(Debug, Default)]
+pub struct Settlement {
+    pub payouts: Vec<(String, i64)>,
+    pub treasury: i64,
+}
+
+/// Without quorum every stake is refunded. Otherwise the losing side's stakes
+/// are slashed and shared among the winning side pro rata to stake, on top of
+/// their own stake back. Rounding dust, and slashed stakes with no winning
+/// stake to receive them, go to the treasury.
+/// Sums are taken in i128; every payout is bounded by the escrow, which the
+/// ledger holds as i64.
+pub fn settle(outcome: Outcome, stakes: &[Stake]) -> Settlement {
+    let winning_side = match outcome {
+        Outcome::Accepted => true,
+        Outcome::Rejected => false,
+        Outcome::NoQuorum => {
+            return Settlement {
+                payouts: stakes.iter().filter(|s| s.amount > 0).map(|s| (s.address.clone(), s.amount)).collect(),
+                treasury: 0,
+            };
+        }
+    };
+
+    let (winners, losers): (Vec<&Stake>, Vec<&Stake>) = stakes
+        .iter()
+        .filter(|s| s.amount > 0)
+        .partition(|s| s.side == winning_side);
+    let slashed: i128 = losers.iter().map(|s| s.amount as i128).sum();
+    let winning_stake: i128 = winners.iter().map(|s| s.amount as i128).sum();
+
+    let mut settlement = Settlement::default();
+    let mut distributed = 0;
+    for winner in winners {
+        let share = slashed * winner.amount as i128 / winning_stake;
+        distributed += share;
+        settlement.payouts.push((winner.address.clone(), (winner.amount as i128 + share) as i64));
+    }
+    settlement.treasury = (slashed - distributed) as i64;
+
+    settlement
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tally(yes: i64, no: i64, weighted_yes: i64, weighted_no: i64) -> Tally {
+        Tally { yes, no, weighted_yes, weighted_no }
+    }
+
+    fn stake(address: &str, side: bool, amount: i64) -> Stake {
+        Stake { address: address.to_string(), side, amount }
+    }
+
+    fn paid(settlement: &Settlement, address: &str) -> i64 {
+        settlement.payouts.iter().filter(|(a, _)| a == address).map(|(_, amount)| amount).sum()
+    }
+
+    #[test]
+    fn outcome_needs_voters_and_weight_for_quorum() {
+        assert_eq!(tally(2, 0, 1_000, 0).outcome(), Outcome::NoQuorum);
+        assert_eq!(tally(3, 0, QUORUM_MIN_WEIGHT - 1, 0).outcome(), Outcome::NoQuorum);
+        assert_eq!(tally(3, 0, QUORUM_MIN_WEIGHT, 0).outcome(), Outcome::Accepted);
+    }
+
+    #[test]
+    fn outcome_is_decided_by_weight_not_headcount() {
+        // Many low-reputation no votes lose to a few established yes votes
+        assert_eq!(tally(2, 10, 600, 500).outcome(), Outcome::Accepted);
+        assert_eq!(tally(10, 2, 500, 600).outcome(), Outcome::Rejected);
+    }
+
+    #[test]
+    fn outcome_threshold_is_inclusive() {
+        assert_eq!(tally(3, 3, 510, 490).outcome(), Outcome::Accepted);
+        assert_eq!(tally(3, 3, 509, 491).outcome(), Outcome::Rejected);
+    }
+
+    #[test]
+    fn settle_accepted_shares_slashed_no_stakes_with_yes() {
+        let stakes = [stake("claimer", true, 300), stake("a", true, 100), stake("b", false, 200)];
+        let s = settle(Outcome::Accepted, &stakes);
+
+        assert_eq!(paid(&s, "claimer"), 450);
+        assert_eq!(paid(&s, "a"), 150);
+        assert_eq!(paid(&s, "b"), 0);
+        assert_eq!(s.treasury, 0);
+    }
+
+    #[test]
+    fn settle_rejected_slashes_the_claimer() {
+        let stakes = [stake("claimer", true, 300), stake("b", false, 100)];
+        let s = settle(Outcome::Rejected, &stakes);
+
+        assert_eq!(paid(&s, "claimer"), 0);
+        assert_eq!(paid(&s, "b"), 400);
+        assert_eq!(s.treasury, 0);
+    }
+
+    #[test]
+    fn settle_without_quorum_refunds_everyone() {
+        let stakes = [stake("claimer", true, 300), stake("a", false, 100), stake("b", true, 0)];
+        let s = settle(Outcome::NoQuorum, &stakes);
+
+        assert_eq!(s.payouts, vec![("claimer".to_string(), 300), ("a".to_string(), 100)]);
+        assert_eq!(s.treasury, 0);
+    }
+
+    #[test]
+    fn settle_sends_rounding_dust_to_the_treasury() {
+        let stakes = [stake("claimer", true, 1), stake("a", true, 1), stake("b", true, 1), stake("c", false, 10)];
+        let s = settle(Outcome::Accepted, &stakes);
+
+        // 10 split three ways is 3 each, with 1 left over
+        for address in ["claimer", "a", "b"] {
+            assert_eq!(paid(&s, address), 4);
+        }
+        assert_eq!(s.treasury, 1);
+    }
+
+    #[test]
+    fn settle_sends_slashed_stakes_without_winners_to_the_treasury() {
+        let stakes = [stake("claimer", true, 0), stake("a", false, 100)];
+        let s = settle(Outcome::Accepted, &stakes);
+
+        assert!(s.payouts.is_empty());
+        assert_eq!(s.treasury, 100);
+    }
+
+    #[test]
+    fn settle_conserves_the_escrow_at_maximum_stakes() {
+        let stakes: Vec<Stake> = (0..20).map(|i| stake(&format!("s{}", i), i % 3 == 0, MAX_STAKE - i)).collect();
+        let escrow: i128 = stakes.iter().map(|s| s.amount as i128).sum();
+        let s = settle(Outcome::Rejected, &stakes);
+
+        let paid: i128 = s.payouts.iter().map(|(_, amount)| *amount as i128).sum();
+        assert_eq!(paid + s.treasury as i128, escrow);
+    }
+}
@@ -0,0 +1,77 @@
+use axum::{
+    async_trait,
+    extract::{FromRequest, FromRequestParts, Path, Query, Request},
+    http::{request::Parts, StatusCode},
+    Json,
+};
+use serde::de::DeserializeOwned;
+use serde_json::json;
+
+// ============ EXTRACTORS ============
+//
+// axum's own extractors answer malformed input with plain-text 400s and 422s.
+// These wrap them so a bad address or body is a 400 with the usual
+// `{"error": ...}` body.
+
+type Rejection = (StatusCode, Json<serde_json::Value>);
+
+fn bad_request(message: String) -> Rejection {
+    (StatusCode::BAD_REQUEST, Json(json!({ "error": message })))
+}
+
+/// `Json<T>` with JSON 400 rejections
+pub struct ApiJson<T>(pub T);
+
+#[async_trait]
+impl<T, S> FromRequest<S> for ApiJson<T>
+where
+    T: DeserializeOwned,
+    S: Send + Sync,
+{
+    type Rejection = Rejection;
+
+    async fn from_request(req: Request, state: &S) -> Result<Self, Self::Rejection> {
+        match Json::<T>::from_request(req, state).await {
+            Ok(Json(value)) => Ok(ApiJson(value)),
+            Err(e) => Err(bad_request(e.body_text())),
+        }
+    }
+}
+
+/// `Path<T>` with JSON 400 rejections
+pub struct ApiPath<T>(pub T);
+
+#[async_trait]
+impl<T, S> FromRequestParts<S> for ApiPath<T>
+where
+    T: DeserializeOwned + Send,
+    S: Send + Sync,
+{
+    type Rejection = Rejection;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        match Path::<T>::from_request_parts(parts, state).await {
+            Ok(Path(value)) => Ok(ApiPath(value)),
+            Err(e) => Err(bad_request(e.body_text())),
+        }
+    }
+}
+
+/// `Query<T>` with JSON 400 rejections
+pub struct ApiQuery<T>(pub T);
+
+#[async_trait]
+impl<T, S> FromRequestParts<S> for ApiQuery<T>
+where
+    T: DeserializeOwned,
+    S: Send + Sync,
+{
+    type Rejection = Rejection;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        match Query::<T>::from_request_parts(parts, state).await {
+            Ok(Query(value)) => Ok(ApiQuery(value)),
+            Err(e) => Err(bad_request(e.body_text())),
+        }
+    }
+}
@@ -0,0 +1,116 @@
+use axum::body::Bytes;
+use serde_json::json;
+use sqlx::{Row, SqlitePool};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::RwLock;
+use tokio::time::sleep;
+
+/// Number of top-ranked posts kept in memory
+pub const FEED_CACHE_SIZE: i64 = 200;
+/// How often the ranking version is checked
+const VERSION_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Snapshot of the global feed at one ranking version
+pub struct CachedFeed {
+    pub version: i64,
+    pub etag: String,
+    pub posts: Arc<Vec<serde_json::Value>>,
+    /// Pre-serialized JSON body served to every reader
+    pub body: Bytes,
+}
+
+/// In-process cache of the top-N ranked feed
+/// A background task polls `ranking_state.version`, which FeedRanker bumps on
+/// every write, and reloads the feed only when it changes. Feed requests are
+/// then served from memory without touching SQLite.
+#[derive(Default)]
+pub struct FeedCache {
+    current: RwLock<Option<Arc<CachedFeed>>>,
+}
+
+impl FeedCache {
+    pub async fn get(&self) -> Option<Arc<CachedFeed>> {
+        self.current.read().await.clone()
+    }
+
+    /// Return the cached feed, loading it first if the refresher hasn't yet
+    pub async fn get_or_load(&self, pool: &SqlitePool) -> Result<Arc<CachedFeed>, sqlx::Error> {
+        if let Some(feed) = self.get().await {
+            return Ok(feed);
+        }
+
+        let version = current_version(pool).await?;
+        self.reload(pool, version).await
+    }
+
+    /// Keep the cache in sync with the ranking version
+    pub async fn run_refresher(&self, pool: SqlitePool) {
+        loop {
+            if let Err(e) = self.refresh(&pool).await {
+                tracing::error!("Failed to refresh feed cache: {}", e);
+            }
+
+            sleep(VERSION_POLL_INTERVAL).await;
+        }
+    }
+
+    async fn refresh(&self, pool: &SqlitePool) -> Result<(), sqlx::Error> {
+        let version = current_version(pool).await?;
+        let stale = match self.get().await {
+            Some(feed) => feed.version != version,
+            None => true,
+        };
+
+        if stale {
+            self.reload(pool, version).await?;
+        }
+
+        Ok(())
+    }
+
+    async fn reload(&self, pool: &SqlitePool, version: i64) -> Result<Arc<CachedFeed>, sqlx::Error> {
+        let posts = load_feed(pool).await?;
+        let body = Bytes::from(serde_json::to_vec(&posts).unwrap_or_default());
+
+        let feed = Arc::new(CachedFeed {
+            version,
+            etag: format!("\"feed-{}\"", version),
+            posts: Arc::new(posts),
+            body,
+        });
+
+        *self.current.write().await = Some(feed.clone());
+        tracing::debug!("Feed cache reloaded at ranking version {}", version);
+        Ok(feed)
+    }
+}
+
+async fn current_version(pool: &SqlitePool) -> Result<i64, sqlx::Error> {
+    let version: Option<i64> = sqlx::query_scalar("SELECT version FROM ranking_state WHERE id = 1")
+        .fetch_optional(pool)
+        .await?;
+
+    Ok(version.unwrap_or(0))
+}
+
+async fn load_feed(pool: &SqlitePool) -> Result<Vec<serde_json::Value>, sqlx::Error> {
+    let rows = sqlx::query("SELECT p.id, p.author, p.content_hash, p.level, p.attention_accumulated, CAST(COALESCE(fr.score,0.0) AS REAL) as score FROM posts p LEFT JOIN feed_rankings fr ON p.id = fr.post_id ORDER BY CAST(COALESCE(fr.score,0.0) AS REAL) DESC LIMIT ?")
+        .bind(FEED_CACHE_SIZE)
+        .fetch_all(pool)
+        .await?;
+
+    Ok(rows
+        .into_iter()
+        .map(|r| {
+            json!({
+                "id": r.get::<String, _>("id"),
+                "author": r.get::<String, _>("author"),
+                "content_hash": r.get::<String, _>("content_hash"),
+                "level": r.get::<i64, _>("level"),
+                "attention_accumulated": r.get::<i64, _>("attention_accumulated"),
+                "score": r.get::<f64, _>("score"),
+            })
+        })
+        .collect())
+}
@@ -4,40 +4,467 @@ use axum::{
     Json,
 };
 use serde_json::json;
+use sqlx::{Row, SqlitePool};
 use std::sync::Arc;
+use suiter_core::{
+    ledger::{self, LedgerTxn},
+    reputation::{self, Reason, ReputationLedger},
+    stream,
+};
+use suiter_ranker::FeedRanker;
+use uuid::Uuid;
+
+use crate::{
+    extract::ApiJson,
+    attention_rules::{self as rules, Rejection, RewardSplit},
+    models::{EndSessionRequest, StartSessionRequest},
+    treasury, AppState,
+};
 
-use crate::AppState;
+fn now_ms() -> i64 {
+    chrono::Utc::now().timestamp_millis()
+}
 
 pub async fn start_session(
-    State(_state): State<Arc<AppState>>,
-    Json(payload): Json<serde_json::Value>,
+    State(state): State<Arc<AppState>>,
+    ApiJson(payload): ApiJson<StartSessionRequest>,
 ) -> (StatusCode, Json<serde_json::Value>) {
-    // TODO: Implement start session
+    let pool = &state.pool;
+    let now = now_ms();
+
+    let reader = payload.reader;
+
+    let author: Option<String> = match sqlx::query_scalar("SELECT author FROM posts WHERE id = ?")
+        .bind(&payload.post_id)
+        .fetch_optional(pool)
+        .await
+    {
+        Ok(a) => a,
+        Err(e) => {
+            tracing::error!("DB error: {}", e);
+            return (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({ "error": "db error" })));
+        }
+    };
+    let Some(author) = author else {
+        return (StatusCode::NOT_FOUND, Json(json!({ "error": "post not found" })));
+    };
+
+    // Abandoned sessions can't block new ones; flag them as timed out
+    if let Err(e) = sqlx::query("UPDATE attention_sessions SET status = 'ended', validity = 'invalid', rejection_reason = ?, ended_at = ? WHERE reader = ? AND status = 'active' AND last_heartbeat_at < ?")
+        .bind(Rejection::HeartbeatTimeout.as_str())
+        .bind(now)
+        .bind(&reader)
+        .bind(now - rules::HEARTBEAT_TIMEOUT_MS)
+        .execute(pool)
+        .await
+    {
+        tracing::error!("Failed to expire stale sessions: {}", e);
+    }
+
+    let mut rejection = if author == reader.as_str() {
+        Some(Rejection::SelfAttention)
+    } else {
+        match sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM attention_sessions WHERE reader = ? AND status = 'active'")
+            .bind(&reader)
+            .fetch_one(pool)
+            .await
+        {
+            Ok(0) => None,
+            Ok(_) => Some(Rejection::ParallelSession),
+            Err(e) => {
+                tracing::error!("DB error: {}", e);
+                return (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({ "error": "db error" })));
+            }
+        }
+    };
+
+    if let Err(e) = sqlx::query(
+        "INSERT OR IGNORE INTO profiles(address, reputation, total_posts, total_attention_earned, joined_at, updated_at) VALUES (?, 50, 0, 0, CURRENT_TIMESTAMP, CURRENT_TIMESTAMP)"
+    ).bind(&reader).execute(pool).await {
+        tracing::error!("Failed to ensure profile: {}", e);
+    }
+
+    // Rejected sessions are still recorded so abuse patterns stay auditable.
+    // A start that raced another past the check above loses to the unique
+    // index on active sessions and is recorded as parallel instead.
+    let id = Uuid::new_v4().to_string();
+    let mut inserted = insert_session(pool, &id, reader.as_str(), &payload.post_id, now, rejection).await;
+    if matches!(&inserted, Err(sqlx::Error::Database(e)) if e.is_unique_violation()) {
+        rejection = Some(Rejection::ParallelSession);
+        inserted = insert_session(pool, &id, reader.as_str(), &payload.post_id, now, rejection).await;
+    }
+    if let Err(e) = inserted {
+        tracing::error!("Failed to insert session: {}", e);
+        return (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({ "error": "failed to start session" })));
+    }
+    let validity = if rejection.is_some() { "invalid" } else { "pending" };
+
     (StatusCode::CREATED, Json(json!({
-        "session_id": "0x...",
-        "status": "started"
+        "session_id": id,
+        "status": if rejection.is_some() { "rejected" } else { "started" },
+        "validity": validity,
+        "rejection_reason": rejection.map(|r| r.as_str()),
+        "heartbeat_interval_ms": rules::HEARTBEAT_INTERVAL_MS,
     })))
 }
 
+/// Record a new session: active, or already ended if it was rejected
+async fn insert_session(
+    pool: &SqlitePool,
+    id: &str,
+    reader: &str,
+    post_id: &str,
+    now: i64,
+    rejection: Option<Rejection>,
+) -> Result<(), sqlx::Error> {
+    let (status, validity) = match rejection {
+        Some(_) => ("ended", "invalid"),
+        None => ("active", "pending"),
+    };
+
+    sqlx::query(
+        "INSERT INTO attention_sessions(id, reader, post_id, duration_ms, reward, claimed, created_at, started_at, ended_at, last_heartbeat_at, heartbeat_count, verified_ms, status, validity, rejection_reason) VALUES (?, ?, ?, 0, 0, FALSE, CURRENT_TIMESTAMP, ?, ?, ?, 0, 0, ?, ?, ?)"
+    )
+    .bind(id)
+    .bind(reader)
+    .bind(post_id)
+    .bind(now)
+    .bind(rejection.map(|_| now))
+    .bind(now)
+    .bind(status)
+    .bind(validity)
+    .bind(rejection.map(|r| r.as_str()))
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+pub async fn heartbeat(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+) -> (StatusCode, Json<serde_json::Value>) {
+    let pool = &state.pool;
+    let now = now_ms();
+
+    let row = match sqlx::query("SELECT status, last_heartbeat_at FROM attention_sessions WHERE id = ?")
+        .bind(&id)
+        .fetch_optional(pool)
+        .await
+    {
+        Ok(Some(r)) => r,
+        Ok(None) => return (StatusCode::NOT_FOUND, Json(json!({ "error": "session not found" }))),
+        Err(e) => {
+            tracing::error!("DB error: {}", e);
+            return (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({ "error": "db error" })));
+        }
+    };
+
+    if row.get::<String, _>("status") != "active" {
+        return (StatusCode::CONFLICT, Json(json!({ "error": "session not active" })));
+    }
+
+    let gap = now - row.get::<Option<i64>, _>("last_heartbeat_at").unwrap_or(now);
+    if gap > rules::HEARTBEAT_TIMEOUT_MS {
+        let _ = sqlx::query("UPDATE attention_sessions SET status = 'ended', validity = 'invalid', rejection_reason = ?, ended_at = ? WHERE id = ?")
+            .bind(Rejection::HeartbeatTimeout.as_str())
+            .bind(now)
+            .bind(&id)
+            .execute(pool)
+            .await;
+        return (StatusCode::CONFLICT, Json(json!({
+            "error": "session timed out",
+            "rejection_reason": Rejection::HeartbeatTimeout.as_str(),
+        })));
+    }
+
+    let result = sqlx::query(
+        "UPDATE attention_sessions SET last_heartbeat_at = ?, heartbeat_count = heartbeat_count + 1, verified_ms = verified_ms + ? WHERE id = ? AND status = 'active' RETURNING verified_ms"
+    )
+    .bind(now)
+    .bind(rules::heartbeat_credit(gap))
+    .bind(&id)
+    .fetch_optional(pool)
+    .await;
+
+    match result {
+        Ok(Some(r)) => (StatusCode::OK, Json(json!({
+            "session_id": id,
+            "verified_ms": r.get::<i64, _>("verified_ms"),
+        }))),
+        Ok(None) => (StatusCode::CONFLICT, Json(json!({ "error": "session not active" }))),
+        Err(e) => {
+            tracing::error!("Failed to record heartbeat: {}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({ "error": "db error" })))
+        }
+    }
+}
+
 pub async fn end_session(
-    State(_state): State<Arc<AppState>>,
-    Path(_id): Path<String>,
-    Json(payload): Json<serde_json::Value>,
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+    ApiJson(payload): ApiJson<EndSessionRequest>,
 ) -> (StatusCode, Json<serde_json::Value>) {
-    // TODO: Implement end session
+    let pool = &state.pool;
+    let now = now_ms();
+
+    let row = match sqlx::query(
+        "SELECT s.reader, s.post_id, s.status, s.started_at, s.last_heartbeat_at, s.verified_ms, COALESCE(p.reputation, 50) as reputation, (SELECT MAX(score) FROM risk_flags WHERE address = s.reader) as risk FROM attention_sessions s LEFT JOIN profiles p ON p.address = s.reader WHERE s.id = ?"
+    )
+    .bind(&id)
+    .fetch_optional(pool)
+    .await
+    {
+        Ok(Some(r)) => r,
+        Ok(None) => return (StatusCode::NOT_FOUND, Json(json!({ "error": "session not found" }))),
+        Err(e) => {
+            tracing::error!("DB error: {}", e);
+            return (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({ "error": "db error" })));
+        }
+    };
+
+    if row.get::<String, _>("status") != "active" {
+        return (StatusCode::CONFLICT, Json(json!({ "error": "session not active" })));
+    }
+
+    let reader = row.get::<String, _>("reader");
+    let post_id = row.get::<String, _>("post_id");
+    let started_at = row.get::<Option<i64>, _>("started_at").unwrap_or(now);
+    let last_heartbeat = row.get::<Option<i64>, _>("last_heartbeat_at").unwrap_or(started_at);
+
+    // The tail since the last heartbeat counts only if it is within one gap
+    let verified = row.get::<i64, _>("verified_ms") + rules::heartbeat_credit(now - last_heartbeat);
+
+    let risk = row.get::<Option<f64>, _>("risk").unwrap_or(0.0);
+
+    let outcome = rules::check_session(payload.duration_ms, now - started_at, verified).and_then(|credited| {
+        if risk >= rules::RISK_BLOCK_THRESHOLD {
+            Err(Rejection::RiskFlagged)
+        } else {
+            Ok(credited)
+        }
+    });
+
+    let (duration_ms, reward) = match outcome {
+        Ok(credited) => {
+            let prior: Result<(i64, i64), sqlx::Error> = sqlx::query_as(
+                r#"
+                SELECT
+                    (SELECT COUNT(*) FROM attention_sessions WHERE reader = ?1 AND post_id = ?2 AND validity = 'valid'),
+                    (SELECT COUNT(*) FROM attention_sessions WHERE reader = ?1 AND validity = 'valid' AND ended_at >= ?3)
+                "#
+            )
+            .bind(&reader)
+            .bind(&post_id)
+            .bind(now - 24 * 3600 * 1000)
+            .fetch_one(pool)
+            .await;
+
+            let (prior_pair, prior_today) = match prior {
+                Ok(p) => p,
+                Err(e) => {
+                    tracing::error!("DB error: {}", e);
+                    return (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({ "error": "db error" })));
+                }
+            };
+
+            let base = rules::base_reward(credited, row.get::<i64, _>("reputation"));
+            let reward = rules::apply_factor(base, rules::diminishing_factor(prior_pair, prior_today));
+            let reward = rules::apply_factor(reward, rules::risk_factor(risk));
+
+            let reward = if state.reward_throttling {
+                match treasury::load(pool).await {
+                    Ok(status) => rules::apply_factor(reward, status.throttle_factor()).min(status.available().max(0) as u64),
+                    Err(e) => {
+                        tracing::error!("DB error: {}", e);
+                        return (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({ "error": "db error" })));
+                    }
+                }
+            } else {
+                reward
+            };
+            (credited, reward)
+        }
+        Err(_) => (0, 0),
+    };
+
+    let validity = if outcome.is_ok() { "valid" } else { "invalid" };
+    let rejection = outcome.err().map(|r| r.as_str());
+
+    let ended = sqlx::query(
+        "UPDATE attention_sessions SET status = 'ended', ended_at = ?, duration_ms = ?, reward = ?, validity = ?, rejection_reason = ? WHERE id = ? AND status = 'active'"
+    )
+    .bind(now)
+    .bind(duration_ms)
+    .bind(reward as i64)
+    .bind(validity)
+    .bind(rejection)
+    .bind(&id)
+    .execute(pool)
+    .await;
+    match ended {
+        // A concurrent end, or a new session expiring this one, got there first
+        Ok(r) if r.rows_affected() == 0 => {
+            return (StatusCode::CONFLICT, Json(json!({ "error": "session not active" })));
+        }
+        Ok(_) => {}
+        Err(e) => {
+            tracing::error!("Failed to end session: {}", e);
+            return (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({ "error": "failed to end session" })));
+        }
+    }
+
+    if validity == "valid" {
+        state.stream.publish(pool, &stream::post_topic(&post_id), "attention", json!({
+            "post_id": post_id,
+            "session_id": id,
+            "duration_ms": duration_ms,
+        })).await;
+    }
+
     (StatusCode::OK, Json(json!({
-        "reward": 0,
+        "session_id": id,
+        "duration_ms": duration_ms,
+        "reward": reward,
+        "validity": validity,
+        "rejection_reason": rejection,
         "claimed": false
     })))
 }
 
 pub async fn claim_reward(
-    State(_state): State<Arc<AppState>>,
-    Path(_id): Path<String>,
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
 ) -> (StatusCode, Json<serde_json::Value>) {
-    // TODO: Implement claim reward
-    (StatusCode::OK, Json(json!({
-        "amount": 0,
-        "claimed": true
-    })))
+    let pool = &state.pool;
+
+    match claim_session(pool, &id).await {
+        Ok(Some((reward, author, split))) => {
+            if let Err(e) = FeedRanker::mark_author_dirty(pool, &author, "reputation").await {
+                tracing::error!("Failed to mark author {} dirty: {}", author, e);
+            }
+            (StatusCode::OK, Json(json!({
+                "amount": reward,
+                "split": {
+                    "creator": split.creator,
+                    "reader": split.reader,
+                    "pool": split.pool
+                },
+                "claimed": true
+            })))
+        }
+        Ok(None) => (StatusCode::CONFLICT, Json(json!({ "error": "session not claimable" }))),
+        Err(e) => {
+            tracing::error!("Failed to claim reward: {}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({ "error": "db error" })))
+        }
+    }
+}
+
+/// Mark the session claimed, split its reward between creator, reader and
+/// pool, and credit the post's author with reputation for the attention, in
+/// one transaction. Returns the reward, the author and the split, or None if
+/// the session isn't claimable.
+async fn claim_session(pool: &sqlx::SqlitePool, id: &str) -> Result<Option<(i64, String, RewardSplit)>, sqlx::Error> {
+    let mut tx = pool.begin().await?;
+
+    let row = sqlx::query(
+        "UPDATE attention_sessions SET claimed = TRUE, claimed_at = CURRENT_TIMESTAMP WHERE id = ? AND validity = 'valid' AND claimed = FALSE RETURNING reward, post_id, reader"
+    )
+    .bind(id)
+    .fetch_optional(&mut *tx)
+    .await?;
+    let Some(row) = row else {
+        return Ok(None);
+    };
+    let reward = row.get::<i64, _>("reward");
+    let post_id = row.get::<String, _>("post_id");
+    let reader = row.get::<String, _>("reader");
+
+    let author: String = sqlx::query_scalar("SELECT author FROM posts WHERE id = ?")
+        .bind(&post_id)
+        .fetch_one(&mut *tx)
+        .await?;
+
+    let split = rules::split_reward(reward);
+    let mut txn = LedgerTxn::new();
+    txn.transfer(ledger::ATTENTION_REWARDS, &ledger::user(&author), split.creator)
+        .transfer(ledger::ATTENTION_REWARDS, &ledger::user(&reader), split.reader)
+        .transfer(ledger::ATTENTION_REWARDS, ledger::ATTENTION_POOL, split.pool);
+    txn.post(&mut tx, &format!("reward:session:{}", id), "attention_reward", None).await?;
+
+    sqlx::query(
+        "INSERT INTO attention_rewards(session_id, post_id, creator, reader, total, creator_amount, reader_amount, pool_amount, claimed_at) VALUES (?, ?, ?, ?, ?, ?, ?, ?, CURRENT_TIMESTAMP)"
+    )
+    .bind(id)
+    .bind(&post_id)
+    .bind(&author)
+    .bind(&reader)
+    .bind(reward)
+    .bind(split.creator)
+    .bind(split.reader)
+    .bind(split.pool)
+    .execute(&mut *tx)
+    .await?;
+
+    for (address, earned) in [(&author, split.creator), (&reader, split.reader)] {
+        sqlx::query("UPDATE profiles SET total_attention_earned = total_attention_earned + ?, updated_at = CURRENT_TIMESTAMP WHERE address = ?")
+            .bind(earned)
+            .bind(address)
+            .execute(&mut *tx)
+            .await?;
+    }
+
+    let gain = reputation::attention_gain(reward);
+    ReputationLedger::apply(&mut tx, &author, gain, Reason::AttentionGain, &format!("session:{}", id)).await?;
+
+    tx.commit().await?;
+    Ok(Some((reward, author, split)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_state;
+
+    async fn attention_events(pool: &SqlitePool) -> i64 {
+        sqlx::query_scalar("SELECT COUNT(*) FROM stream_events WHERE kind = 'attention'")
+            .fetch_one(pool)
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn ending_a_session_twice_conflicts_and_publishes_once() {
+        let state = test_state().await;
+        let pool = &state.pool;
+        let started = now_ms() - 10_000;
+
+        sqlx::query("INSERT INTO profiles(address, reputation) VALUES ('0xauthor', 50), ('0xreader', 50)")
+            .execute(pool)
+            .await
+            .unwrap();
+        sqlx::query("INSERT INTO posts(id, author, content_hash, created_at) VALUES ('0xpost', '0xauthor', 'h', CURRENT_TIMESTAMP)")
+            .execute(pool)
+            .await
+            .unwrap();
+        sqlx::query(
+            "INSERT INTO attention_sessions(id, reader, post_id, duration_ms, reward, status, validity, started_at, last_heartbeat_at) VALUES ('s1', '0xreader', '0xpost', 0, 0, 'active', 'pending', ?, ?)"
+        )
+        .bind(started)
+        .bind(started)
+        .execute(pool)
+        .await
+        .unwrap();
+
+        let end = || end_session(State(state.clone()), Path("s1".to_string()), ApiJson(EndSessionRequest { duration_ms: 10_000 }));
+
+        let (status, Json(body)) = end().await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["validity"], "valid");
+        assert_eq!(attention_events(pool).await, 1);
+
+        let (status, _) = end().await;
+        assert_eq!(status, StatusCode::CONFLICT);
+        assert_eq!(attention_events(pool).await, 1);
+    }
 }
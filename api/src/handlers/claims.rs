@@ -4,42 +4,484 @@ use axum::{
     Json,
 };
 use serde_json::json;
+use sqlx::{Row, Sqlite, SqlitePool, Transaction};
 use std::sync::Arc;
+use suiter_core::{
+    ledger::{self, LedgerTxn},
+    notifications::{self, Kind},
+    search::{self, Doc},
+    stream,
+};
+use suiter_ranker::FeedRanker;
+use uuid::Uuid;
 
-use crate::AppState;
+use crate::{
+    extract::ApiJson,
+    claim_rules::{self as rules, Tally},
+    models::{CreateClaimRequest, VoteRequest},
+    settlement::{self, load_tally},
+    AppState,
+};
 
 pub async fn create_claim(
-    State(_state): State<Arc<AppState>>,
-    Json(payload): Json<serde_json::Value>,
+    State(state): State<Arc<AppState>>,
+    ApiJson(payload): ApiJson<CreateClaimRequest>,
 ) -> (StatusCode, Json<serde_json::Value>) {
-    // TODO: Implement create claim
+    let pool = &state.pool;
+
+    let claim_text = payload.claim_text.trim();
+    if claim_text.is_empty() {
+        return (StatusCode::BAD_REQUEST, Json(json!({ "error": "claim_text required" })));
+    }
+    if payload.stake < rules::MIN_CLAIM_STAKE {
+        return (StatusCode::BAD_REQUEST, Json(json!({
+            "error": "stake below minimum",
+            "min_stake": rules::MIN_CLAIM_STAKE,
+        })));
+    }
+    if payload.stake > rules::MAX_STAKE {
+        return (StatusCode::BAD_REQUEST, Json(json!({
+            "error": "stake above maximum",
+            "max_stake": rules::MAX_STAKE,
+        })));
+    }
+
+    let row = match sqlx::query(
+        "SELECT (SELECT COUNT(*) FROM posts WHERE id = ?1) as post_exists, (SELECT author FROM posts WHERE id = ?1) as author, (SELECT reputation FROM profiles WHERE address = ?2) as reputation"
+    )
+    .bind(&payload.post_id)
+    .bind(&payload.claimer)
+    .fetch_one(pool)
+    .await
+    {
+        Ok(r) => r,
+        Err(e) => {
+            tracing::error!("DB error: {}", e);
+            return (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({ "error": "db error" })));
+        }
+    };
+
+    if row.get::<i64, _>("post_exists") == 0 {
+        return (StatusCode::NOT_FOUND, Json(json!({ "error": "post not found" })));
+    }
+    if row.get::<Option<i64>, _>("reputation").unwrap_or(0) < rules::MIN_REPUTATION_TO_CLAIM {
+        return (StatusCode::FORBIDDEN, Json(json!({
+            "error": "insufficient reputation to claim",
+            "required": rules::MIN_REPUTATION_TO_CLAIM,
+        })));
+    }
+
+    let id = Uuid::new_v4().to_string();
+    let voting_end = match insert_claim(pool, &id, &payload, claim_text).await {
+        Ok(v) => v,
+        Err(StakeError::Db(e)) => {
+            tracing::error!("Failed to insert claim: {}", e);
+            return (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({ "error": "failed to create claim" })));
+        }
+        Err(e) => return e.into_response(),
+    };
+
+    if let Err(e) = FeedRanker::mark_dirty(pool, &payload.post_id, "claim").await {
+        tracing::error!("Failed to mark post {} dirty: {}", payload.post_id, e);
+    }
+    if let Err(e) = search::index(pool, Doc::Claim, &id).await {
+        tracing::error!("Failed to index claim {}: {}", id, e);
+    }
+    state.stream.publish(pool, &stream::post_topic(&payload.post_id), "claim_created", json!({
+        "post_id": payload.post_id,
+        "claim_id": id,
+        "claimer": payload.claimer,
+        "stake": payload.stake,
+    })).await;
+
+    if let Some(author) = row.get::<Option<String>, _>("author").filter(|a| a != payload.claimer.as_str()) {
+        let event = json!({ "post_id": payload.post_id, "claim_id": id, "claimer": payload.claimer });
+        if let Err(e) = notifications::notify(pool, &author, Kind::ClaimCreated, &format!("claim:{}", id), &event).await {
+            tracing::error!("Failed to notify {}: {}", author, e);
+        }
+    }
+
     (StatusCode::CREATED, Json(json!({
-        "claim_id": "0x...",
-        "status": "created"
+        "claim_id": id,
+        "status": "created",
+        "stake": payload.stake,
+        "voting_end": voting_end,
     })))
 }
 
 pub async fn get_claim(
-    State(_state): State<Arc<AppState>>,
-    Path(_id): Path<String>,
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
 ) -> (StatusCode, Json<serde_json::Value>) {
-    // TODO: Implement get claim
+    let pool = &state.pool;
+
+    // Don't wait for the settlement sweep to show a claim whose voting ended
+    if let Err(e) = settlement::resolve_and_settle(pool, &id).await {
+        tracing::error!("Failed to resolve claim {}: {}", id, e);
+    }
+
+    let row = match sqlx::query(
+        "SELECT id, post_id, claimer, claim_text, stake, resolved, accepted, outcome, voting_end, resolved_at, settled_at, created_at FROM truth_claims WHERE id = ?"
+    )
+    .bind(&id)
+    .fetch_optional(pool)
+    .await
+    {
+        Ok(Some(r)) => r,
+        Ok(None) => return (StatusCode::NOT_FOUND, Json(json!({ "error": "claim not found" }))),
+        Err(e) => {
+            tracing::error!("DB error: {}", e);
+            return (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({ "error": "db error" })));
+        }
+    };
+
+    let tally = match load_tally(pool, &id).await {
+        Ok(t) => t,
+        Err(e) => {
+            tracing::error!("DB error: {}", e);
+            return (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({ "error": "db error" })));
+        }
+    };
+
     (StatusCode::OK, Json(json!({
-        "id": "0x...",
-        "votes_yes": 0,
-        "votes_no": 0,
-        "resolved": false
+        "id": row.get::<String, _>("id"),
+        "post_id": row.get::<String, _>("post_id"),
+        "claimer": row.get::<String, _>("claimer"),
+        "claim_text": row.get::<String, _>("claim_text"),
+        "stake": row.get::<i64, _>("stake"),
+        "voting_end": row.get::<String, _>("voting_end"),
+        "created_at": row.get::<String, _>("created_at"),
+        "resolved": row.get::<bool, _>("resolved"),
+        "accepted": row.get::<Option<bool>, _>("accepted"),
+        "outcome": row.get::<Option<String>, _>("outcome"),
+        "resolved_at": row.get::<Option<String>, _>("resolved_at"),
+        "settled_at": row.get::<Option<String>, _>("settled_at"),
+        "votes_yes": tally.yes,
+        "votes_no": tally.no,
+        "tally": tally_json(&tally),
     })))
 }
 
 pub async fn vote(
-    State(_state): State<Arc<AppState>>,
-    Path(_id): Path<String>,
-    Json(payload): Json<serde_json::Value>,
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+    ApiJson(payload): ApiJson<VoteRequest>,
 ) -> (StatusCode, Json<serde_json::Value>) {
-    // TODO: Implement vote
+    let pool = &state.pool;
+
+    let voter = payload.voter.clone();
+    if payload.stake < 0 {
+        return (StatusCode::BAD_REQUEST, Json(json!({ "error": "stake must not be negative" })));
+    }
+    if payload.stake > rules::MAX_STAKE {
+        return (StatusCode::BAD_REQUEST, Json(json!({
+            "error": "stake above maximum",
+            "max_stake": rules::MAX_STAKE,
+        })));
+    }
+
+    let row = match sqlx::query(
+        "SELECT claimer, resolved, voting_end > CURRENT_TIMESTAMP as open, (SELECT reputation FROM profiles WHERE address = ?) as reputation FROM truth_claims WHERE id = ?"
+    )
+    .bind(&voter)
+    .bind(&id)
+    .fetch_optional(pool)
+    .await
+    {
+        Ok(Some(r)) => r,
+        Ok(None) => return (StatusCode::NOT_FOUND, Json(json!({ "error": "claim not found" }))),
+        Err(e) => {
+            tracing::error!("DB error: {}", e);
+            return (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({ "error": "db error" })));
+        }
+    };
+
+    if row.get::<bool, _>("resolved") || !row.get::<bool, _>("open") {
+        return (StatusCode::CONFLICT, Json(json!({ "error": "voting closed" })));
+    }
+    if row.get::<String, _>("claimer") == voter.as_str() {
+        return (StatusCode::FORBIDDEN, Json(json!({ "error": "claimers can't vote on their own claim" })));
+    }
+
+    let reputation = row.get::<Option<i64>, _>("reputation").unwrap_or(0);
+    if reputation < rules::MIN_REPUTATION_TO_VOTE {
+        return (StatusCode::FORBIDDEN, Json(json!({
+            "error": "insufficient reputation to vote",
+            "required": rules::MIN_REPUTATION_TO_VOTE,
+        })));
+    }
+
+    match insert_vote(pool, &id, voter.as_str(), &payload, reputation).await {
+        Ok(()) => {}
+        Err(StakeError::Db(e)) => {
+            tracing::error!("Failed to record vote: {}", e);
+            return (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({ "error": "failed to record vote" })));
+        }
+        Err(e) => return e.into_response(),
+    }
+
+    let tally = match load_tally(pool, &id).await {
+        Ok(t) => t,
+        Err(e) => {
+            tracing::error!("DB error: {}", e);
+            return (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({ "error": "db error" })));
+        }
+    };
+
+    state.stream.publish(pool, &stream::claim_topic(&id), "vote", json!({
+        "claim_id": id,
+        "voter": voter,
+        "vote": payload.vote,
+        "tally": tally_json(&tally),
+    })).await;
+
     (StatusCode::OK, Json(json!({
-        "votes_yes": 0,
-        "votes_no": 0
+        "votes_yes": tally.yes,
+        "votes_no": tally.no,
+        "weight": reputation,
+        "stake": payload.stake,
+        "tally": tally_json(&tally),
     })))
 }
+
+/// Why a claim or vote wasn't recorded
+enum StakeError {
+    AlreadyVoted,
+    /// The wallet and settled balance together hold less than the stake
+    InsufficientFunds { available: i64 },
+    Db(sqlx::Error),
+}
+
+impl StakeError {
+    fn into_response(self) -> (StatusCode, Json<serde_json::Value>) {
+        match self {
+            StakeError::AlreadyVoted => (StatusCode::CONFLICT, Json(json!({ "error": "already voted" }))),
+            StakeError::InsufficientFunds { available } => (StatusCode::PAYMENT_REQUIRED, Json(json!({
+                "error": "insufficient funds for stake",
+                "available": available,
+            }))),
+            StakeError::Db(e) => {
+                tracing::error!("DB error: {}", e);
+                (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({ "error": "db error" })))
+            }
+        }
+    }
+}
+
+impl From<sqlx::Error> for StakeError {
+    fn from(e: sqlx::Error) -> Self {
+        StakeError::Db(e)
+    }
+}
+
+/// Move `amount` from the staker's wallet into the claim's escrow. Nothing
+/// deposits into wallets yet, so whatever the wallet can't cover is first
+/// moved in from the staker's settled balance: payouts and attention
+/// rewards owed to them. The caller's transaction has already written, so
+/// it holds SQLite's write lock and no concurrent stake can spend the same
+/// balance.
+async fn lock_stake(
+    tx: &mut Transaction<'_, Sqlite>,
+    staker: &str,
+    claim_id: &str,
+    amount: i64,
+    txn_id: &str,
+) -> Result<(), StakeError> {
+    let wallet = ledger::wallet(staker);
+    let owed = ledger::user(staker);
+    let in_wallet = ledger::balance(tx, &wallet).await?;
+    let settled = ledger::balance(tx, &owed).await?;
+    if in_wallet + settled < amount {
+        return Err(StakeError::InsufficientFunds { available: in_wallet + settled });
+    }
+
+    let shortfall = amount - in_wallet;
+    if shortfall > 0 {
+        LedgerTxn::new()
+            .transfer(&owed, &wallet, shortfall)
+            .post(tx, &format!("fund:{}", txn_id), "stake_funding", None)
+            .await?;
+    }
+    LedgerTxn::new()
+        .transfer(&wallet, &ledger::claim_escrow(claim_id), amount)
+        .post(tx, txn_id, "stake", Some(claim_id))
+        .await?;
+    Ok(())
+}
+
+/// Insert the claim and lock the claimer's stake in its escrow
+async fn insert_claim(
+    pool: &SqlitePool,
+    id: &str,
+    payload: &CreateClaimRequest,
+    claim_text: &str,
+) -> Result<String, StakeError> {
+    let mut tx = pool.begin().await?;
+
+    let voting_end: String = sqlx::query_scalar(
+        "INSERT INTO truth_claims(id, post_id, claimer, claim_text, votes_yes, votes_no, resolved, stake, voting_end, created_at) VALUES (?, ?, ?, ?, 0, 0, FALSE, ?, datetime(CURRENT_TIMESTAMP, ?), CURRENT_TIMESTAMP) RETURNING voting_end"
+    )
+    .bind(id)
+    .bind(&payload.post_id)
+    .bind(&payload.claimer)
+    .bind(claim_text)
+    .bind(payload.stake)
+    .bind(format!("+{} seconds", rules::VOTING_PERIOD_SECS))
+    .fetch_one(&mut *tx)
+    .await?;
+
+    lock_stake(&mut tx, payload.claimer.as_str(), id, payload.stake, &format!("stake:claim:{}", id)).await?;
+
+    tx.commit().await?;
+    Ok(voting_end)
+}
+
+/// Record the vote with its reputation snapshot and stake
+async fn insert_vote(
+    pool: &SqlitePool,
+    claim_id: &str,
+    voter: &str,
+    payload: &VoteRequest,
+    reputation: i64,
+) -> Result<(), StakeError> {
+    let mut tx = pool.begin().await?;
+
+    // The snapshot fixes the vote's weight; later reputation changes don't move it
+    let inserted = sqlx::query(
+        "INSERT OR IGNORE INTO claim_votes(claim_id, voter, vote, reputation_snapshot, stake, created_at) VALUES (?, ?, ?, ?, ?, CURRENT_TIMESTAMP)"
+    )
+    .bind(claim_id)
+    .bind(voter)
+    .bind(payload.vote)
+    .bind(reputation)
+    .bind(payload.stake)
+    .execute(&mut *tx)
+    .await?;
+    if inserted.rows_affected() == 0 {
+        return Err(StakeError::AlreadyVoted);
+    }
+
+    let column = if payload.vote { "votes_yes" } else { "votes_no" };
+    sqlx::query(&format!("UPDATE truth_claims SET {0} = {0} + 1 WHERE id = ?", column))
+        .bind(claim_id)
+        .execute(&mut *tx)
+        .await?;
+
+    if payload.stake > 0 {
+        lock_stake(&mut tx, voter, claim_id, payload.stake, &format!("stake:vote:{}:{}", claim_id, voter)).await?;
+    }
+
+    tx.commit().await?;
+    Ok(())
+}
+
+fn tally_json(tally: &Tally) -> serde_json::Value {
+    json!({
+        "raw": {
+            "yes": tally.yes,
+            "no": tally.no,
+            "voters": tally.voters(),
+        },
+        "weighted": {
+            "yes": tally.weighted_yes,
+            "no": tally.weighted_no,
+            "total": tally.weight(),
+            "yes_share": tally.yes_share(),
+        },
+        "threshold": rules::CLAIM_THRESHOLD,
+        "quorum": {
+            "min_voters": rules::QUORUM_MIN_VOTERS,
+            "min_weight": rules::QUORUM_MIN_WEIGHT,
+            "met": tally.has_quorum(),
+        },
+        "projected_outcome": tally.outcome().as_str(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_state;
+    use suiter_core::address::SuiAddress;
+
+    const CLAIMER: &str = "0x00000000000000000000000000000000000000000000000000000000000000c1";
+
+    async fn setup(pool: &SqlitePool) {
+        sqlx::query("INSERT INTO profiles(address, reputation) VALUES ('0xauthor', 50), (?, 200)")
+            .bind(CLAIMER)
+            .execute(pool)
+            .await
+            .unwrap();
+        sqlx::query("INSERT INTO posts(id, author, content_hash, created_at) VALUES ('0xpost', '0xauthor', 'h', CURRENT_TIMESTAMP)")
+            .execute(pool)
+            .await
+            .unwrap();
+    }
+
+    async fn credit(pool: &SqlitePool, account: &str, amount: i64) {
+        let mut tx = pool.begin().await.unwrap();
+        LedgerTxn::new()
+            .transfer(ledger::ATTENTION_REWARDS, account, amount)
+            .post(&mut tx, &format!("test:{}", account), "attention_reward", None)
+            .await
+            .unwrap();
+        tx.commit().await.unwrap();
+    }
+
+    async fn balance(pool: &SqlitePool, account: &str) -> i64 {
+        let mut tx = pool.begin().await.unwrap();
+        ledger::balance(&mut tx, account).await.unwrap()
+    }
+
+    fn request(stake: i64) -> CreateClaimRequest {
+        CreateClaimRequest {
+            post_id: "0xpost".to_string(),
+            claimer: SuiAddress::parse(CLAIMER).unwrap(),
+            claim_text: "this is false".to_string(),
+            stake,
+        }
+    }
+
+    #[tokio::test]
+    async fn create_claim_stakes_from_settled_balance() {
+        let state = test_state().await;
+        setup(&state.pool).await;
+        credit(&state.pool, &ledger::user(CLAIMER), rules::MIN_CLAIM_STAKE + 5).await;
+
+        let (status, Json(body)) = create_claim(State(state.clone()), ApiJson(request(rules::MIN_CLAIM_STAKE))).await;
+        assert_eq!(status, StatusCode::CREATED, "{}", body);
+        let claim_id = body["claim_id"].as_str().unwrap();
+
+        assert_eq!(balance(&state.pool, &ledger::claim_escrow(claim_id)).await, rules::MIN_CLAIM_STAKE);
+        assert_eq!(balance(&state.pool, &ledger::wallet(CLAIMER)).await, 0);
+        assert_eq!(balance(&state.pool, &ledger::user(CLAIMER)).await, 5);
+    }
+
+    #[tokio::test]
+    async fn create_claim_spends_the_wallet_before_the_settled_balance() {
+        let state = test_state().await;
+        setup(&state.pool).await;
+        credit(&state.pool, &ledger::wallet(CLAIMER), 40).await;
+        credit(&state.pool, &ledger::user(CLAIMER), rules::MIN_CLAIM_STAKE).await;
+
+        let (status, _) = create_claim(State(state.clone()), ApiJson(request(rules::MIN_CLAIM_STAKE))).await;
+        assert_eq!(status, StatusCode::CREATED);
+        assert_eq!(balance(&state.pool, &ledger::wallet(CLAIMER)).await, 0);
+        assert_eq!(balance(&state.pool, &ledger::user(CLAIMER)).await, 40);
+    }
+
+    #[tokio::test]
+    async fn create_claim_without_funds_is_refused_and_leaves_nothing() {
+        let state = test_state().await;
+        setup(&state.pool).await;
+        credit(&state.pool, &ledger::user(CLAIMER), rules::MIN_CLAIM_STAKE - 1).await;
+
+        let (status, Json(body)) = create_claim(State(state.clone()), ApiJson(request(rules::MIN_CLAIM_STAKE))).await;
+        assert_eq!(status, StatusCode::PAYMENT_REQUIRED);
+        assert_eq!(body["available"], rules::MIN_CLAIM_STAKE - 1);
+
+        let claims: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM truth_claims").fetch_one(&state.pool).await.unwrap();
+        assert_eq!(claims, 0);
+        assert_eq!(balance(&state.pool, &ledger::user(CLAIMER)).await, rules::MIN_CLAIM_STAKE - 1);
+    }
+}
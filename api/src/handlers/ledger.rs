@@ -0,0 +1,251 @@
+use axum::{
+    extract::{Query, State},
+    http::StatusCode,
+    Json,
+};
+use serde_json::json;
+use sqlx::Row;
+use std::sync::Arc;
+use suiter_core::{address::SuiAddress, ledger};
+
+use crate::{
+    extract::ApiPath,
+    models::{EarningsQuery, LedgerQuery},
+    treasury, AppState,
+};
+
+const DEFAULT_PAGE: i64 = 50;
+const MAX_PAGE: i64 = 200;
+const DEFAULT_BUCKETS: i64 = 30;
+const MAX_BUCKETS: i64 = 366;
+
+pub async fn get_balance(
+    State(state): State<Arc<AppState>>,
+    ApiPath(address): ApiPath<SuiAddress>,
+) -> (StatusCode, Json<serde_json::Value>) {
+    let pool = &state.pool;
+
+    // Stakes still sitting in the escrow of claims that haven't settled
+    let row = sqlx::query(
+        r#"
+        SELECT
+            (SELECT COALESCE(SUM(amount), 0) FROM ledger_entries WHERE account = ?1) as available,
+            (SELECT COALESCE(-SUM(amount), 0) FROM ledger_entries WHERE account = ?2 AND entry_type = 'stake') as total_staked,
+            (SELECT COALESCE(-SUM(le.amount), 0) FROM ledger_entries le JOIN truth_claims tc ON tc.id = le.claim_id
+                WHERE le.account = ?2 AND tc.settled_at IS NULL) as locked
+        "#
+    )
+    .bind(ledger::user(address.as_str()))
+    .bind(ledger::wallet(address.as_str()))
+    .fetch_one(pool)
+    .await;
+
+    match row {
+        Ok(r) => (StatusCode::OK, Json(json!({
+            "address": address,
+            "available": r.get::<i64, _>("available"),
+            "locked": r.get::<i64, _>("locked"),
+            "total_staked": r.get::<i64, _>("total_staked"),
+        }))),
+        Err(e) => {
+            tracing::error!("DB error: {}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({ "error": "db error" })))
+        }
+    }
+}
+
+pub async fn get_payouts(
+    State(state): State<Arc<AppState>>,
+    ApiPath(address): ApiPath<SuiAddress>,
+    Query(query): Query<LedgerQuery>,
+) -> (StatusCode, Json<serde_json::Value>) {
+    let pool = &state.pool;
+    let limit = query.limit.unwrap_or(DEFAULT_PAGE).clamp(1, MAX_PAGE);
+
+    let rows = sqlx::query(
+        r#"
+        SELECT le.id, le.txn_id, le.amount, le.entry_type, le.claim_id, le.created_at, tc.outcome,
+            (SELECT COALESCE(-SUM(w.amount), 0) FROM ledger_entries w WHERE w.account = ?2 AND w.claim_id = le.claim_id) as staked
+        FROM ledger_entries le
+        LEFT JOIN truth_claims tc ON tc.id = le.claim_id
+        WHERE le.account = ?1 AND le.id < ?3
+        ORDER BY le.id DESC
+        LIMIT ?4
+        "#
+    )
+    .bind(ledger::user(address.as_str()))
+    .bind(ledger::wallet(address.as_str()))
+    .bind(query.before.unwrap_or(i64::MAX))
+    .bind(limit)
+    .fetch_all(pool)
+    .await;
+
+    match rows {
+        Ok(rows) => {
+            let payouts: Vec<serde_json::Value> = rows
+                .iter()
+                .map(|r| {
+                    let amount = r.get::<i64, _>("amount");
+                    let staked = r.get::<i64, _>("staked");
+                    json!({
+                        "id": r.get::<i64, _>("id"),
+                        "txn_id": r.get::<String, _>("txn_id"),
+                        "entry_type": r.get::<String, _>("entry_type"),
+                        "claim_id": r.get::<Option<String>, _>("claim_id"),
+                        "outcome": r.get::<Option<String>, _>("outcome"),
+                        "amount": amount,
+                        "staked": staked,
+                        "net": amount - staked,
+                        "created_at": r.get::<String, _>("created_at"),
+                    })
+                })
+                .collect();
+            let next_before = rows.last().map(|r| r.get::<i64, _>("id"));
+
+            (StatusCode::OK, Json(json!({
+                "address": address,
+                "payouts": payouts,
+                "next_before": next_before,
+            })))
+        }
+        Err(e) => {
+            tracing::error!("DB error: {}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({ "error": "db error" })))
+        }
+    }
+}
+
+/// Attention earnings split by role (creator of the post read, or reader),
+/// overall and per day, week or month
+pub async fn get_earnings(
+    State(state): State<Arc<AppState>>,
+    ApiPath(address): ApiPath<SuiAddress>,
+    Query(query): Query<EarningsQuery>,
+) -> (StatusCode, Json<serde_json::Value>) {
+    let pool = &state.pool;
+    let period = query.period.unwrap_or_else(|| "day".to_string());
+    let bucket = match period.as_str() {
+        "day" => "%Y-%m-%d",
+        "week" => "%Y-W%W",
+        "month" => "%Y-%m",
+        _ => {
+            return (StatusCode::BAD_REQUEST, Json(json!({ "error": "period must be day, week or month" })));
+        }
+    };
+    let limit = query.limit.unwrap_or(DEFAULT_BUCKETS).clamp(1, MAX_BUCKETS);
+
+    let rows = sqlx::query(
+        r#"
+        WITH earned AS (
+            SELECT claimed_at, creator_amount as creator, 0 as reader FROM attention_rewards WHERE creator = ?1
+            UNION ALL
+            SELECT claimed_at, 0, reader_amount FROM attention_rewards WHERE reader = ?1
+        )
+        SELECT strftime(?2, claimed_at) as bucket,
+            SUM(creator) as creator, SUM(reader) as reader, COUNT(*) as sessions
+        FROM earned
+        GROUP BY bucket
+        ORDER BY bucket DESC
+        LIMIT ?3
+        "#
+    )
+    .bind(&address)
+    .bind(bucket)
+    .bind(limit)
+    .fetch_all(pool)
+    .await;
+
+    let totals = sqlx::query(
+        r#"
+        SELECT
+            (SELECT COALESCE(SUM(creator_amount), 0) FROM attention_rewards WHERE creator = ?1) as creator,
+            (SELECT COALESCE(SUM(reader_amount), 0) FROM attention_rewards WHERE reader = ?1) as reader
+        "#
+    )
+    .bind(&address)
+    .fetch_one(pool)
+    .await;
+
+    match (rows, totals) {
+        (Ok(rows), Ok(totals)) => {
+            let periods: Vec<serde_json::Value> = rows
+                .iter()
+                .map(|r| {
+                    let creator = r.get::<i64, _>("creator");
+                    let reader = r.get::<i64, _>("reader");
+                    json!({
+                        "period": r.get::<String, _>("bucket"),
+                        "creator": creator,
+                        "reader": reader,
+                        "total": creator + reader,
+                        "sessions": r.get::<i64, _>("sessions"),
+                    })
+                })
+                .collect();
+            let creator = totals.get::<i64, _>("creator");
+            let reader = totals.get::<i64, _>("reader");
+
+            (StatusCode::OK, Json(json!({
+                "address": address,
+                "total": {
+                    "creator": creator,
+                    "reader": reader,
+                    "total": creator + reader,
+                },
+                "period": period,
+                "periods": periods,
+            })))
+        }
+        (Err(e), _) | (_, Err(e)) => {
+            tracing::error!("DB error: {}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({ "error": "db error" })))
+        }
+    }
+}
+
+/// Attention pool balance, burn rate and projected runway
+pub async fn get_attention_pool(
+    State(state): State<Arc<AppState>>,
+) -> (StatusCode, Json<serde_json::Value>) {
+    let pool = &state.pool;
+
+    let status = match treasury::load(pool).await {
+        Ok(s) => s,
+        Err(e) => {
+            tracing::error!("DB error: {}", e);
+            return (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({ "error": "db error" })));
+        }
+    };
+
+    // Latest on-chain view, if the indexer is tracking the pool
+    let chain = sqlx::query("SELECT pool_id, balance, total_rewarded, session_count, observed_at FROM attention_pool_snapshots ORDER BY id DESC LIMIT 1")
+        .fetch_optional(pool)
+        .await;
+    let chain = match chain {
+        Ok(row) => row.map(|r| json!({
+            "pool_id": r.get::<String, _>("pool_id"),
+            "balance": r.get::<i64, _>("balance"),
+            "total_rewarded": r.get::<i64, _>("total_rewarded"),
+            "session_count": r.get::<i64, _>("session_count"),
+            "observed_at": r.get::<String, _>("observed_at"),
+            "drift": treasury::drift(&status, r.get::<i64, _>("balance")),
+        })),
+        Err(e) => {
+            tracing::error!("DB error: {}", e);
+            return (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({ "error": "db error" })));
+        }
+    };
+
+    (StatusCode::OK, Json(json!({
+        "balance": status.balance,
+        "outstanding": status.outstanding,
+        "available": status.available(),
+        "burn_per_day": status.burn_per_day,
+        "runway_days": status.runway_days(),
+        "throttling": {
+            "enabled": state.reward_throttling,
+            "factor_bp": status.throttle_factor(),
+        },
+        "chain": chain,
+    })))
+}
@@ -0,0 +1,668 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Json,
+};
+use chrono::{Duration, Utc};
+use serde_json::json;
+use sqlx::{sqlite::SqliteRow, Row, SqlitePool};
+use std::collections::HashMap;
+use std::sync::Arc;
+use suiter_core::address::SuiAddress;
+use uuid::Uuid;
+
+use crate::{
+    extract::{ApiJson, ApiPath, ApiQuery},
+    lifeline_rules::{self as rules, SupportRejection},
+    models::{CreateLifelineRequest, DailyQuery, LifelineQuery, SupportRequest},
+    AppState,
+};
+
+const DEFAULT_DAYS: i64 = 30;
+const REFUND_COLUMNS: &str = "lifeline_id, supporter, recipient, amount, supports, status, created_at";
+const MAX_DAYS: i64 = 365;
+const TOP_SUPPORTERS: i64 = 10;
+
+/// What supporter `?1` sent today: paid supports plus those still pending,
+/// which hold their share of the daily limit until they're matched
+const SENT_TODAY: &str = r#"(
+    (SELECT COALESCE(SUM(amount), 0) FROM lifeline_supports WHERE supporter = ?1 AND support_day = date('now'))
+    + (SELECT COALESCE(SUM(amount), 0) FROM pending_supports WHERE supporter = ?1 AND support_day = date('now') AND status = 'pending')
+)"#;
+
+/// The next deadline is the cadence after the later of creation and the
+/// creator's last post since then; LifelineMonitor enforces the same rule
+const LIFELINE_COLUMNS: &str = r#"
+    id, recipient, total_received, supporter_count, active, last_support_at, created_at,
+    cadence_days, deactivated_at, deactivation_reason,
+    CASE WHEN active THEN datetime(
+        COALESCE((SELECT MAX(p.created_at) FROM posts p WHERE p.author = creator_lifelines.recipient AND p.created_at >= creator_lifelines.created_at), created_at),
+        '+' || cadence_days || ' days'
+    ) END as next_deadline
+"#;
+
+pub async fn list_lifelines(
+    State(state): State<Arc<AppState>>,
+    ApiQuery(query): ApiQuery<LifelineQuery>,
+) -> (StatusCode, Json<serde_json::Value>) {
+    let rows = sqlx::query(&format!(
+        "SELECT {} FROM creator_lifelines WHERE (?1 IS NULL OR recipient = ?1) AND (?2 IS NULL OR active = ?2) ORDER BY total_received DESC LIMIT 100",
+        LIFELINE_COLUMNS
+    ))
+    .bind(&query.recipient)
+    .bind(query.active)
+    .fetch_all(&state.pool)
+    .await;
+
+    match rows {
+        Ok(rows) => (StatusCode::OK, Json(json!(rows.iter().map(lifeline_json).collect::<Vec<_>>()))),
+        Err(e) => {
+            tracing::error!("DB error: {}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({ "error": "db error" })))
+        }
+    }
+}
+
+pub async fn create_lifeline(
+    State(state): State<Arc<AppState>>,
+    ApiJson(payload): ApiJson<CreateLifelineRequest>,
+) -> (StatusCode, Json<serde_json::Value>) {
+    let pool = &state.pool;
+
+    let row = match sqlx::query(
+        "SELECT (SELECT reputation FROM profiles WHERE address = ?1) as reputation, (SELECT id FROM creator_lifelines WHERE recipient = ?1 AND active = TRUE) as existing"
+    )
+    .bind(&payload.recipient)
+    .fetch_one(pool)
+    .await
+    {
+        Ok(r) => r,
+        Err(e) => {
+            tracing::error!("DB error: {}", e);
+            return (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({ "error": "db error" })));
+        }
+    };
+
+    if row.get::<Option<i64>, _>("reputation").unwrap_or(0) < rules::LIFELINE_THRESHOLD {
+        return (StatusCode::FORBIDDEN, Json(json!({
+            "error": "insufficient reputation for a lifeline",
+            "required": rules::LIFELINE_THRESHOLD,
+        })));
+    }
+    let cadence_days = payload.cadence_days.unwrap_or(rules::DEFAULT_CADENCE_DAYS);
+    if !(rules::MIN_CADENCE_DAYS..=rules::MAX_CADENCE_DAYS).contains(&cadence_days) {
+        return (StatusCode::BAD_REQUEST, Json(json!({
+            "error": "cadence_days out of range",
+            "min": rules::MIN_CADENCE_DAYS,
+            "max": rules::MAX_CADENCE_DAYS,
+        })));
+    }
+    if let Some(existing) = row.get::<Option<String>, _>("existing") {
+        return (StatusCode::CONFLICT, Json(json!({
+            "error": "recipient already has an active lifeline",
+            "lifeline_id": existing,
+        })));
+    }
+
+    let id = Uuid::new_v4().to_string();
+    if let Err(e) = sqlx::query(
+        "INSERT INTO creator_lifelines(id, recipient, total_received, supporter_count, active, cadence_days, created_at) VALUES (?, ?, 0, 0, TRUE, ?, CURRENT_TIMESTAMP)"
+    )
+    .bind(&id)
+    .bind(&payload.recipient)
+    .bind(cadence_days)
+    .execute(pool)
+    .await
+    {
+        tracing::error!("Failed to insert lifeline: {}", e);
+        return (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({ "error": "failed to create lifeline" })));
+    }
+
+    (StatusCode::CREATED, Json(json!({ "id": id, "status": "created", "cadence_days": cadence_days })))
+}
+
+pub async fn get_lifeline(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+) -> (StatusCode, Json<serde_json::Value>) {
+    let pool = &state.pool;
+
+    let row = match load_lifeline(pool, &id).await {
+        Ok(Some(r)) => r,
+        Ok(None) => return (StatusCode::NOT_FOUND, Json(json!({ "error": "lifeline not found" }))),
+        Err(e) => {
+            tracing::error!("DB error: {}", e);
+            return (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({ "error": "db error" })));
+        }
+    };
+
+    let today: Result<i64, sqlx::Error> = sqlx::query_scalar(
+        "SELECT COALESCE(SUM(amount), 0) FROM lifeline_supports WHERE lifeline_id = ? AND support_day = date('now')"
+    )
+    .bind(&id)
+    .fetch_one(pool)
+    .await;
+
+    let missed = sqlx::query("SELECT deadline, last_post_at, detected_at FROM lifeline_missed_deadlines WHERE lifeline_id = ? ORDER BY deadline")
+        .bind(&id)
+        .fetch_all(pool)
+        .await;
+
+    match (today, missed) {
+        (Ok(today), Ok(missed)) => {
+            let mut lifeline = lifeline_json(&row);
+            lifeline["received_today"] = json!(today);
+            lifeline["missed_deadlines"] = json!(missed
+                .iter()
+                .map(|r| json!({
+                    "deadline": r.get::<String, _>("deadline"),
+                    "last_post_at": r.get::<Option<String>, _>("last_post_at"),
+                    "detected_at": r.get::<String, _>("detected_at"),
+                }))
+                .collect::<Vec<_>>());
+            (StatusCode::OK, Json(lifeline))
+        }
+        (Err(e), _) | (_, Err(e)) => {
+            tracing::error!("DB error: {}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({ "error": "db error" })))
+        }
+    }
+}
+
+pub async fn get_supporters(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+) -> (StatusCode, Json<serde_json::Value>) {
+    let rows = sqlx::query(
+        "SELECT supporter, SUM(amount) as total, COUNT(*) as supports, MAX(created_at) as last_support_at FROM lifeline_supports WHERE lifeline_id = ? GROUP BY supporter ORDER BY total DESC"
+    )
+    .bind(&id)
+    .fetch_all(&state.pool)
+    .await;
+
+    match rows {
+        Ok(rows) => (StatusCode::OK, Json(json!({
+            "lifeline_id": id,
+            "supporters": rows.iter().map(supporter_json).collect::<Vec<_>>(),
+        }))),
+        Err(e) => {
+            tracing::error!("DB error: {}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({ "error": "db error" })))
+        }
+    }
+}
+
+pub async fn get_daily_totals(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+    Query(query): Query<DailyQuery>,
+) -> (StatusCode, Json<serde_json::Value>) {
+    let days = query.days.unwrap_or(DEFAULT_DAYS).clamp(1, MAX_DAYS);
+
+    match daily_totals(&state.pool, "lifeline_id", &id, days).await {
+        Ok(series) => (StatusCode::OK, Json(json!({
+            "lifeline_id": id,
+            "days": series,
+        }))),
+        Err(e) => {
+            tracing::error!("DB error: {}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({ "error": "db error" })))
+        }
+    }
+}
+
+/// `POST /api/lifelines/:id/support`
+/// Records the intent to support; no funds move here. The support stays
+/// pending until the supporter's own `creator_lifeline::send_support`
+/// transaction is indexed, and only then counts towards the lifeline.
+pub async fn send_support(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+    ApiJson(payload): ApiJson<SupportRequest>,
+) -> (StatusCode, Json<serde_json::Value>) {
+    let pool = &state.pool;
+
+    let supporter = payload.supporter;
+
+    let row = match sqlx::query(
+        &format!("SELECT recipient, active, {} as sent_today FROM creator_lifelines WHERE id = ?2", SENT_TODAY)
+    )
+    .bind(&supporter)
+    .bind(&id)
+    .fetch_optional(pool)
+    .await
+    {
+        Ok(Some(r)) => r,
+        Ok(None) => return (StatusCode::NOT_FOUND, Json(json!({ "error": "lifeline not found" }))),
+        Err(e) => {
+            tracing::error!("DB error: {}", e);
+            return (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({ "error": "db error" })));
+        }
+    };
+
+    if !row.get::<bool, _>("active") {
+        return (StatusCode::CONFLICT, Json(json!({ "error": "lifeline not active" })));
+    }
+
+    let recipient = row.get::<String, _>("recipient");
+    let sent_today = row.get::<i64, _>("sent_today");
+    if let Err(r) = rules::check_support(supporter.as_str(), &recipient, payload.amount, sent_today) {
+        return support_rejected(r, sent_today);
+    }
+
+    let pending_id = Uuid::new_v4().to_string();
+    match insert_pending_support(pool, &pending_id, &id, supporter.as_str(), &recipient, payload.amount).await {
+        Ok(true) => {}
+        // Another request from the same supporter used up today's allowance first
+        Ok(false) => return support_rejected(SupportRejection::DailyLimitExceeded, sent_today),
+        Err(e) => {
+            tracing::error!("Failed to record support: {}", e);
+            return (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({ "error": "failed to record support" })));
+        }
+    }
+
+    (StatusCode::ACCEPTED, Json(json!({
+        "pending_id": pending_id,
+        "status": "pending",
+        "lifeline_id": id,
+        "amount": payload.amount,
+        "remaining_today": rules::remaining_today(sent_today + payload.amount),
+    })))
+}
+
+/// `GET /api/lifelines/:id/support/:pending_id`
+/// Whether a support sent through the API has been paid on chain yet
+pub async fn get_pending_support(
+    State(state): State<Arc<AppState>>,
+    Path((id, pending_id)): Path<(String, String)>,
+) -> (StatusCode, Json<serde_json::Value>) {
+    let row = sqlx::query("SELECT id, lifeline_id, supporter, amount, status, support_id, created_at, confirmed_at FROM pending_supports WHERE id = ? AND lifeline_id = ?")
+        .bind(&pending_id)
+        .bind(&id)
+        .fetch_optional(&state.pool)
+        .await;
+
+    match row {
+        Ok(Some(r)) => (StatusCode::OK, Json(json!({
+            "pending_id": r.get::<String, _>("id"),
+            "lifeline_id": r.get::<String, _>("lifeline_id"),
+            "supporter": r.get::<String, _>("supporter"),
+            "amount": r.get::<i64, _>("amount"),
+            "status": r.get::<String, _>("status"),
+            "support_id": r.get::<Option<String>, _>("support_id"),
+            "created_at": r.get::<String, _>("created_at"),
+            "confirmed_at": r.get::<Option<String>, _>("confirmed_at"),
+        }))),
+        Ok(None) => (StatusCode::NOT_FOUND, Json(json!({ "error": "support not found" }))),
+        Err(e) => {
+            tracing::error!("DB error: {}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({ "error": "db error" })))
+        }
+    }
+}
+
+pub async fn get_lifeline_refunds(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+) -> (StatusCode, Json<serde_json::Value>) {
+    let rows = sqlx::query(&format!("SELECT {} FROM lifeline_refunds WHERE lifeline_id = ? ORDER BY amount DESC", REFUND_COLUMNS))
+        .bind(&id)
+        .fetch_all(&state.pool)
+        .await;
+
+    match rows {
+        Ok(rows) => (StatusCode::OK, Json(json!({
+            "lifeline_id": id,
+            "total_owed": rows.iter().filter(|r| r.get::<String, _>("status") == "owed").map(|r| r.get::<i64, _>("amount")).sum::<i64>(),
+            "refunds": rows.iter().map(refund_json).collect::<Vec<_>>(),
+        }))),
+        Err(e) => {
+            tracing::error!("DB error: {}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({ "error": "db error" })))
+        }
+    }
+}
+
+/// Refunds a supporter is entitled to across all lifelines
+pub async fn get_supporter_refunds(
+    State(state): State<Arc<AppState>>,
+    ApiPath(address): ApiPath<SuiAddress>,
+) -> (StatusCode, Json<serde_json::Value>) {
+    let rows = sqlx::query(&format!("SELECT {} FROM lifeline_refunds WHERE supporter = ? ORDER BY created_at DESC", REFUND_COLUMNS))
+        .bind(&address)
+        .fetch_all(&state.pool)
+        .await;
+
+    match rows {
+        Ok(rows) => (StatusCode::OK, Json(json!({
+            "address": address,
+            "total_owed": rows.iter().filter(|r| r.get::<String, _>("status") == "owed").map(|r| r.get::<i64, _>("amount")).sum::<i64>(),
+            "refunds": rows.iter().map(refund_json).collect::<Vec<_>>(),
+        }))),
+        Err(e) => {
+            tracing::error!("DB error: {}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({ "error": "db error" })))
+        }
+    }
+}
+
+pub async fn get_dashboard(
+    State(state): State<Arc<AppState>>,
+    ApiPath(address): ApiPath<SuiAddress>,
+    Query(query): Query<DailyQuery>,
+) -> (StatusCode, Json<serde_json::Value>) {
+    let days = query.days.unwrap_or(DEFAULT_DAYS).clamp(1, MAX_DAYS);
+
+    match load_dashboard(&state.pool, address.as_str(), days).await {
+        Ok(dashboard) => (StatusCode::OK, Json(dashboard)),
+        Err(e) => {
+            tracing::error!("DB error: {}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({ "error": "db error" })))
+        }
+    }
+}
+
+async fn load_dashboard(pool: &SqlitePool, address: &str, days: i64) -> Result<serde_json::Value, sqlx::Error> {
+    let lifelines = sqlx::query(&format!(
+        "SELECT {} FROM creator_lifelines WHERE recipient = ? ORDER BY created_at DESC",
+        LIFELINE_COLUMNS
+    ))
+    .bind(address)
+    .fetch_all(pool)
+    .await?;
+
+    let totals = sqlx::query(
+        r#"
+        SELECT
+            COALESCE(SUM(amount), 0) as total_received,
+            COUNT(*) as supports,
+            COUNT(DISTINCT supporter) as supporters,
+            COALESCE(SUM(CASE WHEN support_day = date('now') THEN amount ELSE 0 END), 0) as today,
+            COALESCE(SUM(CASE WHEN support_day > date('now', '-7 days') THEN amount ELSE 0 END), 0) as last_7_days,
+            COALESCE(SUM(CASE WHEN support_day > date('now', '-30 days') THEN amount ELSE 0 END), 0) as last_30_days
+        FROM lifeline_supports WHERE recipient = ?
+        "#
+    )
+    .bind(address)
+    .fetch_one(pool)
+    .await?;
+
+    let top = sqlx::query(
+        "SELECT supporter, SUM(amount) as total, COUNT(*) as supports, MAX(created_at) as last_support_at FROM lifeline_supports WHERE recipient = ? GROUP BY supporter ORDER BY total DESC LIMIT ?"
+    )
+    .bind(address)
+    .bind(TOP_SUPPORTERS)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(json!({
+        "address": address,
+        "lifelines": lifelines.iter().map(lifeline_json).collect::<Vec<_>>(),
+        "totals": {
+            "total_received": totals.get::<i64, _>("total_received"),
+            "supports": totals.get::<i64, _>("supports"),
+            "supporters": totals.get::<i64, _>("supporters"),
+            "today": totals.get::<i64, _>("today"),
+            "last_7_days": totals.get::<i64, _>("last_7_days"),
+            "last_30_days": totals.get::<i64, _>("last_30_days"),
+        },
+        "top_supporters": top.iter().map(supporter_json).collect::<Vec<_>>(),
+        "daily": daily_totals(pool, "recipient", address, days).await?,
+    }))
+}
+
+async fn load_lifeline(pool: &SqlitePool, id: &str) -> Result<Option<SqliteRow>, sqlx::Error> {
+    sqlx::query(&format!("SELECT {} FROM creator_lifelines WHERE id = ?", LIFELINE_COLUMNS))
+        .bind(id)
+        .fetch_optional(pool)
+        .await
+}
+
+/// Hold a support until its chain payment is indexed. The daily limit is
+/// re-checked in the insert itself so concurrent requests can't both squeeze
+/// under it. Returns false if the limit was hit.
+async fn insert_pending_support(
+    pool: &SqlitePool,
+    pending_id: &str,
+    lifeline_id: &str,
+    supporter: &str,
+    recipient: &str,
+    amount: i64,
+) -> Result<bool, sqlx::Error> {
+    let mut tx = pool.begin().await?;
+
+    sqlx::query("INSERT OR IGNORE INTO profiles(address, reputation, total_posts, total_attention_earned, joined_at, updated_at) VALUES (?, 50, 0, 0, CURRENT_TIMESTAMP, CURRENT_TIMESTAMP)")
+        .bind(supporter)
+        .execute(&mut *tx)
+        .await?;
+
+    let inserted = sqlx::query(&format!(
+        r#"
+        INSERT INTO pending_supports(id, lifeline_id, supporter, recipient, amount, support_day, status, created_at)
+        SELECT ?2, ?3, ?1, ?4, ?5, date('now'), 'pending', CURRENT_TIMESTAMP
+        WHERE {} + ?5 <= ?6
+        "#,
+        SENT_TODAY
+    ))
+    .bind(supporter)
+    .bind(pending_id)
+    .bind(lifeline_id)
+    .bind(recipient)
+    .bind(amount)
+    .bind(rules::MAX_DAILY_SUPPORT)
+    .execute(&mut *tx)
+    .await?;
+    if inserted.rows_affected() == 0 {
+        return Ok(false);
+    }
+
+    tx.commit().await?;
+    Ok(true)
+}
+
+/// Per-day support totals for the last `days` UTC days, zero-filled
+async fn daily_totals(
+    pool: &SqlitePool,
+    column: &str,
+    value: &str,
+    days: i64,
+) -> Result<Vec<serde_json::Value>, sqlx::Error> {
+    let rows = sqlx::query(&format!(
+        "SELECT support_day, SUM(amount) as total, COUNT(*) as supports FROM lifeline_supports WHERE {} = ? AND support_day > date('now', ?) GROUP BY support_day",
+        column
+    ))
+    .bind(value)
+    .bind(format!("-{} days", days))
+    .fetch_all(pool)
+    .await?;
+
+    let by_day: HashMap<String, (i64, i64)> = rows
+        .iter()
+        .map(|r| (r.get("support_day"), (r.get("total"), r.get("supports"))))
+        .collect();
+
+    let today = Utc::now().date_naive();
+    Ok((0..days)
+        .rev()
+        .map(|back| {
+            let day = (today - Duration::days(back)).format("%Y-%m-%d").to_string();
+            let (total, supports) = by_day.get(&day).copied().unwrap_or((0, 0));
+            json!({ "day": day, "total": total, "supports": supports })
+        })
+        .collect())
+}
+
+fn support_rejected(reason: SupportRejection, sent_today: i64) -> (StatusCode, Json<serde_json::Value>) {
+    let status = match reason {
+        SupportRejection::DailyLimitExceeded => StatusCode::TOO_MANY_REQUESTS,
+        SupportRejection::BelowMinimum | SupportRejection::SelfSupport => StatusCode::BAD_REQUEST,
+    };
+
+    (status, Json(json!({
+        "error": reason.as_str(),
+        "min_amount": rules::MIN_SUPPORT_AMOUNT,
+        "remaining_today": rules::remaining_today(sent_today),
+    })))
+}
+
+fn lifeline_json(r: &SqliteRow) -> serde_json::Value {
+    json!({
+        "id": r.get::<String, _>("id"),
+        "recipient": r.get::<String, _>("recipient"),
+        "total_received": r.get::<i64, _>("total_received"),
+        "supporter_count": r.get::<i64, _>("supporter_count"),
+        "active": r.get::<bool, _>("active"),
+        "last_support_at": r.get::<Option<String>, _>("last_support_at"),
+        "created_at": r.get::<String, _>("created_at"),
+        "cadence_days": r.get::<i64, _>("cadence_days"),
+        "next_deadline": r.get::<Option<String>, _>("next_deadline"),
+        "deactivated_at": r.get::<Option<String>, _>("deactivated_at"),
+        "deactivation_reason": r.get::<Option<String>, _>("deactivation_reason"),
+    })
+}
+
+fn refund_json(r: &SqliteRow) -> serde_json::Value {
+    json!({
+        "lifeline_id": r.get::<String, _>("lifeline_id"),
+        "supporter": r.get::<String, _>("supporter"),
+        "recipient": r.get::<String, _>("recipient"),
+        "amount": r.get::<i64, _>("amount"),
+        "supports": r.get::<i64, _>("supports"),
+        "status": r.get::<String, _>("status"),
+        "created_at": r.get::<String, _>("created_at"),
+    })
+}
+
+fn supporter_json(r: &SqliteRow) -> serde_json::Value {
+    json!({
+        "supporter": r.get::<String, _>("supporter"),
+        "total": r.get::<i64, _>("total"),
+        "supports": r.get::<i64, _>("supports"),
+        "last_support_at": r.get::<String, _>("last_support_at"),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_state;
+
+    const SUPPORTER: &str = "0x00000000000000000000000000000000000000000000000000000000000000a1";
+    const CREATOR: &str = "0x00000000000000000000000000000000000000000000000000000000000000c1";
+
+    async fn setup(pool: &SqlitePool) {
+        sqlx::query("INSERT INTO profiles(address, reputation) VALUES (?, 100)")
+            .bind(CREATOR)
+            .execute(pool)
+            .await
+            .unwrap();
+        sqlx::query("INSERT INTO creator_lifelines(id, recipient, active) VALUES ('0xlife', ?1, TRUE), ('0xended', ?1, FALSE)")
+            .bind(CREATOR)
+            .execute(pool)
+            .await
+            .unwrap();
+    }
+
+    async fn support(state: &Arc<AppState>, lifeline: &str, supporter: &str, amount: i64) -> (StatusCode, serde_json::Value) {
+        let request = SupportRequest { supporter: SuiAddress::parse(supporter).unwrap(), amount };
+        let (status, Json(body)) = send_support(State(state.clone()), Path(lifeline.to_string()), ApiJson(request)).await;
+        (status, body)
+    }
+
+    /// What the indexer does when the supporter's chain payment lands
+    async fn confirm(pool: &SqlitePool, pending_id: &str) {
+        let (lifeline, amount): (String, i64) = sqlx::query_as("SELECT lifeline_id, amount FROM pending_supports WHERE id = ?")
+            .bind(pending_id)
+            .fetch_one(pool)
+            .await
+            .unwrap();
+        sqlx::query("INSERT INTO lifeline_supports(id, lifeline_id, supporter, recipient, amount, support_day, source) VALUES ('0xdigest:0', ?, ?, ?, ?, date('now'), 'chain')")
+            .bind(&lifeline)
+            .bind(SUPPORTER)
+            .bind(CREATOR)
+            .bind(amount)
+            .execute(pool)
+            .await
+            .unwrap();
+        sqlx::query("UPDATE pending_supports SET status = 'confirmed', support_id = '0xdigest:0', confirmed_at = CURRENT_TIMESTAMP WHERE id = ?")
+            .bind(pending_id)
+            .execute(pool)
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn support_stays_pending_until_paid_and_counts_once_against_the_daily_limit() {
+        let state = test_state().await;
+        let pool = &state.pool;
+        setup(pool).await;
+
+        let first = 40_000_000_000;
+        let (status, body) = support(&state, "0xlife", SUPPORTER, first).await;
+        assert_eq!(status, StatusCode::ACCEPTED);
+        assert_eq!(body["status"], "pending");
+        assert_eq!(body["remaining_today"], rules::MAX_DAILY_SUPPORT - first);
+        let pending_id = body["pending_id"].as_str().unwrap().to_string();
+
+        let lookup = || get_pending_support(State(state.clone()), Path(("0xlife".to_string(), pending_id.clone())));
+        let (status, Json(body)) = lookup().await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["status"], "pending");
+        assert_eq!(body["support_id"], serde_json::Value::Null);
+        // Nothing reaches the lifeline until the chain pays
+        let supports: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM lifeline_supports").fetch_one(pool).await.unwrap();
+        assert_eq!(supports, 0);
+
+        confirm(pool, &pending_id).await;
+        let (_, Json(body)) = lookup().await;
+        assert_eq!(body["status"], "confirmed");
+        assert_eq!(body["support_id"], "0xdigest:0");
+
+        // The confirmed support holds its share once, not as pending and paid
+        let rest = rules::MAX_DAILY_SUPPORT - first;
+        let (status, body) = support(&state, "0xlife", SUPPORTER, rest).await;
+        assert_eq!(status, StatusCode::ACCEPTED);
+        assert_eq!(body["remaining_today"], 0);
+
+        let (status, body) = support(&state, "0xlife", SUPPORTER, rules::MIN_SUPPORT_AMOUNT).await;
+        assert_eq!(status, StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(body["error"], "daily_limit_exceeded");
+        assert_eq!(body["remaining_today"], 0);
+    }
+
+    #[tokio::test]
+    async fn invalid_supports_are_refused() {
+        let state = test_state().await;
+        setup(&state.pool).await;
+
+        let (status, body) = support(&state, "0xlife", CREATOR, rules::MIN_SUPPORT_AMOUNT).await;
+        assert_eq!((status, body["error"].as_str()), (StatusCode::BAD_REQUEST, Some("self_support")));
+        let (status, body) = support(&state, "0xlife", SUPPORTER, rules::MIN_SUPPORT_AMOUNT - 1).await;
+        assert_eq!((status, body["error"].as_str()), (StatusCode::BAD_REQUEST, Some("below_min_support")));
+        let (status, _) = support(&state, "0xended", SUPPORTER, rules::MIN_SUPPORT_AMOUNT).await;
+        assert_eq!(status, StatusCode::CONFLICT);
+        let (status, _) = support(&state, "0xmissing", SUPPORTER, rules::MIN_SUPPORT_AMOUNT).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+
+        let pending: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM pending_supports").fetch_one(&state.pool).await.unwrap();
+        assert_eq!(pending, 0);
+    }
+
+    #[tokio::test]
+    async fn racing_supports_cannot_both_fit_under_the_daily_limit() {
+        let state = test_state().await;
+        let pool = &state.pool;
+        setup(pool).await;
+
+        // Both requests passed check_support against the same sent_today of 0;
+        // the insert re-checks the limit, so only the first is held
+        let amount = rules::MAX_DAILY_SUPPORT / 2 + 1;
+        assert!(insert_pending_support(pool, "p1", "0xlife", SUPPORTER, CREATOR, amount).await.unwrap());
+        assert!(!insert_pending_support(pool, "p2", "0xlife", SUPPORTER, CREATOR, amount).await.unwrap());
+        // What still fits is accepted
+        assert!(insert_pending_support(pool, "p3", "0xlife", SUPPORTER, CREATOR, rules::MAX_DAILY_SUPPORT - amount).await.unwrap());
+
+        let held: Vec<String> = sqlx::query_scalar("SELECT id FROM pending_supports ORDER BY id").fetch_all(pool).await.unwrap();
+        assert_eq!(held, ["p1", "p3"]);
+    }
+}
@@ -2,4 +2,14 @@ pub mod posts;
 pub mod profiles;
 pub mod attention;
 pub mod claims;
+pub mod ledger;
+pub mod lifelines;
 pub mod debug;
+pub mod tx;
+pub mod sponsor;
+pub mod submit;
+pub mod stream;
+pub mod notifications;
+pub mod webhooks;
+pub mod search;
+pub mod tags;
@@ -0,0 +1,332 @@
+use axum::{extract::State, http::StatusCode, Json};
+use serde_json::json;
+use sqlx::{Row, SqlitePool};
+use std::sync::Arc;
+use suiter_core::{address::SuiAddress, notifications::Kind, webhooks};
+
+use super::webhooks::new_secret;
+use crate::{
+    extract::{ApiJson, ApiQuery},
+    models::{AddressQuery, MarkReadRequest, NotificationsQuery, UpdatePreferencesRequest},
+    AppState,
+};
+
+const DEFAULT_PAGE: i64 = 50;
+const MAX_PAGE: i64 = 200;
+/// Ids one mark-read request may name
+const MAX_MARK_IDS: usize = 200;
+const MAX_URL_LEN: usize = 2048;
+
+/// `GET /api/notifications?address=&unread=&before=&limit=`
+/// Newest first; page back by passing `next_before` as `before`.
+pub async fn list_notifications(
+    State(state): State<Arc<AppState>>,
+    ApiQuery(query): ApiQuery<NotificationsQuery>,
+) -> (StatusCode, Json<serde_json::Value>) {
+    let pool = &state.pool;
+    let limit = query.limit.unwrap_or(DEFAULT_PAGE).clamp(1, MAX_PAGE);
+
+    let rows = sqlx::query(
+        r#"
+        SELECT id, kind, source_ref, payload, read_at, created_at FROM notifications
+        WHERE recipient = ?1 AND in_app AND id < ?2 AND (NOT ?3 OR read_at IS NULL)
+        ORDER BY id DESC
+        LIMIT ?4
+        "#
+    )
+    .bind(&query.address)
+    .bind(query.before.unwrap_or(i64::MAX))
+    .bind(query.unread)
+    .bind(limit)
+    .fetch_all(pool)
+    .await;
+
+    let rows = match rows {
+        Ok(r) => r,
+        Err(e) => {
+            tracing::error!("DB error: {}", e);
+            return (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({ "error": "db error" })));
+        }
+    };
+    let unread = match unread_counts(pool, &query.address).await {
+        Ok(u) => u,
+        Err(e) => {
+            tracing::error!("DB error: {}", e);
+            return (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({ "error": "db error" })));
+        }
+    };
+
+    let notifications: Vec<serde_json::Value> = rows
+        .iter()
+        .map(|r| json!({
+            "id": r.get::<i64, _>("id"),
+            "kind": r.get::<String, _>("kind"),
+            "source_ref": r.get::<String, _>("source_ref"),
+            "payload": serde_json::from_str::<serde_json::Value>(&r.get::<String, _>("payload")).unwrap_or(serde_json::Value::Null),
+            "read": r.get::<Option<String>, _>("read_at").is_some(),
+            "read_at": r.get::<Option<String>, _>("read_at"),
+            "created_at": r.get::<String, _>("created_at"),
+        }))
+        .collect();
+    let next_before = if rows.len() as i64 == limit {
+        rows.last().map(|r| r.get::<i64, _>("id"))
+    } else {
+        None
+    };
+
+    (StatusCode::OK, Json(json!({
+        "address": query.address,
+        "notifications": notifications,
+        "unread": unread,
+        "next_before": next_before,
+    })))
+}
+
+/// `GET /api/notifications/unread?address=`
+pub async fn get_unread(
+    State(state): State<Arc<AppState>>,
+    ApiQuery(query): ApiQuery<AddressQuery>,
+) -> (StatusCode, Json<serde_json::Value>) {
+    match unread_counts(&state.pool, &query.address).await {
+        Ok(unread) => (StatusCode::OK, Json(json!({ "address": query.address, "unread": unread }))),
+        Err(e) => {
+            tracing::error!("DB error: {}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({ "error": "db error" })))
+        }
+    }
+}
+
+/// `POST /api/notifications/read` with `{"address", "ids": [...]}` or
+/// `{"address", "all": true}`
+pub async fn mark_read(
+    State(state): State<Arc<AppState>>,
+    ApiJson(payload): ApiJson<MarkReadRequest>,
+) -> (StatusCode, Json<serde_json::Value>) {
+    let pool = &state.pool;
+
+    let result = if payload.all {
+        sqlx::query("UPDATE notifications SET read_at = CURRENT_TIMESTAMP WHERE recipient = ? AND in_app AND read_at IS NULL")
+            .bind(&payload.address)
+            .execute(pool)
+            .await
+    } else {
+        if payload.ids.is_empty() {
+            return (StatusCode::BAD_REQUEST, Json(json!({ "error": "ids or all required" })));
+        }
+        if payload.ids.len() > MAX_MARK_IDS {
+            return (StatusCode::BAD_REQUEST, Json(json!({ "error": format!("at most {} ids", MAX_MARK_IDS) })));
+        }
+
+        let placeholders = vec!["?"; payload.ids.len()].join(", ");
+        let query = format!(
+            "UPDATE notifications SET read_at = CURRENT_TIMESTAMP WHERE recipient = ? AND read_at IS NULL AND id IN ({})",
+            placeholders
+        );
+        let mut q = sqlx::query(&query).bind(&payload.address);
+        for id in &payload.ids {
+            q = q.bind(id);
+        }
+        q.execute(pool).await
+    };
+
+    let marked = match result {
+        Ok(r) => r.rows_affected(),
+        Err(e) => {
+            tracing::error!("DB error: {}", e);
+            return (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({ "error": "db error" })));
+        }
+    };
+
+    match unread_counts(pool, &payload.address).await {
+        Ok(unread) => (StatusCode::OK, Json(json!({ "marked": marked, "unread": unread }))),
+        Err(e) => {
+            tracing::error!("DB error: {}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({ "error": "db error" })))
+        }
+    }
+}
+
+/// `GET /api/notifications/preferences?address=`
+pub async fn get_preferences(
+    State(state): State<Arc<AppState>>,
+    ApiQuery(query): ApiQuery<AddressQuery>,
+) -> (StatusCode, Json<serde_json::Value>) {
+    match preferences(&state.pool, &query.address).await {
+        Ok(p) => (StatusCode::OK, Json(p)),
+        Err(e) => {
+            tracing::error!("DB error: {}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({ "error": "db error" })))
+        }
+    }
+}
+
+/// `PUT /api/notifications/preferences`
+/// Turns sinks on or off per kind and sets the webhook URL. A kind muted
+/// for every sink is no longer recorded at all. Registering a URL where
+/// there was none returns `webhook_secret`, the key its deliveries are
+/// signed with; it isn't shown again, and changing the URL keeps it.
+pub async fn update_preferences(
+    State(state): State<Arc<AppState>>,
+    ApiJson(payload): ApiJson<UpdatePreferencesRequest>,
+) -> (StatusCode, Json<serde_json::Value>) {
+    let pool = &state.pool;
+
+    let mut kinds = Vec::new();
+    for (name, preference) in &payload.kinds {
+        match Kind::parse(name) {
+            Some(kind) => kinds.push((kind, preference)),
+            None => {
+                let known: Vec<&str> = Kind::ALL.iter().map(|k| k.as_str()).collect();
+                return (StatusCode::BAD_REQUEST, Json(json!({
+                    "error": format!("unknown notification kind {:?}: expected one of {}", name, known.join(", "))
+                })));
+            }
+        }
+    }
+    if let Some(url) = payload.webhook_url.as_deref().filter(|u| !u.is_empty()) {
+        if let Err(e) = validate_webhook_url(url).await {
+            return (StatusCode::BAD_REQUEST, Json(json!({ "error": e })));
+        }
+    }
+
+    let result: Result<Option<String>, sqlx::Error> = async {
+        let mut tx = pool.begin().await?;
+        sqlx::query("INSERT OR IGNORE INTO profiles(address, reputation, total_posts, total_attention_earned, joined_at, updated_at) VALUES (?, 50, 0, 0, CURRENT_TIMESTAMP, CURRENT_TIMESTAMP)")
+            .bind(&payload.address)
+            .execute(&mut *tx)
+            .await?;
+
+        for (kind, preference) in kinds {
+            sqlx::query(
+                r#"
+                INSERT INTO notification_preferences(address, kind, in_app, webhook, updated_at)
+                VALUES (?1, ?2, COALESCE(?3, TRUE), COALESCE(?4, TRUE), CURRENT_TIMESTAMP)
+                ON CONFLICT (address, kind) DO UPDATE SET
+                    in_app = COALESCE(?3, in_app),
+                    webhook = COALESCE(?4, webhook),
+                    updated_at = CURRENT_TIMESTAMP
+                "#
+            )
+            .bind(&payload.address)
+            .bind(kind.as_str())
+            .bind(preference.in_app)
+            .bind(preference.webhook)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        let mut secret = None;
+        match payload.webhook_url.as_deref() {
+            Some("") => {
+                sqlx::query("DELETE FROM notification_webhooks WHERE address = ?")
+                    .bind(&payload.address)
+                    .execute(&mut *tx)
+                    .await?;
+            }
+            Some(url) => {
+                let updated = sqlx::query("UPDATE notification_webhooks SET url = ?, updated_at = CURRENT_TIMESTAMP WHERE address = ?")
+                    .bind(url)
+                    .bind(&payload.address)
+                    .execute(&mut *tx)
+                    .await?;
+                if updated.rows_affected() == 0 {
+                    let new_secret = new_secret();
+                    sqlx::query("INSERT INTO notification_webhooks(address, url, secret, updated_at) VALUES (?, ?, ?, CURRENT_TIMESTAMP)")
+                        .bind(&payload.address)
+                        .bind(url)
+                        .bind(&new_secret)
+                        .execute(&mut *tx)
+                        .await?;
+                    secret = Some(new_secret);
+                }
+            }
+            None => {}
+        }
+
+        tx.commit().await?;
+        Ok(secret)
+    }
+    .await;
+
+    let secret = match result {
+        Ok(s) => s,
+        Err(e) => {
+            tracing::error!("DB error: {}", e);
+            return (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({ "error": "db error" })));
+        }
+    };
+
+    match preferences(pool, &payload.address).await {
+        Ok(mut p) => {
+            if let Some(secret) = secret {
+                p["webhook_secret"] = json!(secret);
+            }
+            (StatusCode::OK, Json(p))
+        }
+        Err(e) => {
+            tracing::error!("DB error: {}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({ "error": "db error" })))
+        }
+    }
+}
+
+/// Unread in-app notifications, in total and per kind
+async fn unread_counts(pool: &SqlitePool, address: &SuiAddress) -> Result<serde_json::Value, sqlx::Error> {
+    let rows = sqlx::query("SELECT kind, COUNT(*) as count FROM notifications WHERE recipient = ? AND in_app AND read_at IS NULL GROUP BY kind")
+        .bind(address)
+        .fetch_all(pool)
+        .await?;
+
+    let mut by_kind = serde_json::Map::new();
+    let mut total = 0;
+    for r in &rows {
+        let count: i64 = r.get("count");
+        total += count;
+        by_kind.insert(r.get("kind"), json!(count));
+    }
+
+    Ok(json!({ "total": total, "by_kind": by_kind }))
+}
+
+/// Every kind's settings, defaults included, and the webhook URL
+async fn preferences(pool: &SqlitePool, address: &SuiAddress) -> Result<serde_json::Value, sqlx::Error> {
+    let rows = sqlx::query("SELECT kind, in_app, webhook FROM notification_preferences WHERE address = ?")
+        .bind(address)
+        .fetch_all(pool)
+        .await?;
+    let webhook_url: Option<String> = sqlx::query_scalar("SELECT url FROM notification_webhooks WHERE address = ?")
+        .bind(address)
+        .fetch_optional(pool)
+        .await?;
+
+    let mut kinds = serde_json::Map::new();
+    for kind in Kind::ALL {
+        let row = rows.iter().find(|r| r.get::<String, _>("kind") == kind.as_str());
+        kinds.insert(kind.as_str().to_string(), json!({
+            "in_app": row.is_none_or(|r| r.get::<bool, _>("in_app")),
+            "webhook": row.is_none_or(|r| r.get::<bool, _>("webhook")),
+        }));
+    }
+
+    Ok(json!({ "address": address, "kinds": kinds, "webhook_url": webhook_url }))
+}
+
+/// Webhook URLs must be absolute http(s) URLs whose host resolves only to
+/// public addresses; shared with `webhooks`. The dispatchers check the
+/// addresses again on every send.
+pub(crate) async fn validate_webhook_url(url: &str) -> Result<(), String> {
+    if url.len() > MAX_URL_LEN {
+        return Err(format!("webhook URL longer than {} characters", MAX_URL_LEN));
+    }
+    let parsed = match reqwest::Url::parse(url) {
+        Ok(u) if matches!(u.scheme(), "http" | "https") => u,
+        Ok(_) => return Err("webhook URL must be an http or https URL".to_string()),
+        Err(e) => return Err(format!("invalid webhook URL: {}", e)),
+    };
+    let (Some(host), Some(port)) = (parsed.host_str(), parsed.port_or_known_default()) else {
+        return Err("webhook URL must be an http or https URL".to_string());
+    };
+
+    webhooks::resolve_public(host, port, webhooks::allow_private_networks()).await?;
+    Ok(())
+}
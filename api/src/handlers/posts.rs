@@ -1,14 +1,37 @@
 use axum::{
     extract::{Path, State},
-    http::StatusCode,
+    http::{header, HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
     Json,
 };
 use serde_json::json;
-use sqlx::Row;
+use sqlx::{Row, SqlitePool};
 use std::sync::Arc;
 use uuid::Uuid;
-use crate::{models::Post, AppState};
+use crate::{
+    affinity::{self, ReadHistoryEntry, ReaderAffinity},
+    extract::ApiQuery,
+    feed_cache::CachedFeed,
+    models::FeedQuery,
+    AppState,
+};
+use suiter_core::{
+    address::SuiAddress,
+    search::{self, Doc},
+    stream, tags,
+};
+use suiter_ranker::{self as ranking, FeedRanker};
+
+/// Sessions considered when building a reader's affinity profile
+const READ_HISTORY_LIMIT: i64 = 500;
+/// Score changes returned by the ranking explainability endpoint
+const RANKING_HISTORY_LIMIT: i64 = 100;
+const MAX_NONCE_LEN: usize = 100;
 
+/// `POST /api/posts`
+/// The post is provisional until the indexer links it to its on-chain
+/// object. Clients pass a `nonce` (also given to `/api/tx/submit`) so retries
+/// return the same post and the indexer can match the event to it.
 pub async fn create_post(
     State(state): State<Arc<AppState>>,
     Json(payload): Json<serde_json::Value>,
@@ -22,11 +45,47 @@ pub async fn create_post(
         }
     };
 
-    let author = payload
-        .get("author")
+    let author = match payload.get("author").and_then(|v| v.as_str()).map(SuiAddress::parse) {
+        Some(Ok(address)) => address,
+        Some(Err(e)) => {
+            return (StatusCode::BAD_REQUEST, Json(json!({ "error": e.to_string() })));
+        }
+        None => {
+            return (StatusCode::BAD_REQUEST, Json(json!({ "error": "author required" })));
+        }
+    };
+
+    let nonce = payload
+        .get("nonce")
         .and_then(|v| v.as_str())
-        .map(|s| s.to_string())
-        .unwrap_or_else(|| "anonymous".to_string());
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty());
+    if nonce.as_ref().is_some_and(|n| n.len() > MAX_NONCE_LEN) {
+        return (StatusCode::BAD_REQUEST, Json(json!({ "error": "nonce too long" })));
+    }
+
+    // A retry of a create we already accepted
+    if let Some(nonce) = &nonce {
+        let existing = sqlx::query("SELECT id, provisional FROM posts WHERE author = ? AND client_nonce = ?")
+            .bind(&author)
+            .bind(nonce)
+            .fetch_optional(pool)
+            .await;
+        match existing {
+            Ok(Some(r)) => {
+                return (StatusCode::OK, Json(json!({
+                    "id": r.get::<String, _>("id"),
+                    "status": "exists",
+                    "provisional": r.get::<bool, _>("provisional"),
+                })));
+            }
+            Ok(None) => {}
+            Err(e) => {
+                tracing::error!("DB error: {}", e);
+                return (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({ "error": "db error" })));
+            }
+        }
+    }
 
     let id = Uuid::new_v4().to_string();
 
@@ -39,8 +98,8 @@ pub async fn create_post(
 
     // Insert post
     if let Err(e) = sqlx::query(
-        "INSERT INTO posts(id, author, content_hash, attention_accumulated, level, created_at, updated_at) VALUES (?, ?, ?, 0, 1, CURRENT_TIMESTAMP, CURRENT_TIMESTAMP)"
-    ).bind(&id).bind(&author).bind(&content).execute(pool).await {
+        "INSERT INTO posts(id, author, content_hash, attention_accumulated, level, created_at, updated_at, provisional, client_nonce) VALUES (?, ?, ?, 0, 1, CURRENT_TIMESTAMP, CURRENT_TIMESTAMP, TRUE, ?)"
+    ).bind(&id).bind(&author).bind(&content).bind(&nonce).execute(pool).await {
         tracing::error!("Failed to insert post: {}", e);
         return (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({"error": "failed to insert post"})));
     }
@@ -50,7 +109,26 @@ pub async fn create_post(
         tracing::error!("Failed to update profile count: {}", e);
     }
 
-    (StatusCode::CREATED, Json(json!({ "id": id, "status": "created" })))
+    // Rank the new post now rather than waiting for the indexer's next poll
+    if let Err(e) = state.ranker.recompute(std::slice::from_ref(&id)).await {
+        tracing::error!("Failed to rank post {}: {}", id, e);
+        if let Err(e) = FeedRanker::mark_dirty(pool, &id, "created").await {
+            tracing::error!("Failed to mark post {} dirty: {}", id, e);
+        }
+    }
+
+    if let Err(e) = search::index(pool, Doc::Post, &id).await {
+        tracing::error!("Failed to index post {}: {}", id, e);
+    }
+    if let Err(e) = tags::tag_post(pool, &id).await {
+        tracing::error!("Failed to tag post {}: {}", id, e);
+    }
+
+    let event = json!({ "post_id": id, "author": author, "provisional": true });
+    state.stream.publish(pool, stream::FEED, "post_created", event.clone()).await;
+    state.stream.publish(pool, &stream::profile_topic(author.as_str()), "post_created", event).await;
+
+    (StatusCode::CREATED, Json(json!({ "id": id, "status": "created", "provisional": true })))
 }
 
 pub async fn get_post(
@@ -59,7 +137,8 @@ pub async fn get_post(
 ) -> (StatusCode, Json<serde_json::Value>) {
     let pool = &state.pool;
 
-    let row = sqlx::query("SELECT id, author, content_hash, level, attention_accumulated, created_at FROM posts WHERE id = ?")
+    // Linked posts still answer to the id the API gave them
+    let row = sqlx::query("SELECT id, author, content_hash, level, attention_accumulated, created_at, provisional FROM posts WHERE id = ?1 OR provisional_id = ?1 ORDER BY id = ?1 DESC LIMIT 1")
         .bind(&id)
         .fetch_optional(pool)
         .await
@@ -74,9 +153,10 @@ pub async fn get_post(
                 "id": r.get::<String, _>("id"),
                 "author": r.get::<String, _>("author"),
                 "content_hash": r.get::<String, _>("content_hash"),
-                "level": r.get::<i64, _>("level") as i64,
+                "level": r.get::<i64, _>("level"),
                 "attention_accumulated": r.get::<i64, _>("attention_accumulated"),
                 "created_at": r.get::<String, _>("created_at"),
+                "provisional": r.get::<bool, _>("provisional"),
             });
             (StatusCode::OK, Json(obj))
         }
@@ -85,32 +165,309 @@ pub async fn get_post(
     }
 }
 
+/// Explain a post's ranking: each component with its weight and raw input,
+/// the post's current rank position and its score history
+pub async fn get_ranking(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+) -> (StatusCode, Json<serde_json::Value>) {
+    let pool = &state.pool;
+
+    let row = match sqlx::query(
+        r#"
+        SELECT p.level, p.attention_accumulated, p.created_at,
+               p.created_at > datetime('now', '-1 hour') as fresh,
+               COALESCE(pr.reputation, 50) as reputation,
+               CAST(fr.score AS REAL) as score,
+               CAST(fr.level_score AS REAL) as level_score,
+               CAST(fr.reputation_score AS REAL) as reputation_score,
+               CAST(fr.attention_score AS REAL) as attention_score,
+               CAST(fr.time_score AS REAL) as time_score,
+               fr.calculated_at,
+               EXISTS (SELECT 1 FROM ranking_dirty d WHERE d.post_id = p.id) as pending,
+               (SELECT MAX(score) FROM risk_flags WHERE address = p.author) as risk
+        FROM posts p
+        LEFT JOIN profiles pr ON p.author = pr.address
+        LEFT JOIN feed_rankings fr ON p.id = fr.post_id
+        WHERE p.id = ?
+        "#
+    )
+    .bind(&id)
+    .fetch_optional(pool)
+    .await
+    {
+        Ok(Some(r)) => r,
+        Ok(None) => return (StatusCode::NOT_FOUND, Json(json!({ "error": "not found" }))),
+        Err(e) => {
+            tracing::error!("Failed to load ranking for {}: {}", id, e);
+            return (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({ "error": "db error" })));
+        }
+    };
+
+    let Some(score) = row.get::<Option<f64>, _>("score") else {
+        return (StatusCode::NOT_FOUND, Json(json!({ "error": "post not ranked yet" })));
+    };
+
+    let position: Result<(i64, i64), sqlx::Error> = sqlx::query_as(
+        "SELECT (SELECT COUNT(*) FROM feed_rankings WHERE CAST(score AS REAL) > ?) + 1, (SELECT COUNT(*) FROM feed_rankings)"
+    )
+    .bind(score)
+    .fetch_one(pool)
+    .await;
+
+    let history = sqlx::query(
+        r#"
+        SELECT CAST(score AS REAL) as score,
+               CAST(level_score AS REAL) as level_score,
+               CAST(reputation_score AS REAL) as reputation_score,
+               CAST(attention_score AS REAL) as attention_score,
+               CAST(time_score AS REAL) as time_score,
+               calculated_at
+        FROM feed_ranking_history
+        WHERE post_id = ?
+        ORDER BY id DESC
+        LIMIT ?
+        "#
+    )
+    .bind(&id)
+    .bind(RANKING_HISTORY_LIMIT)
+    .fetch_all(pool)
+    .await;
+
+    let ((rank, total_ranked), history) = match (position, history) {
+        (Ok(p), Ok(h)) => (p, h),
+        (Err(e), _) | (_, Err(e)) => {
+            tracing::error!("Failed to load ranking history for {}: {}", id, e);
+            return (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({ "error": "db error" })));
+        }
+    };
+
+    let level = row.get::<i64, _>("level");
+    let reputation = row.get::<i64, _>("reputation");
+    let attention = row.get::<i64, _>("attention_accumulated");
+    let fresh = row.get::<bool, _>("fresh");
+
+    let components = json!([
+        {
+            "name": "level",
+            "weight": ranking::LEVEL_WEIGHT,
+            "raw": level,
+            "normalized": level as f64 / ranking::MAX_LEVEL,
+            "contribution": row.get::<Option<f64>, _>("level_score"),
+        },
+        {
+            "name": "reputation",
+            "weight": ranking::REPUTATION_WEIGHT,
+            "raw": reputation,
+            "normalized": reputation as f64 / ranking::MAX_REPUTATION,
+            "contribution": row.get::<Option<f64>, _>("reputation_score"),
+        },
+        {
+            "name": "attention",
+            "weight": ranking::ATTENTION_WEIGHT,
+            "raw": attention,
+            "normalized": attention as f64 / ranking::ATTENTION_SCALE,
+            "contribution": row.get::<Option<f64>, _>("attention_score"),
+        },
+        {
+            "name": "time",
+            "weight": ranking::TIME_WEIGHT,
+            "raw": row.get::<String, _>("created_at"),
+            "normalized": if fresh { ranking::FRESH_TREND } else { ranking::STALE_TREND },
+            "contribution": row.get::<Option<f64>, _>("time_score"),
+        },
+    ]);
+
+    let history: Vec<serde_json::Value> = history
+        .into_iter()
+        .map(|h| json!({
+            "score": h.get::<f64, _>("score"),
+            "level_score": h.get::<Option<f64>, _>("level_score"),
+            "reputation_score": h.get::<Option<f64>, _>("reputation_score"),
+            "attention_score": h.get::<Option<f64>, _>("attention_score"),
+            "time_score": h.get::<Option<f64>, _>("time_score"),
+            "calculated_at": h.get::<i64, _>("calculated_at"),
+        }))
+        .collect();
+
+    (StatusCode::OK, Json(json!({
+        "post_id": id,
+        "score": score,
+        "rank": rank,
+        "total_ranked": total_ranked,
+        "calculated_at": row.get::<Option<i64>, _>("calculated_at"),
+        // Raw inputs are live; a pending re-score means they may not be reflected yet
+        "rescore_pending": row.get::<bool, _>("pending"),
+        "components": components,
+        // Sybil risk of the author; the total is scaled by (1 - penalty * risk)
+        "author_risk": row.get::<Option<f64>, _>("risk").unwrap_or(0.0),
+        "risk_penalty": ranking::RISK_PENALTY,
+        "history": history,
+    })))
+}
+
 pub async fn get_feed(
     State(state): State<Arc<AppState>>,
-) -> (StatusCode, Json<Vec<serde_json::Value>>) {
+    ApiQuery(query): ApiQuery<FeedQuery>,
+    headers: HeaderMap,
+) -> Response {
     let pool = &state.pool;
 
-    let rows = match sqlx::query("SELECT p.id, p.author, p.content_hash, p.level, p.attention_accumulated, CAST(COALESCE(fr.score,0.0) AS REAL) as score FROM posts p LEFT JOIN feed_rankings fr ON p.id = fr.post_id ORDER BY CAST(COALESCE(fr.score,0.0) AS REAL) DESC LIMIT 200")
-        .fetch_all(pool).await {
-            Ok(r) => r,
-            Err(e) => {
-                tracing::error!("Failed to fetch feed: {}", e);
-                return (StatusCode::INTERNAL_SERVER_ERROR, Json(vec![]));
-            }
+    let feed = match state.feed_cache.get_or_load(pool).await {
+        Ok(f) => f,
+        Err(e) => {
+            tracing::error!("Failed to fetch feed: {}", e);
+            return (StatusCode::INTERNAL_SERVER_ERROR, Json(Vec::<serde_json::Value>::new())).into_response();
+        }
+    };
+
+    let Some(reader) = query.reader else {
+        return cached_feed_response(&feed, &headers);
+    };
+
+    let history = match load_read_history(pool, reader.as_str()).await {
+        Ok(h) => h,
+        Err(e) => {
+            tracing::error!("Failed to load read history for {}: {}", reader, e);
+            return (StatusCode::INTERNAL_SERVER_ERROR, Json(Vec::<serde_json::Value>::new())).into_response();
+        }
+    };
+
+    // Cold start: no usable history, serve the global ranking as-is
+    let affinity = ReaderAffinity::from_history(&history);
+    if affinity.is_cold() {
+        return cached_feed_response(&feed, &headers);
+    }
+
+    let mut out = feed.posts.as_ref().clone();
+    personalize(&mut out, &affinity);
+    (StatusCode::OK, Json(out)).into_response()
+}
+
+/// Serve the global feed from cache, honouring `If-None-Match`
+fn cached_feed_response(feed: &CachedFeed, headers: &HeaderMap) -> Response {
+    let not_modified = headers
+        .get(header::IF_NONE_MATCH)
+        .and_then(|v| v.to_str().ok())
+        .map(|v| v.split(',').any(|tag| tag.trim() == feed.etag || tag.trim() == "*"))
+        .unwrap_or(false);
+
+    if not_modified {
+        return (StatusCode::NOT_MODIFIED, [(header::ETAG, feed.etag.clone())]).into_response();
+    }
+
+    (
+        StatusCode::OK,
+        [
+            (header::ETAG, feed.etag.clone()),
+            (header::CONTENT_TYPE, "application/json".to_string()),
+        ],
+        feed.body.clone(),
+    )
+        .into_response()
+}
+
+/// Most recent sessions of a reader, joined with the posts they read
+async fn load_read_history(pool: &SqlitePool, reader: &str) -> Result<Vec<ReadHistoryEntry>, sqlx::Error> {
+    let rows = sqlx::query("SELECT p.author, p.content_hash, a.duration_ms FROM attention_sessions a JOIN posts p ON p.id = a.post_id WHERE a.reader = ? AND a.validity = 'valid' ORDER BY a.created_at DESC, a.id LIMIT ?")
+        .bind(reader)
+        .bind(READ_HISTORY_LIMIT)
+        .fetch_all(pool)
+        .await?;
+
+    Ok(rows
+        .into_iter()
+        .map(|r| ReadHistoryEntry {
+            author: r.get::<String, _>("author"),
+            content: r.get::<String, _>("content_hash"),
+            duration_ms: r.get::<i64, _>("duration_ms"),
+        })
+        .collect())
+}
+
+/// Re-rank feed entries by blending the global score with reader affinity.
+/// Global scores are normalized against the best post so both terms are in [0, 1].
+fn personalize(feed: &mut [serde_json::Value], affinity: &ReaderAffinity) {
+    let max_score = feed
+        .iter()
+        .filter_map(|p| p["score"].as_f64())
+        .fold(0.0, f64::max);
+    let weight = affinity.blend_weight();
+
+    for post in feed.iter_mut() {
+        let global = match post["score"].as_f64() {
+            Some(s) if max_score > 0.0 => s / max_score,
+            _ => 0.0,
         };
+        let reader_affinity = affinity.score(
+            post["author"].as_str().unwrap_or_default(),
+            post["content_hash"].as_str().unwrap_or_default(),
+        );
+        post["affinity"] = json!(reader_affinity);
+        post["personalized_score"] = json!(affinity::blend(global, reader_affinity, weight));
+    }
 
-    let mut out = Vec::with_capacity(rows.len());
-    for r in rows {
-        let obj = json!({
-            "id": r.get::<String, _>("id"),
-            "author": r.get::<String, _>("author"),
-            "content_hash": r.get::<String, _>("content_hash"),
-            "level": r.get::<i64, _>("level") as i64,
-            "attention_accumulated": r.get::<i64, _>("attention_accumulated"),
-            "score": r.get::<f64, _>("score"),
-        });
-        out.push(obj);
+    // Ties fall back to post id so the order is deterministic
+    feed.sort_by(|a, b| {
+        let sa = a["personalized_score"].as_f64().unwrap_or(0.0);
+        let sb = b["personalized_score"].as_f64().unwrap_or(0.0);
+        sb.total_cmp(&sa)
+            .then_with(|| a["id"].as_str().cmp(&b["id"].as_str()))
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_state;
+
+    async fn post(pool: &SqlitePool, id: &str, level: i64) {
+        sqlx::query("INSERT OR IGNORE INTO profiles(address, reputation) VALUES ('0xa', 500)")
+            .execute(pool)
+            .await
+            .unwrap();
+        sqlx::query("INSERT INTO posts(id, author, content_hash, level, created_at) VALUES (?, '0xa', 'h', ?, CURRENT_TIMESTAMP)")
+            .bind(id)
+            .bind(level)
+            .execute(pool)
+            .await
+            .unwrap();
     }
 
-    (StatusCode::OK, Json(out))
+    #[tokio::test]
+    async fn get_ranking_explains_a_ranked_post() {
+        let state = test_state().await;
+        post(&state.pool, "0xp1", 5).await;
+        post(&state.pool, "0xp2", 1).await;
+        state.ranker.recompute(&["0xp1".to_string(), "0xp2".to_string()]).await.unwrap();
+
+        let (status, Json(body)) = get_ranking(State(state.clone()), Path("0xp2".to_string())).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["rank"], 2);
+        assert_eq!(body["total_ranked"], 2);
+        assert_eq!(body["rescore_pending"], false);
+
+        // The components add up to the score when nobody is flagged
+        let contributions: f64 = body["components"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|c| c["contribution"].as_f64().unwrap())
+            .sum();
+        assert!((contributions - body["score"].as_f64().unwrap()).abs() < 1e-9);
+        assert_eq!(body["history"].as_array().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn get_ranking_answers_404_for_unranked_and_unknown_posts() {
+        let state = test_state().await;
+        post(&state.pool, "0xp1", 1).await;
+
+        let (status, Json(body)) = get_ranking(State(state.clone()), Path("0xp1".to_string())).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        assert_eq!(body["error"], "post not ranked yet");
+
+        let (status, _) = get_ranking(State(state), Path("0xnone".to_string())).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+    }
 }
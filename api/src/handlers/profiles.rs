@@ -1,16 +1,27 @@
 use axum::{
-    extract::{Path, State},
+    extract::State,
     http::StatusCode,
     Json,
 };
 use serde_json::json;
 use std::sync::Arc;
 
-use crate::AppState;
+use suiter_core::{
+    address::SuiAddress,
+    search::{self, Doc},
+};
+
+use crate::{
+    extract::{ApiJson, ApiPath},
+    models::UpdateProfileRequest,
+    AppState,
+};
+
+const MAX_DISPLAY_NAME_LEN: usize = 50;
 
 pub async fn get_profile(
     State(_state): State<Arc<AppState>>,
-    Path(_address): Path<String>,
+    ApiPath(_address): ApiPath<SuiAddress>,
 ) -> (StatusCode, Json<serde_json::Value>) {
     // TODO: Implement get profile
     (StatusCode::OK, Json(json!({
@@ -23,7 +34,7 @@ pub async fn get_profile(
 
 pub async fn get_reputation(
     State(_state): State<Arc<AppState>>,
-    Path(_address): Path<String>,
+    ApiPath(_address): ApiPath<SuiAddress>,
 ) -> (StatusCode, Json<serde_json::Value>) {
     // TODO: Implement get reputation
     (StatusCode::OK, Json(json!({
@@ -31,3 +42,44 @@ pub async fn get_reputation(
         "status": "normal"
     })))
 }
+
+/// `PUT /api/profiles/:address` with `{"display_name"}`
+pub async fn update_profile(
+    State(state): State<Arc<AppState>>,
+    ApiPath(address): ApiPath<SuiAddress>,
+    ApiJson(payload): ApiJson<UpdateProfileRequest>,
+) -> (StatusCode, Json<serde_json::Value>) {
+    let pool = &state.pool;
+
+    let display_name = payload.display_name.as_deref().map(str::trim).filter(|n| !n.is_empty());
+    if let Some(name) = display_name {
+        if name.chars().count() > MAX_DISPLAY_NAME_LEN {
+            return (StatusCode::BAD_REQUEST, Json(json!({ "error": format!("display_name longer than {} characters", MAX_DISPLAY_NAME_LEN) })));
+        }
+        if name.chars().any(char::is_control) {
+            return (StatusCode::BAD_REQUEST, Json(json!({ "error": "display_name contains control characters" })));
+        }
+    }
+
+    let result = sqlx::query(
+        r#"
+        INSERT INTO profiles(address, reputation, total_posts, total_attention_earned, display_name, joined_at, updated_at)
+        VALUES (?1, 50, 0, 0, ?2, CURRENT_TIMESTAMP, CURRENT_TIMESTAMP)
+        ON CONFLICT (address) DO UPDATE SET display_name = ?2, updated_at = CURRENT_TIMESTAMP
+        "#
+    )
+    .bind(&address)
+    .bind(display_name)
+    .execute(pool)
+    .await;
+    if let Err(e) = result {
+        tracing::error!("DB error: {}", e);
+        return (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({ "error": "db error" })));
+    }
+
+    if let Err(e) = search::index(pool, Doc::Profile, address.as_str()).await {
+        tracing::error!("Failed to index profile {}: {}", address, e);
+    }
+
+    (StatusCode::OK, Json(json!({ "address": address, "display_name": display_name })))
+}
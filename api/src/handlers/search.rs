@@ -0,0 +1,249 @@
+use axum::{extract::State, http::StatusCode, Json};
+use serde_json::json;
+use sqlx::{sqlite::SqliteRow, Row};
+use std::sync::Arc;
+use suiter_core::search::Doc;
+
+use crate::{extract::ApiQuery, models::SearchQuery, AppState};
+
+const DEFAULT_PAGE: i64 = 20;
+const MAX_PAGE: i64 = 100;
+/// Deepest result reachable by paging
+const MAX_OFFSET: i64 = 1000;
+const MAX_QUERY_LEN: usize = 200;
+/// Words of the query that are matched; the rest are ignored
+const MAX_TERMS: usize = 10;
+/// How much feed standing lifts a match: a post with feed score 1.0 ranks
+/// as if its text matched (1 + FEED_WEIGHT) times as well
+const FEED_WEIGHT: f64 = 1.0;
+/// Tokens around a match in a snippet
+const SNIPPET_TOKENS: i64 = 16;
+
+/// `GET /api/search?q=&types=post,claim,profile&limit=&offset=`
+/// Every word must match; the last one also matches as a prefix, so
+/// results follow along while typing. Results are ranked by BM25 relevance
+/// lifted by the feed score of the post (or, for a claim, the post it's
+/// about), with `<mark>`-highlighted snippets.
+pub async fn search(
+    State(state): State<Arc<AppState>>,
+    ApiQuery(query): ApiQuery<SearchQuery>,
+) -> (StatusCode, Json<serde_json::Value>) {
+    let pool = &state.pool;
+
+    if query.q.len() > MAX_QUERY_LEN {
+        return (StatusCode::BAD_REQUEST, Json(json!({ "error": format!("q longer than {} characters", MAX_QUERY_LEN) })));
+    }
+    let Some(matcher) = match_expression(&query.q) else {
+        return (StatusCode::BAD_REQUEST, Json(json!({ "error": "q must contain a word to search for" })));
+    };
+    let kinds = match parse_types(query.types.as_deref()) {
+        Ok(k) => k,
+        Err(e) => return (StatusCode::BAD_REQUEST, Json(json!({ "error": e }))),
+    };
+    let limit = query.limit.unwrap_or(DEFAULT_PAGE).clamp(1, MAX_PAGE);
+    let offset = query.offset.unwrap_or(0).clamp(0, MAX_OFFSET);
+
+    // Kinds are validated names, so they can be inlined
+    let kind_list = kinds.iter().map(|k| format!("'{}'", k.as_str())).collect::<Vec<_>>().join(", ");
+    let results = sqlx::query(&format!(
+        r#"
+        SELECT d.kind, d.ref_id,
+            snippet(search_index, 0, '<mark>', '</mark>', '…', {snippet}) as snippet,
+            -bm25(search_index) as relevance,
+            COALESCE(fr.score, 0.0) as feed_score,
+            p.author, p.level, p.created_at, p.provisional,
+            c.post_id, c.claimer, c.resolved, c.outcome,
+            pr.display_name, pr.reputation
+        FROM search_index
+        JOIN search_documents d ON d.id = search_index.rowid
+        LEFT JOIN posts p ON d.kind = 'post' AND p.id = d.ref_id
+        LEFT JOIN truth_claims c ON d.kind = 'claim' AND c.id = d.ref_id
+        LEFT JOIN profiles pr ON d.kind = 'profile' AND pr.address = d.ref_id
+        LEFT JOIN feed_rankings fr ON fr.post_id = COALESCE(p.id, c.post_id)
+        WHERE search_index MATCH ?1 AND d.kind IN ({kinds})
+        ORDER BY -bm25(search_index) * (1.0 + ?2 * COALESCE(fr.score, 0.0)) DESC, d.id DESC
+        LIMIT ?3 OFFSET ?4
+        "#,
+        snippet = SNIPPET_TOKENS,
+        kinds = kind_list,
+    ))
+    .bind(&matcher)
+    .bind(FEED_WEIGHT)
+    .bind(limit)
+    .bind(offset)
+    .fetch_all(pool)
+    .await;
+
+    let total: Result<i64, _> = sqlx::query_scalar(&format!(
+        "SELECT COUNT(*) FROM search_index JOIN search_documents d ON d.id = search_index.rowid WHERE search_index MATCH ? AND d.kind IN ({})",
+        kind_list
+    ))
+    .bind(&matcher)
+    .fetch_one(pool)
+    .await;
+
+    match (results, total) {
+        (Ok(rows), Ok(total)) => {
+            let next_offset = offset + rows.len() as i64;
+            (StatusCode::OK, Json(json!({
+                "query": query.q,
+                "results": rows.iter().map(result_json).collect::<Vec<_>>(),
+                "total": total,
+                "next_offset": (next_offset < total && next_offset <= MAX_OFFSET).then_some(next_offset),
+            })))
+        }
+        (Err(e), _) | (_, Err(e)) => {
+            tracing::error!("DB error: {}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({ "error": "db error" })))
+        }
+    }
+}
+
+/// FTS5 expression for `q`: every word quoted, so user input can't form
+/// query syntax, and the last one matched as a prefix unless the query
+/// ends in whitespace. None if `q` has no words.
+fn match_expression(q: &str) -> Option<String> {
+    let words: Vec<&str> = q
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .take(MAX_TERMS)
+        .collect();
+    let last = words.len().checked_sub(1)?;
+    let prefix = !q.ends_with(char::is_whitespace);
+
+    Some(
+        words
+            .iter()
+            .enumerate()
+            .map(|(i, w)| if i == last && prefix { format!("\"{}\"*", w) } else { format!("\"{}\"", w) })
+            .collect::<Vec<_>>()
+            .join(" "),
+    )
+}
+
+fn parse_types(types: Option<&str>) -> Result<Vec<Doc>, String> {
+    let Some(types) = types.filter(|t| !t.trim().is_empty()) else {
+        return Ok(Doc::ALL.to_vec());
+    };
+
+    let mut kinds = Vec::new();
+    for name in types.split(',').map(str::trim).filter(|t| !t.is_empty()) {
+        match Doc::parse(name) {
+            Some(k) if !kinds.contains(&k) => kinds.push(k),
+            Some(_) => {}
+            None => return Err(format!("unknown type {:?}: expected post, claim or profile", name)),
+        }
+    }
+    Ok(kinds)
+}
+
+fn result_json(r: &SqliteRow) -> serde_json::Value {
+    let kind: String = r.get("kind");
+    let relevance: f64 = r.get("relevance");
+    let feed_score: f64 = r.get("feed_score");
+
+    let mut result = json!({
+        "type": kind,
+        "id": r.get::<String, _>("ref_id"),
+        "snippet": r.get::<String, _>("snippet"),
+        "score": relevance * (1.0 + FEED_WEIGHT * feed_score),
+        "relevance": relevance,
+        "feed_score": feed_score,
+    });
+    let details = match Doc::parse(&kind) {
+        Some(Doc::Post) => json!({
+            "author": r.get::<Option<String>, _>("author"),
+            "level": r.get::<Option<i64>, _>("level"),
+            "created_at": r.get::<Option<String>, _>("created_at"),
+            "provisional": r.get::<Option<bool>, _>("provisional"),
+        }),
+        Some(Doc::Claim) => json!({
+            "post_id": r.get::<Option<String>, _>("post_id"),
+            "claimer": r.get::<Option<String>, _>("claimer"),
+            "resolved": r.get::<Option<bool>, _>("resolved"),
+            "outcome": r.get::<Option<String>, _>("outcome"),
+        }),
+        Some(Doc::Profile) => json!({
+            "display_name": r.get::<Option<String>, _>("display_name"),
+            "reputation": r.get::<Option<i64>, _>("reputation"),
+        }),
+        None => json!({}),
+    };
+    if let serde_json::Value::Object(details) = details {
+        for (key, value) in details {
+            result[key] = value;
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_state;
+    use sqlx::SqlitePool;
+    use suiter_core::search;
+
+    async fn post(pool: &SqlitePool, id: &str, content: &str, feed_score: Option<f64>) {
+        sqlx::query("INSERT OR IGNORE INTO profiles(address) VALUES ('0xa')")
+            .execute(pool)
+            .await
+            .unwrap();
+        sqlx::query("INSERT INTO posts(id, author, content_hash, created_at) VALUES (?, '0xa', ?, CURRENT_TIMESTAMP)")
+            .bind(id)
+            .bind(content)
+            .execute(pool)
+            .await
+            .unwrap();
+        if let Some(score) = feed_score {
+            sqlx::query("INSERT INTO feed_rankings(id, post_id, score, calculated_at) VALUES ('fr_' || ?1, ?1, ?2, 0)")
+                .bind(id)
+                .bind(score)
+                .execute(pool)
+                .await
+                .unwrap();
+        }
+        search::index(pool, Doc::Post, id).await.unwrap();
+    }
+
+    fn query(q: &str) -> ApiQuery<SearchQuery> {
+        ApiQuery(SearchQuery { q: q.to_string(), types: None, limit: None, offset: None })
+    }
+
+    #[tokio::test]
+    async fn feed_score_lifts_equally_relevant_matches() {
+        let state = test_state().await;
+        // Indexed last, so it would win the tie-break without the lift
+        post(&state.pool, "0xranked", "walrus sighting at the pier", Some(0.5)).await;
+        post(&state.pool, "0xunranked", "walrus sighting at the pier", None).await;
+
+        let (status, Json(body)) = search(State(state), query("walrus")).await;
+        assert_eq!(status, StatusCode::OK);
+        let results = body["results"].as_array().unwrap();
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0]["id"], "0xranked");
+        assert_eq!(results[1]["feed_score"], 0.0);
+
+        let top = &results[0];
+        let relevance = top["relevance"].as_f64().unwrap();
+        assert!(relevance > 0.0);
+        assert_eq!(top["feed_score"], 0.5);
+        assert!((top["score"].as_f64().unwrap() - relevance * (1.0 + FEED_WEIGHT * 0.5)).abs() < 1e-9);
+        assert_eq!(results[1]["score"].as_f64().unwrap(), results[1]["relevance"].as_f64().unwrap());
+    }
+
+    #[tokio::test]
+    async fn claims_rank_with_the_feed_score_of_their_post() {
+        let state = test_state().await;
+        post(&state.pool, "0xranked", "harbour news", Some(0.8)).await;
+        sqlx::query("INSERT INTO truth_claims(id, post_id, claimer, claim_text, voting_end) VALUES ('c1', '0xranked', '0xa', 'walrus claim', CURRENT_TIMESTAMP)")
+            .execute(&state.pool)
+            .await
+            .unwrap();
+        search::index(&state.pool, Doc::Claim, "c1").await.unwrap();
+
+        let (_, Json(body)) = search(State(state), query("walrus")).await;
+        assert_eq!(body["results"][0]["type"], "claim");
+        assert_eq!(body["results"][0]["feed_score"], 0.8);
+    }
+}
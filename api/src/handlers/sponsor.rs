@@ -0,0 +1,247 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Json,
+};
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use serde_json::json;
+use sqlx::{Row, SqlitePool};
+use std::sync::Arc;
+use suiter_core::address::SuiAddress;
+
+use crate::{
+    extract::ApiPath,
+    handlers::tx::{self, TxError},
+    models::BuildTxRequest,
+    sponsor_rules::{self as rules, SponsorRejection, Usage},
+    sui_tx::Address,
+    AppState,
+};
+
+/// `POST /api/tx/sponsor/:module/:function`
+/// Like `/api/tx/:module/:function`, but gas is paid from the sponsor's coins
+/// and the response carries the sponsor's signature. The sender signs the
+/// same `tx_bytes` and submits both signatures.
+pub async fn sponsor_tx(
+    State(state): State<Arc<AppState>>,
+    Path((module, function)): Path<(String, String)>,
+    Json(body): Json<serde_json::Value>,
+) -> (StatusCode, Json<serde_json::Value>) {
+    let request = match tx::parse_request(&module, &function, body) {
+        Ok(r) => r,
+        Err(response) => return response,
+    };
+
+    match sponsor(&state, &request).await {
+        Ok(Ok(sponsored)) => (StatusCode::OK, Json(sponsored)),
+        Ok(Err(rejection)) => (rejection_status(rejection), Json(json!({ "error": rejection.as_str() }))),
+        Err(e) => e.into_response(),
+    }
+}
+
+/// `GET /api/profiles/:address/sponsorship`: today's sponsored-transaction
+/// quota and what's left of it
+pub async fn get_quota(
+    State(state): State<Arc<AppState>>,
+    ApiPath(address): ApiPath<SuiAddress>,
+) -> (StatusCode, Json<serde_json::Value>) {
+    match load_usage(&state.pool, address.as_str()).await {
+        Ok((reputation, _, usage)) => {
+            let quota = rules::daily_quota(reputation);
+            (StatusCode::OK, Json(json!({
+                "address": address,
+                "reputation": reputation,
+                "daily_quota": quota,
+                "used_today": usage.today,
+                "remaining_today": (quota - usage.today).max(0),
+                "functions": rules::SPONSORED_FUNCTIONS,
+                "enabled": state.sponsor.is_some(),
+            })))
+        }
+        Err(e) => {
+            tracing::error!("DB error: {}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({ "error": "db error" })))
+        }
+    }
+}
+
+async fn sponsor(
+    state: &AppState,
+    request: &BuildTxRequest,
+) -> Result<Result<serde_json::Value, SponsorRejection>, TxError> {
+    let Some(sponsor) = &state.sponsor else {
+        return Err(TxError::NotConfigured("SPONSOR_PRIVATE_KEY"));
+    };
+    let sender = Address::from(&request.sender);
+    let (module, function) = request.call.module_function();
+    let function = format!("{}::{}", module, function);
+
+    let (reputation, risk, usage) = load_usage(&state.pool, request.sender.as_str()).await?;
+    if let Err(rejection) = rules::check_request(&function, reputation, risk, &usage) {
+        return Ok(Err(rejection));
+    }
+
+    // Allowlisted calls never touch the gas coin, so the sponsor only pays gas
+    let ptb = tx::compose(state, &request.sender, &request.call).await?;
+    let price = state.sui.reference_gas_price().await?;
+    let coins = state.sui.gas_coins(&sponsor.address()).await?;
+    let (payment, available) = tx::select_gas(coins, rules::MAX_SPONSORED_BUDGET);
+    if payment.is_empty() {
+        tracing::error!("Gas sponsor {} has no SUI", sponsor.address());
+        return Ok(Err(SponsorRejection::SponsorBudgetExhausted));
+    }
+
+    let (tx_data, dry_run, budget) = tx::estimate(state, ptb, sender, sponsor.address(), payment, price, available).await?;
+    if let Err(rejection) = rules::check_budget(budget, &usage) {
+        return Ok(Err(rejection));
+    }
+
+    let quota = rules::daily_quota(reputation);
+    let digest = tx_data.digest();
+    let entry = SponsoredTx {
+        digest: &digest,
+        sender: request.sender.as_str(),
+        sponsor: &sponsor.address().to_string(),
+        function: &function,
+        gas_budget: budget as i64,
+        gas_price: price as i64,
+        reputation,
+    };
+    record(&state.pool, &entry).await?;
+
+    let tx_bytes = tx_data.to_bytes();
+    tracing::info!("Sponsored {} for {} ({})", function, request.sender, digest);
+
+    Ok(Ok(json!({
+        "function": function,
+        "sender": sender.to_string(),
+        "sponsor": sponsor.address().to_string(),
+        "digest": digest,
+        "tx_bytes": BASE64.encode(&tx_bytes),
+        "sponsor_signature": sponsor.sign(&tx_bytes),
+        "gas": tx::gas_json(&dry_run, price, budget),
+        "quota": {
+            "daily": quota,
+            "remaining_today": (quota - usage.today - 1).max(0),
+        },
+    })))
+}
+
+/// Reputation, Sybil risk and sponsorship usage for an address. Only
+/// submitted sponsorships count: co-signing is free to ask for on anyone's
+/// behalf, so unsubmitted ones prove nothing about the sender.
+async fn load_usage(pool: &SqlitePool, address: &str) -> Result<(i64, f64, Usage), sqlx::Error> {
+    let row = sqlx::query(
+        r#"
+        SELECT
+            (SELECT reputation FROM profiles WHERE address = ?1) as reputation,
+            (SELECT MAX(score) FROM risk_flags WHERE address = ?1) as risk,
+            (SELECT COUNT(*) FROM sponsored_transactions WHERE sender = ?1 AND submitted_at >= date('now')) as today,
+            (SELECT CAST(strftime('%s', 'now') - strftime('%s', MAX(submitted_at)) AS INTEGER) FROM sponsored_transactions WHERE sender = ?1) as secs_since_last,
+            (SELECT COALESCE(SUM(gas_budget), 0) FROM sponsored_transactions WHERE submitted_at >= date('now')) as spent_today
+        "#
+    )
+    .bind(address)
+    .fetch_one(pool)
+    .await?;
+
+    let reputation = row
+        .get::<Option<i64>, _>("reputation")
+        .unwrap_or(suiter_core::reputation::STARTING_REPUTATION);
+    let usage = Usage {
+        today: row.get("today"),
+        secs_since_last: row.get("secs_since_last"),
+        sponsor_spent_today: row.get("spent_today"),
+    };
+
+    Ok((reputation, row.get::<Option<f64>, _>("risk").unwrap_or(0.0), usage))
+}
+
+/// One `sponsored_transactions` audit row
+struct SponsoredTx<'a> {
+    digest: &'a str,
+    sender: &'a str,
+    sponsor: &'a str,
+    function: &'a str,
+    gas_budget: i64,
+    gas_price: i64,
+    reputation: i64,
+}
+
+/// Write the audit row. It doesn't count against any quota until the
+/// transaction is submitted; see `claim_submission`.
+async fn record(pool: &SqlitePool, entry: &SponsoredTx<'_>) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+        INSERT OR IGNORE INTO sponsored_transactions(digest, sender, sponsor, function, gas_budget, gas_price, reputation, created_at)
+        VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, CURRENT_TIMESTAMP)
+        "#
+    )
+    .bind(entry.digest)
+    .bind(entry.sender)
+    .bind(entry.sponsor)
+    .bind(entry.function)
+    .bind(entry.gas_budget)
+    .bind(entry.gas_price)
+    .bind(entry.reputation)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+/// Count a sponsored transaction against its sender's quota and the
+/// sponsor's daily spend as it is submitted, re-checking both in the same
+/// statement so concurrent submissions can't overshoot them. Returns false
+/// if either is used up; transactions the sponsor didn't sign, and
+/// resubmissions, pass.
+pub async fn claim_submission(pool: &SqlitePool, digest: &str) -> Result<bool, sqlx::Error> {
+    let row = sqlx::query("SELECT sender, submitted_at IS NOT NULL as submitted FROM sponsored_transactions WHERE digest = ?")
+        .bind(digest)
+        .fetch_optional(pool)
+        .await?;
+    let Some(row) = row else {
+        return Ok(true);
+    };
+    if row.get::<bool, _>("submitted") {
+        return Ok(true);
+    }
+
+    let sender = row.get::<String, _>("sender");
+    let (reputation, _, _) = load_usage(pool, &sender).await?;
+    let claimed = sqlx::query(
+        r#"
+        UPDATE sponsored_transactions SET submitted_at = CURRENT_TIMESTAMP
+        WHERE digest = ?1 AND submitted_at IS NULL
+          AND (SELECT COUNT(*) FROM sponsored_transactions WHERE sender = ?2 AND submitted_at >= date('now')) < ?3
+          AND (SELECT COALESCE(SUM(gas_budget), 0) FROM sponsored_transactions WHERE submitted_at >= date('now')) + gas_budget <= ?4
+        "#
+    )
+    .bind(digest)
+    .bind(&sender)
+    .bind(rules::daily_quota(reputation))
+    .bind(rules::MAX_DAILY_SPONSOR_SPEND)
+    .execute(pool)
+    .await?;
+
+    Ok(claimed.rows_affected() > 0)
+}
+
+/// Undo `claim_submission` for a transaction the node refused to execute
+pub async fn release_submission(pool: &SqlitePool, digest: &str) -> Result<(), sqlx::Error> {
+    sqlx::query("UPDATE sponsored_transactions SET submitted_at = NULL WHERE digest = ?")
+        .bind(digest)
+        .execute(pool)
+        .await?;
+    Ok(())
+}
+
+fn rejection_status(rejection: SponsorRejection) -> StatusCode {
+    match rejection {
+        SponsorRejection::NotAllowlisted | SponsorRejection::RiskFlagged => StatusCode::FORBIDDEN,
+        SponsorRejection::BudgetTooHigh => StatusCode::UNPROCESSABLE_ENTITY,
+        SponsorRejection::QuotaExhausted
+        | SponsorRejection::TooFrequent
+        | SponsorRejection::SponsorBudgetExhausted => StatusCode::TOO_MANY_REQUESTS,
+    }
+}
@@ -0,0 +1,273 @@
+use axum::{
+    extract::{
+        ws::{Message, WebSocket, WebSocketUpgrade},
+        State,
+    },
+    http::{HeaderMap, StatusCode},
+    response::{
+        sse::{Event, KeepAlive, Sse},
+        IntoResponse, Response,
+    },
+    Json,
+};
+use futures_util::{stream as futures_stream, SinkExt, StreamExt};
+use serde_json::json;
+use sqlx::SqlitePool;
+use std::collections::{BTreeSet, VecDeque};
+use std::convert::Infallible;
+use std::sync::Arc;
+use suiter_core::{address::SuiAddress, stream};
+use tokio::sync::broadcast::{self, error::RecvError};
+
+use crate::{
+    extract::ApiQuery,
+    models::{StreamCommand, StreamQuery},
+    stream_bus::{self, StreamEvent},
+    AppState,
+};
+
+/// Topics one connection may follow
+const MAX_TOPICS: usize = 50;
+/// Events replayed on resume or after falling behind; past this the client
+/// is told to reset instead
+const REPLAY_LIMIT: i64 = 500;
+const MAX_ID_LEN: usize = 100;
+
+/// `GET /api/stream?topics=feed,post:<id>&resume=<id>`
+/// Server-sent events, or a WebSocket when the request asks to upgrade.
+/// Every event carries its id; reconnecting with it as `resume` (or SSE's
+/// `Last-Event-ID`) replays what was missed. A `reset` event means the gap
+/// was too large to replay and the client should reload its state.
+pub async fn stream(
+    State(state): State<Arc<AppState>>,
+    ws: Option<WebSocketUpgrade>,
+    ApiQuery(query): ApiQuery<StreamQuery>,
+    headers: HeaderMap,
+) -> Response {
+    let topics = match parse_topics(query.topics.as_deref().unwrap_or(stream::FEED).split(',')) {
+        Ok(t) => t,
+        Err(e) => return (StatusCode::BAD_REQUEST, Json(json!({ "error": e }))).into_response(),
+    };
+    let resume = query.resume.or_else(|| {
+        headers
+            .get("last-event-id")
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.parse().ok())
+    });
+
+    let subscription = match Subscription::start(&state, topics, resume).await {
+        Ok(s) => s,
+        Err(e) => {
+            tracing::error!("DB error: {}", e);
+            return (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({ "error": "db error" }))).into_response();
+        }
+    };
+
+    match ws {
+        Some(ws) => ws.on_upgrade(move |socket| run_socket(socket, state.pool.clone(), subscription)),
+        None => {
+            let pool = state.pool.clone();
+            let events = futures_stream::unfold((subscription, pool), |(mut sub, pool)| async move {
+                let delivery = sub.next(&pool).await?;
+                Some((Ok::<_, Infallible>(sse_event(&delivery)), (sub, pool)))
+            });
+            Sse::new(events).keep_alive(KeepAlive::default()).into_response()
+        }
+    }
+}
+
+async fn run_socket(socket: WebSocket, pool: SqlitePool, mut sub: Subscription) {
+    let (mut sink, mut incoming) = socket.split();
+
+    loop {
+        let frame = tokio::select! {
+            message = incoming.next() => match message {
+                Some(Ok(Message::Text(text))) => sub.command(&text),
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => return,
+                Some(Ok(_)) => continue,
+            },
+            delivery = sub.next(&pool) => match delivery {
+                Some(delivery) => delivery.to_json(),
+                None => return,
+            },
+        };
+
+        if sink.send(Message::Text(frame.to_string())).await.is_err() {
+            return;
+        }
+    }
+}
+
+/// What a subscriber receives next
+enum Delivery {
+    Event(Arc<StreamEvent>),
+    /// Events were missed and can't be replayed; carries the id to resume from
+    Reset(i64),
+}
+
+impl Delivery {
+    fn to_json(&self) -> serde_json::Value {
+        match self {
+            Delivery::Event(e) => json!(e),
+            Delivery::Reset(id) => json!({ "kind": "reset", "id": id }),
+        }
+    }
+}
+
+fn sse_event(delivery: &Delivery) -> Event {
+    let event = match delivery {
+        Delivery::Event(e) => Event::default()
+            .id(e.id.to_string())
+            .event(&e.kind)
+            .json_data(json!({ "topic": e.topic, "payload": e.payload, "created_at": e.created_at })),
+        Delivery::Reset(id) => Event::default().id(id.to_string()).event("reset").json_data(json!({ "id": id })),
+    };
+    event.unwrap_or_default()
+}
+
+/// One connection's view of the bus
+/// Events come from the broadcast channel; when the connection resumes or
+/// falls more than BUS_CAPACITY behind, the gap is filled from
+/// `stream_events` first. Ids only increase, so anything at or below the
+/// last delivered id is a duplicate.
+struct Subscription {
+    topics: BTreeSet<String>,
+    receiver: broadcast::Receiver<Arc<StreamEvent>>,
+    last_id: i64,
+    backlog: VecDeque<Arc<StreamEvent>>,
+    /// Fill the gap before reading the channel again
+    behind: bool,
+    reset: bool,
+}
+
+impl Subscription {
+    async fn start(state: &AppState, topics: BTreeSet<String>, resume: Option<i64>) -> Result<Self, sqlx::Error> {
+        // Subscribe before reading the position so nothing falls in between
+        let receiver = state.stream.subscribe();
+        let (_, latest) = stream_bus::bounds(&state.pool).await?;
+
+        Ok(Subscription {
+            topics,
+            receiver,
+            last_id: resume.map_or(latest, |r| r.clamp(0, latest)),
+            backlog: VecDeque::new(),
+            behind: resume.is_some(),
+            reset: false,
+        })
+    }
+
+    /// The next delivery, or None once the bus is gone
+    async fn next(&mut self, pool: &SqlitePool) -> Option<Delivery> {
+        loop {
+            if self.reset {
+                self.reset = false;
+                return Some(Delivery::Reset(self.last_id));
+            }
+            if let Some(event) = self.backlog.pop_front() {
+                self.last_id = event.id;
+                return Some(Delivery::Event(event));
+            }
+            if self.behind {
+                if let Err(e) = self.catch_up(pool).await {
+                    tracing::error!("Failed to replay stream events: {}", e);
+                    self.skip_to_latest(pool).await;
+                }
+                self.behind = false;
+                continue;
+            }
+
+            match self.receiver.recv().await {
+                Ok(event) if event.id > self.last_id && self.topics.contains(&event.topic) => {
+                    self.last_id = event.id;
+                    return Some(Delivery::Event(event));
+                }
+                Ok(_) => {}
+                Err(RecvError::Lagged(skipped)) => {
+                    tracing::debug!("Stream subscriber lagged by {} events", skipped);
+                    self.behind = true;
+                }
+                Err(RecvError::Closed) => return None,
+            }
+        }
+    }
+
+    /// Queue the events since `last_id` from the table, or reset if they were
+    /// pruned or are too many to replay
+    async fn catch_up(&mut self, pool: &SqlitePool) -> Result<(), sqlx::Error> {
+        let (oldest, latest) = stream_bus::bounds(pool).await?;
+        let pruned = self.last_id < latest && oldest.is_none_or(|o| self.last_id + 1 < o);
+
+        let topics: Vec<String> = self.topics.iter().cloned().collect();
+        let missed = stream_bus::replay(pool, &topics, self.last_id, REPLAY_LIMIT + 1).await?;
+        if pruned || missed.len() as i64 > REPLAY_LIMIT {
+            self.skip_to(latest);
+            return Ok(());
+        }
+
+        self.backlog.extend(missed.into_iter().map(Arc::new));
+        Ok(())
+    }
+
+    async fn skip_to_latest(&mut self, pool: &SqlitePool) {
+        let latest = stream_bus::bounds(pool).await.map(|(_, l)| l).unwrap_or(self.last_id);
+        self.skip_to(latest);
+    }
+
+    fn skip_to(&mut self, id: i64) {
+        self.backlog.clear();
+        self.last_id = self.last_id.max(id);
+        self.reset = true;
+    }
+
+    /// Apply a WebSocket command; the reply frame says what is followed now
+    fn command(&mut self, text: &str) -> serde_json::Value {
+        let command = match serde_json::from_str::<StreamCommand>(text) {
+            Ok(c) => c,
+            Err(_) => return json!({ "error": "expected {\"subscribe\": [...]} or {\"unsubscribe\": [...]}" }),
+        };
+
+        match command {
+            StreamCommand::Subscribe(raw) => match parse_topics(raw.iter().map(|s| s.as_str())) {
+                Ok(topics) if self.topics.len() + topics.len() <= MAX_TOPICS => self.topics.extend(topics),
+                Ok(_) => return json!({ "error": format!("at most {} topics", MAX_TOPICS) }),
+                Err(e) => return json!({ "error": e }),
+            },
+            StreamCommand::Unsubscribe(raw) => {
+                for topic in parse_topics(raw.iter().map(|s| s.as_str())).unwrap_or_default() {
+                    self.topics.remove(&topic);
+                }
+            }
+        }
+
+        json!({ "kind": "subscribed", "topics": self.topics })
+    }
+}
+
+fn parse_topics<'a>(raw: impl Iterator<Item = &'a str>) -> Result<BTreeSet<String>, String> {
+    let topics = raw
+        .map(str::trim)
+        .filter(|t| !t.is_empty())
+        .map(parse_topic)
+        .collect::<Result<BTreeSet<_>, _>>()?;
+    if topics.len() > MAX_TOPICS {
+        return Err(format!("at most {} topics", MAX_TOPICS));
+    }
+    Ok(topics)
+}
+
+/// Normalize a topic; profile addresses are matched in canonical form
+fn parse_topic(raw: &str) -> Result<String, String> {
+    if raw == stream::FEED {
+        return Ok(raw.to_string());
+    }
+
+    let valid_id = |id: &str| !id.is_empty() && id.len() <= MAX_ID_LEN;
+    match raw.split_once(':') {
+        Some(("post", id)) if valid_id(id) => Ok(stream::post_topic(id)),
+        Some(("claim", id)) if valid_id(id) => Ok(stream::claim_topic(id)),
+        Some(("profile", address)) => SuiAddress::parse(address)
+            .map(|a| stream::profile_topic(a.as_str()))
+            .map_err(|e| e.to_string()),
+        _ => Err(format!("unknown topic {:?}: expected feed, post:<id>, claim:<id> or profile:<address>", raw)),
+    }
+}
@@ -0,0 +1,210 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Json,
+};
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use serde_json::json;
+use sqlx::Row;
+use std::sync::Arc;
+use suiter_core::address::SuiAddress;
+
+use crate::{
+    extract::ApiPath,
+    handlers::{sponsor, tx::TxError},
+    models::{self, SubmitTxRequest, TransactionsQuery},
+    sui_rpc::Execution,
+    sui_tx::{self, Address, TransactionData},
+    AppState,
+};
+
+const DEFAULT_PAGE: i64 = 50;
+const MAX_PAGE: i64 = 200;
+const STATUSES: &[&str] = &["pending", "confirmed", "failed"];
+
+/// `POST /api/tx/submit`
+/// Executes a signed transaction built by `/api/tx/...` and tracks it in
+/// `pending_transactions` until the indexer sees it land. Aborted
+/// transactions are recorded as failed straight away; ones the node refuses
+/// outright are not recorded.
+pub async fn submit_tx(
+    State(state): State<Arc<AppState>>,
+    Json(request): Json<SubmitTxRequest>,
+) -> (StatusCode, Json<serde_json::Value>) {
+    match submit(&state, &request).await {
+        Ok(submitted) => (StatusCode::OK, Json(submitted)),
+        Err(e) => e.into_response(),
+    }
+}
+
+async fn submit(state: &AppState, request: &SubmitTxRequest) -> Result<serde_json::Value, TxError> {
+    let tx_bytes = BASE64
+        .decode(request.tx_bytes.trim())
+        .map_err(|_| TxError::BadRequest("tx_bytes must be base64".to_string()))?;
+    let tx_data = TransactionData::from_bytes(&tx_bytes)
+        .ok_or_else(|| TxError::BadRequest("tx_bytes is not a transaction built by this API".to_string()))?;
+    let package = state
+        .package_id
+        .as_deref()
+        .and_then(Address::parse)
+        .ok_or(TxError::NotConfigured("PACKAGE_ID"))?;
+    let Some((call, inputs)) = tx_data.move_call(package) else {
+        return Err(TxError::BadRequest("transaction calls no suiter function".to_string()));
+    };
+    if request.signatures.is_empty() {
+        return Err(TxError::BadRequest("signatures required".to_string()));
+    }
+
+    let function = format!("{}::{}", call.module, models::public_function(&call.function));
+    let target_id = target_input(&function)
+        .and_then(|i| inputs.get(i).copied().flatten())
+        .and_then(|arg| arg.address())
+        .map(|a| a.to_string());
+    let sender = tx_data.sender().to_string();
+
+    // Sponsored transactions count against their quotas from here on
+    let local_digest = sui_tx::digest(&tx_bytes);
+    if !sponsor::claim_submission(&state.pool, &local_digest).await? {
+        return Err(TxError::Exhausted("sponsorship_quota_exhausted"));
+    }
+
+    let execution = match state.sui.execute(&tx_bytes, &request.signatures).await {
+        Ok(execution) => execution,
+        Err(e) => {
+            sponsor::release_submission(&state.pool, &local_digest).await?;
+            return Err(e.into());
+        }
+    };
+    let (digest, error) = match execution {
+        Execution::Executed { digest, error } => (digest, error),
+        Execution::Rejected(reason) => {
+            sponsor::release_submission(&state.pool, &local_digest).await?;
+            return Err(TxError::BadRequest(format!("transaction rejected: {}", reason)));
+        }
+    };
+    if digest != local_digest {
+        tracing::warn!("Node returned digest {} for {}", digest, local_digest);
+    }
+
+    let status = if error.is_some() { "failed" } else { "pending" };
+    sqlx::query(
+        r#"
+        INSERT INTO pending_transactions(digest, sender, function, target_id, status, error, client_nonce, submitted_at, resolved_at)
+        VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, CURRENT_TIMESTAMP, CASE WHEN ?5 = 'failed' THEN CURRENT_TIMESTAMP END)
+        ON CONFLICT (digest) DO NOTHING
+        "#
+    )
+    .bind(&digest)
+    .bind(&sender)
+    .bind(&function)
+    .bind(&target_id)
+    .bind(status)
+    .bind(&error)
+    .bind(&request.nonce)
+    .execute(&state.pool)
+    .await?;
+
+    tracing::info!("Submitted {} for {}: {} ({})", function, sender, digest, status);
+
+    Ok(json!({
+        "digest": digest,
+        "sender": sender,
+        "function": function,
+        "target_id": target_id,
+        "status": status,
+        "error": error,
+    }))
+}
+
+/// `GET /api/tx/status/:digest`
+pub async fn get_tx_status(
+    State(state): State<Arc<AppState>>,
+    Path(digest): Path<String>,
+) -> (StatusCode, Json<serde_json::Value>) {
+    let row = sqlx::query("SELECT * FROM pending_transactions WHERE digest = ?")
+        .bind(&digest)
+        .fetch_optional(&state.pool)
+        .await;
+
+    match row {
+        Ok(Some(r)) => (StatusCode::OK, Json(transaction_json(&r))),
+        Ok(None) => (StatusCode::NOT_FOUND, Json(json!({ "error": "transaction not found" }))),
+        Err(e) => {
+            tracing::error!("DB error: {}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({ "error": "db error" })))
+        }
+    }
+}
+
+/// `GET /api/profiles/:address/transactions?status=`
+/// The address's submitted transactions, newest first, so clients can show
+/// optimistic posts, votes and claims until they confirm or fail
+pub async fn list_transactions(
+    State(state): State<Arc<AppState>>,
+    ApiPath(address): ApiPath<SuiAddress>,
+    Query(query): Query<TransactionsQuery>,
+) -> (StatusCode, Json<serde_json::Value>) {
+    if let Some(status) = &query.status {
+        if !STATUSES.contains(&status.as_str()) {
+            return (StatusCode::BAD_REQUEST, Json(json!({ "error": "status must be pending, confirmed or failed" })));
+        }
+    }
+    let limit = query.limit.unwrap_or(DEFAULT_PAGE).clamp(1, MAX_PAGE);
+
+    let rows = sqlx::query(
+        r#"
+        SELECT * FROM pending_transactions
+        WHERE sender = ?1 AND (?2 IS NULL OR status = ?2)
+        ORDER BY submitted_at DESC
+        LIMIT ?3
+        "#
+    )
+    .bind(&address)
+    .bind(&query.status)
+    .bind(limit)
+    .fetch_all(&state.pool)
+    .await;
+
+    match rows {
+        Ok(rows) => {
+            let transactions: Vec<serde_json::Value> = rows.iter().map(transaction_json).collect();
+            (StatusCode::OK, Json(json!({
+                "address": address,
+                "transactions": transactions,
+            })))
+        }
+        Err(e) => {
+            tracing::error!("DB error: {}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({ "error": "db error" })))
+        }
+    }
+}
+
+fn transaction_json(r: &sqlx::sqlite::SqliteRow) -> serde_json::Value {
+    json!({
+        "digest": r.get::<String, _>("digest"),
+        "sender": r.get::<String, _>("sender"),
+        "function": r.get::<String, _>("function"),
+        "target_id": r.get::<Option<String>, _>("target_id"),
+        "object_id": r.get::<Option<String>, _>("object_id"),
+        "status": r.get::<String, _>("status"),
+        "error": r.get::<Option<String>, _>("error"),
+        "submitted_at": r.get::<String, _>("submitted_at"),
+        "resolved_at": r.get::<Option<String>, _>("resolved_at"),
+    })
+}
+
+/// Which move call argument names the object a call acts on
+fn target_input(function: &str) -> Option<usize> {
+    match function {
+        "attention::start_session" => Some(1),
+        "attention::end_session"
+        | "attention::claim_reward"
+        | "attention::fund_pool"
+        | "truth_claim::create_claim"
+        | "truth_claim::vote_on_claim"
+        | "truth_claim::resolve_claim"
+        | "creator_lifeline::send_support" => Some(0),
+        _ => None,
+    }
+}
@@ -0,0 +1,159 @@
+use axum::{extract::State, http::StatusCode, Json};
+use serde_json::json;
+use sqlx::Row;
+use std::sync::Arc;
+use suiter_core::tags;
+
+use crate::{
+    extract::{ApiPath, ApiQuery},
+    models::{TagFeedQuery, TrendingTagsQuery},
+    AppState,
+};
+
+const DEFAULT_PAGE: i64 = 20;
+const MAX_PAGE: i64 = 100;
+/// Deepest post reachable by paging
+const MAX_OFFSET: i64 = 1000;
+const DEFAULT_TRENDING: i64 = 10;
+const MAX_TRENDING: i64 = 50;
+const DEFAULT_WINDOW_HOURS: i64 = 6;
+const MAX_WINDOW_HOURS: i64 = 168;
+
+/// `GET /api/tags/:tag/feed?limit=&offset=`
+/// Posts carrying the tag, ranked by their FeedRanker score like the global
+/// feed. `:tag` is matched case-insensitively, with or without its `#`.
+pub async fn get_tag_feed(
+    State(state): State<Arc<AppState>>,
+    ApiPath(tag): ApiPath<String>,
+    ApiQuery(query): ApiQuery<TagFeedQuery>,
+) -> (StatusCode, Json<serde_json::Value>) {
+    let pool = &state.pool;
+
+    let Some(tag) = tags::normalize(&tag) else {
+        return (StatusCode::BAD_REQUEST, Json(json!({ "error": "invalid tag" })));
+    };
+    let limit = query.limit.unwrap_or(DEFAULT_PAGE).clamp(1, MAX_PAGE);
+    let offset = query.offset.unwrap_or(0).clamp(0, MAX_OFFSET);
+
+    let posts = sqlx::query(
+        r#"
+        SELECT p.id, p.author, p.content_hash, p.level, p.attention_accumulated, p.provisional, p.created_at,
+            CAST(COALESCE(fr.score, 0.0) AS REAL) as score
+        FROM post_tags t
+        JOIN posts p ON p.id = t.post_id
+        LEFT JOIN feed_rankings fr ON fr.post_id = p.id
+        WHERE t.tag = ?
+        ORDER BY CAST(COALESCE(fr.score, 0.0) AS REAL) DESC, p.created_at DESC, p.id
+        LIMIT ? OFFSET ?
+        "#
+    )
+    .bind(&tag)
+    .bind(limit)
+    .bind(offset)
+    .fetch_all(pool)
+    .await;
+
+    let total: Result<i64, _> = sqlx::query_scalar("SELECT COUNT(*) FROM post_tags WHERE tag = ?")
+        .bind(&tag)
+        .fetch_one(pool)
+        .await;
+
+    match (posts, total) {
+        (Ok(rows), Ok(total)) => {
+            let next_offset = offset + rows.len() as i64;
+            let posts: Vec<serde_json::Value> = rows
+                .iter()
+                .map(|r| {
+                    json!({
+                        "id": r.get::<String, _>("id"),
+                        "author": r.get::<String, _>("author"),
+                        "content_hash": r.get::<String, _>("content_hash"),
+                        "level": r.get::<i64, _>("level"),
+                        "attention_accumulated": r.get::<i64, _>("attention_accumulated"),
+                        "provisional": r.get::<bool, _>("provisional"),
+                        "created_at": r.get::<String, _>("created_at"),
+                        "score": r.get::<f64, _>("score"),
+                    })
+                })
+                .collect();
+
+            (StatusCode::OK, Json(json!({
+                "tag": tag,
+                "posts": posts,
+                "total": total,
+                "next_offset": (next_offset < total && next_offset <= MAX_OFFSET).then_some(next_offset),
+            })))
+        }
+        (Err(e), _) | (_, Err(e)) => {
+            tracing::error!("DB error: {}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({ "error": "db error" })))
+        }
+    }
+}
+
+/// `GET /api/tags/trending?window_hours=&limit=`
+/// Tags ranked by attention velocity: valid attention their posts received
+/// per hour over the last `window_hours`. Ties go to the tag accelerating
+/// most against the window before.
+pub async fn get_trending_tags(
+    State(state): State<Arc<AppState>>,
+    ApiQuery(query): ApiQuery<TrendingTagsQuery>,
+) -> (StatusCode, Json<serde_json::Value>) {
+    let pool = &state.pool;
+
+    let window_hours = query.window_hours.unwrap_or(DEFAULT_WINDOW_HOURS).clamp(1, MAX_WINDOW_HOURS);
+    let limit = query.limit.unwrap_or(DEFAULT_TRENDING).clamp(1, MAX_TRENDING);
+
+    // Session end times are unix milliseconds
+    let window_ms = window_hours * 3_600_000;
+    let window_start = chrono::Utc::now().timestamp_millis() - window_ms;
+
+    let rows = sqlx::query(
+        r#"
+        SELECT t.tag,
+            SUM(CASE WHEN s.ended_at >= ?1 THEN s.duration_ms ELSE 0 END) as recent_ms,
+            SUM(CASE WHEN s.ended_at < ?1 THEN s.duration_ms ELSE 0 END) as previous_ms,
+            COUNT(DISTINCT CASE WHEN s.ended_at >= ?1 THEN s.post_id END) as posts,
+            COUNT(DISTINCT CASE WHEN s.ended_at >= ?1 THEN s.reader END) as readers
+        FROM attention_sessions s
+        JOIN post_tags t ON t.post_id = s.post_id
+        WHERE s.validity = 'valid' AND s.ended_at >= ?2
+        GROUP BY t.tag
+        HAVING recent_ms > 0
+        ORDER BY recent_ms DESC, recent_ms - previous_ms DESC, t.tag
+        LIMIT ?3
+        "#
+    )
+    .bind(window_start)
+    .bind(window_start - window_ms)
+    .bind(limit)
+    .fetch_all(pool)
+    .await;
+
+    match rows {
+        Ok(rows) => {
+            let hours = window_hours as f64;
+            let trending: Vec<serde_json::Value> = rows
+                .iter()
+                .map(|r| {
+                    let velocity = r.get::<i64, _>("recent_ms") as f64 / hours;
+                    let previous = r.get::<i64, _>("previous_ms") as f64 / hours;
+                    json!({
+                        "tag": r.get::<String, _>("tag"),
+                        "velocity_ms_per_hour": velocity,
+                        "previous_velocity_ms_per_hour": previous,
+                        "acceleration": velocity - previous,
+                        "posts": r.get::<i64, _>("posts"),
+                        "readers": r.get::<i64, _>("readers"),
+                    })
+                })
+                .collect();
+
+            (StatusCode::OK, Json(json!({ "window_hours": window_hours, "tags": trending })))
+        }
+        Err(e) => {
+            tracing::error!("DB error: {}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({ "error": "db error" })))
+        }
+    }
+}
@@ -0,0 +1,382 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Json,
+};
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use serde_json::json;
+use std::sync::Arc;
+use suiter_core::{address::SuiAddress, reputation::STARTING_REPUTATION};
+
+use crate::{
+    models::{BuildTxRequest, TxCall},
+    sui_rpc::DryRun,
+    sui_tx::{Address, GasData, ObjectRef, ProgrammableTransaction, PtbBuilder, TransactionData},
+    AppState,
+};
+
+/// Budget for the estimating dry run, in MIST; capped at what the sender holds
+const DRY_RUN_BUDGET: u64 = 50_000_000;
+/// Extra computation units budgeted on top of the dry run, as the Sui SDKs do
+const GAS_SAFE_OVERHEAD: u64 = 1_000;
+
+/// Why a transaction couldn't be built
+#[derive(Debug)]
+pub enum TxError {
+    BadRequest(String),
+    NotConfigured(&'static str),
+    DryRunFailed(String),
+    /// A quota the request would exceed
+    Exhausted(&'static str),
+    Rpc(anyhow::Error),
+    Db(sqlx::Error),
+}
+
+impl TxError {
+    pub fn into_response(self) -> (StatusCode, Json<serde_json::Value>) {
+        match self {
+            TxError::BadRequest(msg) => (StatusCode::BAD_REQUEST, Json(json!({ "error": msg }))),
+            TxError::NotConfigured(what) => (StatusCode::SERVICE_UNAVAILABLE, Json(json!({ "error": format!("{} not configured", what) }))),
+            TxError::DryRunFailed(msg) => (StatusCode::UNPROCESSABLE_ENTITY, Json(json!({
+                "error": "dry run failed",
+                "dry_run_error": msg
+            }))),
+            TxError::Exhausted(what) => (StatusCode::TOO_MANY_REQUESTS, Json(json!({ "error": what }))),
+            TxError::Rpc(e) => {
+                tracing::error!("Sui RPC error: {}", e);
+                (StatusCode::BAD_GATEWAY, Json(json!({ "error": "rpc error" })))
+            }
+            TxError::Db(e) => {
+                tracing::error!("DB error: {}", e);
+                (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({ "error": "db error" })))
+            }
+        }
+    }
+}
+
+impl From<anyhow::Error> for TxError {
+    fn from(e: anyhow::Error) -> Self {
+        TxError::Rpc(e)
+    }
+}
+
+impl From<sqlx::Error> for TxError {
+    fn from(e: sqlx::Error) -> Self {
+        TxError::Db(e)
+    }
+}
+
+/// `POST /api/tx/:module/:function`
+/// Returns the unsigned, BCS-serialized transaction for one `suiter` entry
+/// point, paid from the sender's own coins with a budget estimated by dry
+/// run. The wallet only has to sign `tx_bytes`.
+pub async fn build_tx(
+    State(state): State<Arc<AppState>>,
+    Path((module, function)): Path<(String, String)>,
+    Json(body): Json<serde_json::Value>,
+) -> (StatusCode, Json<serde_json::Value>) {
+    let request = match parse_request(&module, &function, body) {
+        Ok(r) => r,
+        Err(response) => return response,
+    };
+
+    match build(&state, &request).await {
+        Ok(built) => (StatusCode::OK, Json(built)),
+        Err(e) => e.into_response(),
+    }
+}
+
+/// The path names the entry point, the body carries its arguments
+pub fn parse_request(
+    module: &str,
+    function: &str,
+    mut body: serde_json::Value,
+) -> Result<BuildTxRequest, (StatusCode, Json<serde_json::Value>)> {
+    if !TxCall::ENTRY_POINTS.contains(&(module, function)) {
+        return Err((StatusCode::NOT_FOUND, Json(json!({ "error": "unknown entry point" }))));
+    }
+
+    let Some(fields) = body.as_object_mut() else {
+        return Err((StatusCode::BAD_REQUEST, Json(json!({ "error": "body must be an object" }))));
+    };
+    fields.insert("function".to_string(), json!(format!("{}::{}", module, function)));
+
+    serde_json::from_value(body).map_err(|e| (StatusCode::BAD_REQUEST, Json(json!({ "error": e.to_string() }))))
+}
+
+async fn build(state: &AppState, request: &BuildTxRequest) -> Result<serde_json::Value, TxError> {
+    let sender = Address::from(&request.sender);
+    let ptb = compose(state, &request.sender, &request.call).await?;
+
+    let price = state.sui.reference_gas_price().await?;
+    let coins = state.sui.gas_coins(&sender).await?;
+    let (payment, available) = select_gas(coins, DRY_RUN_BUDGET);
+    if payment.is_empty() {
+        return Err(TxError::BadRequest("sender has no SUI to pay for gas".to_string()));
+    }
+
+    let (tx, dry_run, budget) = estimate(state, ptb, sender, sender, payment, price, available).await?;
+    let (module, function) = request.call.module_function();
+
+    Ok(json!({
+        "function": format!("{}::{}", module, function),
+        "sender": sender.to_string(),
+        "tx_bytes": BASE64.encode(tx.to_bytes()),
+        "gas": gas_json(&dry_run, price, budget),
+    }))
+}
+
+/// Dry-run the transaction and rebuild it with the budget the run implies.
+/// Returns the final transaction, the dry run and the budget.
+pub async fn estimate(
+    state: &AppState,
+    ptb: ProgrammableTransaction,
+    sender: Address,
+    gas_owner: Address,
+    payment: Vec<ObjectRef>,
+    price: u64,
+    available: u64,
+) -> Result<(TransactionData, DryRun, u64), TxError> {
+    let gas = |budget| GasData {
+        payment: payment.clone(),
+        owner: gas_owner,
+        price,
+        budget,
+    };
+
+    let probe = TransactionData::new(ptb.clone(), sender, gas(DRY_RUN_BUDGET.min(available)));
+    let dry_run = state.sui.dry_run(&probe.to_bytes()).await?;
+    if let Some(error) = dry_run.error {
+        return Err(TxError::DryRunFailed(error));
+    }
+
+    let budget = gas_budget(&dry_run, price);
+    if budget > available {
+        return Err(TxError::BadRequest(format!("gas budget {} exceeds the {} MIST available", budget, available)));
+    }
+
+    Ok((TransactionData::new(ptb, sender, gas(budget)), dry_run, budget))
+}
+
+pub fn gas_json(dry_run: &DryRun, price: u64, budget: u64) -> serde_json::Value {
+    json!({
+        "price": price,
+        "budget": budget,
+        "computation_cost": dry_run.computation_cost,
+        "storage_cost": dry_run.storage_cost,
+        "storage_rebate": dry_run.storage_rebate,
+    })
+}
+
+/// Largest coins first until `target` is covered; returns the coins and
+/// their total
+pub fn select_gas(coins: Vec<(ObjectRef, u64)>, target: u64) -> (Vec<ObjectRef>, u64) {
+    let mut payment = Vec::new();
+    let mut total = 0u64;
+    for (coin, balance) in coins {
+        if total >= target {
+            break;
+        }
+        payment.push(coin);
+        total = total.saturating_add(balance);
+    }
+    (payment, total)
+}
+
+/// Computation plus overhead, plus net storage if that's higher
+fn gas_budget(dry_run: &DryRun, price: u64) -> u64 {
+    let base = dry_run.computation_cost + GAS_SAFE_OVERHEAD * price;
+    (base + dry_run.storage_cost)
+        .saturating_sub(dry_run.storage_rebate)
+        .max(base)
+}
+
+/// The move call for `call`. Calls that create an object target its `entry`
+/// wrapper, which transfers or shares it; the package's structs are
+/// `key`-only, so the transaction itself can't transfer them.
+pub async fn compose(
+    state: &AppState,
+    sender_address: &SuiAddress,
+    call: &TxCall,
+) -> Result<ProgrammableTransaction, TxError> {
+    let sender = Address::from(sender_address);
+    let package = state
+        .package_id
+        .as_deref()
+        .and_then(Address::parse)
+        .ok_or(TxError::NotConfigured("PACKAGE_ID"))?;
+    let (module, _) = call.module_function();
+    let function = call.entry_function();
+    let now = chrono::Utc::now().timestamp() as u64;
+
+    let mut ptb = PtbBuilder::new();
+    let arguments = match call {
+        TxCall::CreateProfile => vec![],
+        TxCall::CreatePost { content_hash } => {
+            vec![ptb.pure(&sender), ptb.pure(&content_hash.as_bytes().to_vec())]
+        }
+        TxCall::StartSession { post_id } => {
+            let post_id = parse_address(post_id, "post_id")?;
+            let rep = reputation(state, sender_address.as_str()).await?;
+            vec![ptb.pure(&sender), ptb.pure(&post_id), ptb.pure(&rep)]
+        }
+        TxCall::EndSession { session_id } => {
+            let session = state.sui.object_arg(&parse_address(session_id, "session_id")?, true).await?;
+            vec![ptb.object(session), ptb.pure(&now)]
+        }
+        TxCall::ClaimReward { session_id, pool_id } => {
+            let session = state.sui.object_arg(&parse_address(session_id, "session_id")?, true).await?;
+            let pool = state.sui.object_arg(&parse_address(pool_id, "pool_id")?, true).await?;
+            vec![ptb.object(session), ptb.object(pool)]
+        }
+        TxCall::FundPool { pool_id, amount } => {
+            let pool = state.sui.object_arg(&parse_address(pool_id, "pool_id")?, true).await?;
+            let pool = ptb.object(pool);
+            let coin = ptb.split_gas(*amount);
+            vec![pool, ptb.coin_into_balance(coin)]
+        }
+        TxCall::CreateClaim { post_id, claim_text } => {
+            let post_id = parse_address(post_id, "post_id")?;
+            let rep = reputation(state, sender_address.as_str()).await?;
+            vec![ptb.pure(&post_id), ptb.pure(&sender), ptb.pure(&claim_text.as_bytes().to_vec()), ptb.pure(&rep)]
+        }
+        TxCall::VoteOnClaim { claim_id, vote } => {
+            let claim = state.sui.object_arg(&parse_address(claim_id, "claim_id")?, true).await?;
+            let rep = reputation(state, sender_address.as_str()).await?;
+            vec![ptb.object(claim), ptb.pure(&sender), ptb.pure(vote), ptb.pure(&rep), ptb.pure(&now)]
+        }
+        TxCall::ResolveClaim { claim_id } => {
+            let claim = state.sui.object_arg(&parse_address(claim_id, "claim_id")?, true).await?;
+            vec![ptb.object(claim), ptb.pure(&now)]
+        }
+        TxCall::CreateLifeline => {
+            let rep = reputation(state, sender_address.as_str()).await?;
+            vec![ptb.pure(&sender), ptb.pure(&rep)]
+        }
+        TxCall::SendSupport { lifeline_id, amount } => {
+            let lifeline = state.sui.object_arg(&parse_address(lifeline_id, "lifeline_id")?, true).await?;
+            let lifeline = ptb.object(lifeline);
+            let coin = ptb.split_gas(*amount);
+            vec![lifeline, ptb.pure(&sender), ptb.coin_into_balance(coin)]
+        }
+    };
+
+    ptb.move_call(package, module, &function, vec![], arguments);
+
+    Ok(ptb.finish())
+}
+
+/// The contract takes reputation as an argument; use what we've indexed
+async fn reputation(state: &AppState, address: &str) -> Result<u64, TxError> {
+    let rep: Option<i64> = sqlx::query_scalar("SELECT reputation FROM profiles WHERE address = ?")
+        .bind(address)
+        .fetch_optional(&state.pool)
+        .await?;
+
+    Ok(rep.unwrap_or(STARTING_REPUTATION).max(0) as u64)
+}
+
+pub fn parse_address(value: &str, field: &str) -> Result<Address, TxError> {
+    Address::parse(value.trim()).ok_or_else(|| TxError::BadRequest(format!("invalid {}", field)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{models, sui_tx::Digest};
+
+    fn coin(n: u8, balance: u64) -> (ObjectRef, u64) {
+        let id = Address::parse(&format!("0x{:x}", n)).unwrap();
+        let digest = Digest::parse(&"1".repeat(32)).unwrap();
+        ((id, 1, digest), balance)
+    }
+
+    fn dry_run(computation_cost: u64, storage_cost: u64, storage_rebate: u64) -> DryRun {
+        DryRun { error: None, computation_cost, storage_cost, storage_rebate }
+    }
+
+    #[test]
+    fn select_gas_stops_once_the_target_is_covered() {
+        let (payment, total) = select_gas(vec![coin(1, 30), coin(2, 30), coin(3, 30)], 50);
+        assert_eq!(payment.len(), 2);
+        assert_eq!(total, 60);
+    }
+
+    #[test]
+    fn select_gas_returns_everything_when_short() {
+        let (payment, total) = select_gas(vec![coin(1, 10), coin(2, 10)], 50);
+        assert_eq!(payment.len(), 2);
+        assert_eq!(total, 20);
+
+        let (payment, total) = select_gas(vec![], 50);
+        assert!(payment.is_empty());
+        assert_eq!(total, 0);
+    }
+
+    #[test]
+    fn select_gas_saturates_instead_of_overflowing() {
+        let (_, total) = select_gas(vec![coin(1, u64::MAX - 1), coin(2, 0)], u64::MAX);
+        assert_eq!(total, u64::MAX - 1);
+        let (_, total) = select_gas(vec![coin(1, u64::MAX - 1), coin(2, 10)], u64::MAX);
+        assert_eq!(total, u64::MAX);
+    }
+
+    #[test]
+    fn gas_budget_adds_overhead_and_net_storage() {
+        let price = 1_000;
+        let base = 2_000_000 + GAS_SAFE_OVERHEAD * price;
+
+        assert_eq!(gas_budget(&dry_run(2_000_000, 500_000, 100_000), price), base + 400_000);
+        // A rebate larger than the storage cost never lowers the budget below computation
+        assert_eq!(gas_budget(&dry_run(2_000_000, 100_000, 5_000_000), price), base);
+    }
+
+    #[test]
+    fn constructors_target_their_entry_wrappers() {
+        assert_eq!(TxCall::CreateProfile.entry_function(), "create_profile_entry");
+        assert_eq!(TxCall::CreateLifeline.entry_function(), "create_lifeline_entry");
+        assert_eq!(TxCall::ResolveClaim { claim_id: String::new() }.entry_function(), "resolve_claim");
+
+        assert_eq!(models::public_function("create_claim_entry"), "create_claim");
+        assert_eq!(models::public_function("vote_on_claim"), "vote_on_claim");
+    }
+
+    #[test]
+    fn parse_request_knows_every_entry_point() {
+        let sender = "0x00000000000000000000000000000000000000000000000000000000000000a1";
+        let args = json!({
+            "sender": sender,
+            "content_hash": "h",
+            "post_id": "0xpost",
+            "session_id": "0xsession",
+            "pool_id": "0xpool",
+            "amount": 1,
+            "claim_text": "text",
+            "claim_id": "0xclaim",
+            "vote": true,
+            "lifeline_id": "0xlifeline",
+        });
+
+        for &(module, function) in TxCall::ENTRY_POINTS {
+            let request = parse_request(module, function, args.clone()).unwrap();
+            assert_eq!(request.call.module_function(), (module, function));
+        }
+    }
+
+    #[test]
+    fn parse_request_tells_unknown_entry_points_from_bad_arguments() {
+        let sender = "0x00000000000000000000000000000000000000000000000000000000000000a1";
+        let status = |module: &str, function: &str, body: serde_json::Value| parse_request(module, function, body).err().map(|(s, _)| s);
+
+        assert_eq!(status("attention", "drain_pool", json!({ "sender": sender })), Some(StatusCode::NOT_FOUND));
+        assert_eq!(status("profile", "create_post", json!({ "sender": sender })), Some(StatusCode::NOT_FOUND));
+        // Checked before the body, so a bad body doesn't hide an unknown function
+        assert_eq!(status("nope", "nope", json!([])), Some(StatusCode::NOT_FOUND));
+
+        assert_eq!(status("post", "create_post", json!({ "sender": sender })), Some(StatusCode::BAD_REQUEST));
+        assert_eq!(status("post", "create_post", json!({ "sender": sender, "content_hash": 5 })), Some(StatusCode::BAD_REQUEST));
+        assert_eq!(status("profile", "create_profile", json!({ "sender": "0xnot-hex" })), Some(StatusCode::BAD_REQUEST));
+        assert_eq!(status("profile", "create_profile", json!([])), Some(StatusCode::BAD_REQUEST));
+        assert_eq!(status("profile", "create_profile", json!({ "sender": sender })), None);
+    }
+}
@@ -0,0 +1,531 @@
+use axum::{
+    extract::State,
+    http::{header, HeaderMap, StatusCode},
+    Json,
+};
+use blake2::Digest as _;
+use serde_json::json;
+use sqlx::{sqlite::SqliteRow, Row, SqlitePool};
+use std::sync::Arc;
+use suiter_core::webhooks::Event;
+use uuid::Uuid;
+
+use super::notifications::validate_webhook_url;
+use crate::{
+    extract::{ApiJson, ApiPath, ApiQuery},
+    models::{CreateWebhookRequest, DeliveriesQuery, OwnerRequest, UpdateWebhookRequest},
+    sui_tx::Blake2b256,
+    AppState,
+};
+
+/// Webhooks one address may register
+const MAX_WEBHOOKS: i64 = 10;
+const DEFAULT_PAGE: i64 = 50;
+const MAX_PAGE: i64 = 200;
+const DELIVERY_STATUSES: [&str; 3] = ["pending", "delivered", "dead"];
+
+const WEBHOOK_COLUMNS: &str = "id, owner, url, events, active, created_at, updated_at";
+
+/// `POST /api/webhooks`
+/// The response carries the signing secret and the management token. The
+/// token is sent as `Authorization: Bearer <token>` to change the webhook,
+/// rotate its secret or read its deliveries; neither is shown again, except
+/// the secret when rotated with the token.
+pub async fn create_webhook(
+    State(state): State<Arc<AppState>>,
+    ApiJson(payload): ApiJson<CreateWebhookRequest>,
+) -> (StatusCode, Json<serde_json::Value>) {
+    let pool = &state.pool;
+
+    if let Err(e) = validate_webhook_url(&payload.url).await {
+        return (StatusCode::BAD_REQUEST, Json(json!({ "error": e })));
+    }
+    let events = match parse_events(payload.events.as_deref()) {
+        Ok(e) => e,
+        Err(e) => return (StatusCode::BAD_REQUEST, Json(json!({ "error": e }))),
+    };
+
+    let registered: Result<i64, _> = sqlx::query_scalar("SELECT COUNT(*) FROM webhooks WHERE owner = ?")
+        .bind(&payload.owner)
+        .fetch_one(pool)
+        .await;
+    match registered {
+        Ok(n) if n >= MAX_WEBHOOKS => {
+            return (StatusCode::CONFLICT, Json(json!({ "error": format!("at most {} webhooks per address", MAX_WEBHOOKS) })));
+        }
+        Ok(_) => {}
+        Err(e) => {
+            tracing::error!("DB error: {}", e);
+            return (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({ "error": "db error" })));
+        }
+    }
+
+    let id = Uuid::new_v4().to_string();
+    let secret = new_secret();
+    let token = format!("whtok_{}{}", Uuid::new_v4().simple(), Uuid::new_v4().simple());
+    let result: Result<(), sqlx::Error> = async {
+        let mut tx = pool.begin().await?;
+        sqlx::query("INSERT OR IGNORE INTO profiles(address, reputation, total_posts, total_attention_earned, joined_at, updated_at) VALUES (?, 50, 0, 0, CURRENT_TIMESTAMP, CURRENT_TIMESTAMP)")
+            .bind(&payload.owner)
+            .execute(&mut *tx)
+            .await?;
+        sqlx::query("INSERT INTO webhooks(id, owner, url, secret, token_hash, events, active, created_at, updated_at) VALUES (?, ?, ?, ?, ?, ?, TRUE, CURRENT_TIMESTAMP, CURRENT_TIMESTAMP)")
+            .bind(&id)
+            .bind(&payload.owner)
+            .bind(&payload.url)
+            .bind(&secret)
+            .bind(token_hash(&token))
+            .bind(&events)
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await
+    }
+    .await;
+
+    if let Err(e) = result {
+        tracing::error!("DB error: {}", e);
+        return (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({ "error": "db error" })));
+    }
+
+    match load_webhook(pool, &id).await {
+        Ok(Some(row)) => {
+            let mut webhook = webhook_json(&row);
+            webhook["secret"] = json!(secret);
+            webhook["token"] = json!(token);
+            (StatusCode::CREATED, Json(webhook))
+        }
+        Ok(None) => (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({ "error": "webhook not recorded" }))),
+        Err(e) => {
+            tracing::error!("DB error: {}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({ "error": "db error" })))
+        }
+    }
+}
+
+/// `GET /api/webhooks?owner=`
+pub async fn list_webhooks(
+    State(state): State<Arc<AppState>>,
+    ApiQuery(query): ApiQuery<OwnerRequest>,
+) -> (StatusCode, Json<serde_json::Value>) {
+    let rows = sqlx::query(&format!("SELECT {} FROM webhooks WHERE owner = ? ORDER BY created_at", WEBHOOK_COLUMNS))
+        .bind(&query.owner)
+        .fetch_all(&state.pool)
+        .await;
+
+    match rows {
+        Ok(rows) => (StatusCode::OK, Json(json!({
+            "owner": query.owner,
+            "webhooks": rows.iter().map(webhook_json).collect::<Vec<_>>(),
+        }))),
+        Err(e) => {
+            tracing::error!("DB error: {}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({ "error": "db error" })))
+        }
+    }
+}
+
+/// `GET /api/webhooks/:id`, with deliveries counted by status
+pub async fn get_webhook(
+    State(state): State<Arc<AppState>>,
+    ApiPath(id): ApiPath<String>,
+) -> (StatusCode, Json<serde_json::Value>) {
+    let pool = &state.pool;
+
+    let counts = sqlx::query("SELECT status, COUNT(*) as count FROM webhook_deliveries WHERE webhook_id = ? GROUP BY status")
+        .bind(&id)
+        .fetch_all(pool)
+        .await;
+
+    match (load_webhook(pool, &id).await, counts) {
+        (Ok(None), _) => (StatusCode::NOT_FOUND, Json(json!({ "error": "webhook not found" }))),
+        (Ok(Some(row)), Ok(counts)) => {
+            let mut deliveries = serde_json::Map::new();
+            for status in DELIVERY_STATUSES {
+                let count = counts
+                    .iter()
+                    .find(|r| r.get::<String, _>("status") == status)
+                    .map_or(0, |r| r.get::<i64, _>("count"));
+                deliveries.insert(status.to_string(), json!(count));
+            }
+            let mut webhook = webhook_json(&row);
+            webhook["deliveries"] = json!(deliveries);
+            (StatusCode::OK, Json(webhook))
+        }
+        (Err(e), _) | (_, Err(e)) => {
+            tracing::error!("DB error: {}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({ "error": "db error" })))
+        }
+    }
+}
+
+/// `PUT /api/webhooks/:id` with the management token
+/// Deactivating pauses deliveries; they're sent once it's active again.
+pub async fn update_webhook(
+    State(state): State<Arc<AppState>>,
+    ApiPath(id): ApiPath<String>,
+    headers: HeaderMap,
+    ApiJson(payload): ApiJson<UpdateWebhookRequest>,
+) -> (StatusCode, Json<serde_json::Value>) {
+    let pool = &state.pool;
+
+    if let Some(rejected) = authorize(pool, &id, &headers).await {
+        return rejected;
+    }
+    if let Some(url) = &payload.url {
+        if let Err(e) = validate_webhook_url(url).await {
+            return (StatusCode::BAD_REQUEST, Json(json!({ "error": e })));
+        }
+    }
+    let events = match payload.events.as_deref().map(|e| parse_events(Some(e))).transpose() {
+        Ok(e) => e,
+        Err(e) => return (StatusCode::BAD_REQUEST, Json(json!({ "error": e }))),
+    };
+
+    let updated = sqlx::query(
+        "UPDATE webhooks SET url = COALESCE(?, url), events = COALESCE(?, events), active = COALESCE(?, active), updated_at = CURRENT_TIMESTAMP WHERE id = ?"
+    )
+    .bind(&payload.url)
+    .bind(&events)
+    .bind(payload.active)
+    .bind(&id)
+    .execute(pool)
+    .await;
+    if let Err(e) = updated {
+        tracing::error!("DB error: {}", e);
+        return (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({ "error": "db error" })));
+    }
+
+    match load_webhook(pool, &id).await {
+        Ok(Some(row)) => (StatusCode::OK, Json(webhook_json(&row))),
+        Ok(None) => (StatusCode::NOT_FOUND, Json(json!({ "error": "webhook not found" }))),
+        Err(e) => {
+            tracing::error!("DB error: {}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({ "error": "db error" })))
+        }
+    }
+}
+
+/// `DELETE /api/webhooks/:id` with the management token; drops its
+/// delivery log too
+pub async fn delete_webhook(
+    State(state): State<Arc<AppState>>,
+    ApiPath(id): ApiPath<String>,
+    headers: HeaderMap,
+) -> (StatusCode, Json<serde_json::Value>) {
+    let pool = &state.pool;
+
+    if let Some(rejected) = authorize(pool, &id, &headers).await {
+        return rejected;
+    }
+
+    let result: Result<(), sqlx::Error> = async {
+        let mut tx = pool.begin().await?;
+        for table in ["webhook_dead_letters", "webhook_deliveries"] {
+            sqlx::query(&format!("DELETE FROM {} WHERE webhook_id = ?", table))
+                .bind(&id)
+                .execute(&mut *tx)
+                .await?;
+        }
+        sqlx::query("DELETE FROM webhooks WHERE id = ?")
+            .bind(&id)
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await
+    }
+    .await;
+
+    match result {
+        Ok(()) => (StatusCode::OK, Json(json!({ "id": id, "deleted": true }))),
+        Err(e) => {
+            tracing::error!("DB error: {}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({ "error": "db error" })))
+        }
+    }
+}
+
+/// `POST /api/webhooks/:id/secret` with the management token: replace the
+/// signing secret. Deliveries sent from now on use the new one.
+pub async fn rotate_secret(
+    State(state): State<Arc<AppState>>,
+    ApiPath(id): ApiPath<String>,
+    headers: HeaderMap,
+) -> (StatusCode, Json<serde_json::Value>) {
+    let pool = &state.pool;
+
+    if let Some(rejected) = authorize(pool, &id, &headers).await {
+        return rejected;
+    }
+
+    let secret = new_secret();
+    let result = sqlx::query("UPDATE webhooks SET secret = ?, updated_at = CURRENT_TIMESTAMP WHERE id = ?")
+        .bind(&secret)
+        .bind(&id)
+        .execute(pool)
+        .await;
+
+    match result {
+        Ok(_) => (StatusCode::OK, Json(json!({ "id": id, "secret": secret }))),
+        Err(e) => {
+            tracing::error!("DB error: {}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({ "error": "db error" })))
+        }
+    }
+}
+
+/// `GET /api/webhooks/:id/deliveries?status=&before=&limit=` with the
+/// management token
+/// The delivery log, newest first; page back with `next_before`.
+pub async fn list_deliveries(
+    State(state): State<Arc<AppState>>,
+    ApiPath(id): ApiPath<String>,
+    headers: HeaderMap,
+    ApiQuery(query): ApiQuery<DeliveriesQuery>,
+) -> (StatusCode, Json<serde_json::Value>) {
+    if let Some(rejected) = authorize(&state.pool, &id, &headers).await {
+        return rejected;
+    }
+    if let Some(status) = query.status.as_deref().filter(|s| !DELIVERY_STATUSES.contains(s)) {
+        return (StatusCode::BAD_REQUEST, Json(json!({
+            "error": format!("unknown status {:?}: expected one of {}", status, DELIVERY_STATUSES.join(", "))
+        })));
+    }
+    let limit = query.limit.unwrap_or(DEFAULT_PAGE).clamp(1, MAX_PAGE);
+
+    let rows = sqlx::query(
+        r#"
+        SELECT id, event, source_ref, payload, status, attempts, next_attempt_at, response_status, last_error, created_at, delivered_at
+        FROM webhook_deliveries
+        WHERE webhook_id = ?1 AND id < ?2 AND (?3 IS NULL OR status = ?3)
+        ORDER BY id DESC
+        LIMIT ?4
+        "#
+    )
+    .bind(&id)
+    .bind(query.before.unwrap_or(i64::MAX))
+    .bind(&query.status)
+    .bind(limit)
+    .fetch_all(&state.pool)
+    .await;
+
+    match rows {
+        Ok(rows) => (StatusCode::OK, Json(json!({
+            "webhook_id": id,
+            "deliveries": rows.iter().map(delivery_json).collect::<Vec<_>>(),
+            "next_before": next_before(&rows, limit, "id"),
+        }))),
+        Err(e) => {
+            tracing::error!("DB error: {}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({ "error": "db error" })))
+        }
+    }
+}
+
+/// `GET /api/webhooks/:id/dead-letters?before=&limit=` with the management
+/// token
+pub async fn list_dead_letters(
+    State(state): State<Arc<AppState>>,
+    ApiPath(id): ApiPath<String>,
+    headers: HeaderMap,
+    ApiQuery(query): ApiQuery<DeliveriesQuery>,
+) -> (StatusCode, Json<serde_json::Value>) {
+    if let Some(rejected) = authorize(&state.pool, &id, &headers).await {
+        return rejected;
+    }
+    let limit = query.limit.unwrap_or(DEFAULT_PAGE).clamp(1, MAX_PAGE);
+
+    let rows = sqlx::query(
+        "SELECT delivery_id, event, payload, attempts, last_error, dead_at FROM webhook_dead_letters WHERE webhook_id = ? AND delivery_id < ? ORDER BY delivery_id DESC LIMIT ?"
+    )
+    .bind(&id)
+    .bind(query.before.unwrap_or(i64::MAX))
+    .bind(limit)
+    .fetch_all(&state.pool)
+    .await;
+
+    match rows {
+        Ok(rows) => (StatusCode::OK, Json(json!({
+            "webhook_id": id,
+            "dead_letters": rows.iter().map(|r| json!({
+                "delivery_id": r.get::<i64, _>("delivery_id"),
+                "event": r.get::<String, _>("event"),
+                "payload": parse_payload(r),
+                "attempts": r.get::<i64, _>("attempts"),
+                "last_error": r.get::<Option<String>, _>("last_error"),
+                "dead_at": r.get::<String, _>("dead_at"),
+            })).collect::<Vec<_>>(),
+            "next_before": next_before(&rows, limit, "delivery_id"),
+        }))),
+        Err(e) => {
+            tracing::error!("DB error: {}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({ "error": "db error" })))
+        }
+    }
+}
+
+/// `POST /api/webhooks/:id/deliveries/:delivery_id/retry` with the
+/// management token
+/// Queue a dead or delivered delivery again with a fresh set of attempts;
+/// a dead one leaves the dead-letter table.
+pub async fn retry_delivery(
+    State(state): State<Arc<AppState>>,
+    ApiPath((id, delivery_id)): ApiPath<(String, i64)>,
+    headers: HeaderMap,
+) -> (StatusCode, Json<serde_json::Value>) {
+    let pool = &state.pool;
+
+    if let Some(rejected) = authorize(pool, &id, &headers).await {
+        return rejected;
+    }
+
+    let status: Result<Option<String>, _> = sqlx::query_scalar("SELECT status FROM webhook_deliveries WHERE id = ? AND webhook_id = ?")
+        .bind(delivery_id)
+        .bind(&id)
+        .fetch_optional(pool)
+        .await;
+    match status {
+        Ok(Some(s)) if s == "pending" => return (StatusCode::CONFLICT, Json(json!({ "error": "delivery already pending" }))),
+        Ok(Some(_)) => {}
+        Ok(None) => return (StatusCode::NOT_FOUND, Json(json!({ "error": "delivery not found" }))),
+        Err(e) => {
+            tracing::error!("DB error: {}", e);
+            return (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({ "error": "db error" })));
+        }
+    }
+
+    let result: Result<(), sqlx::Error> = async {
+        let mut tx = pool.begin().await?;
+        sqlx::query(
+            "UPDATE webhook_deliveries SET status = 'pending', attempts = 0, next_attempt_at = CURRENT_TIMESTAMP, delivered_at = NULL WHERE id = ? AND status != 'pending'"
+        )
+        .bind(delivery_id)
+        .execute(&mut *tx)
+        .await?;
+        sqlx::query("DELETE FROM webhook_dead_letters WHERE delivery_id = ?")
+            .bind(delivery_id)
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await
+    }
+    .await;
+
+    match result {
+        Ok(()) => (StatusCode::OK, Json(json!({ "id": delivery_id, "status": "pending" }))),
+        Err(e) => {
+            tracing::error!("DB error: {}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({ "error": "db error" })))
+        }
+    }
+}
+
+/// Validated, deduplicated event filter as stored; every event when omitted
+fn parse_events(events: Option<&[String]>) -> Result<String, String> {
+    let Some(events) = events else {
+        return Ok(Event::ALL.map(|e| e.as_str()).join(","));
+    };
+    if events.is_empty() {
+        return Err("events must not be empty".to_string());
+    }
+
+    let mut parsed = Vec::new();
+    for name in events {
+        match Event::parse(name) {
+            Some(e) => parsed.push(e),
+            None => {
+                let known: Vec<&str> = Event::ALL.iter().map(|e| e.as_str()).collect();
+                return Err(format!("unknown event {:?}: expected one of {}", name, known.join(", ")));
+            }
+        }
+    }
+    Ok(Event::ALL
+        .into_iter()
+        .filter(|e| parsed.contains(e))
+        .map(|e| e.as_str())
+        .collect::<Vec<_>>()
+        .join(","))
+}
+
+/// An error response unless the request carries webhook `id`'s management
+/// token as `Authorization: Bearer <token>`
+async fn authorize(pool: &SqlitePool, id: &str, headers: &HeaderMap) -> Option<(StatusCode, Json<serde_json::Value>)> {
+    let Some(token) = headers
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "))
+    else {
+        return Some((StatusCode::UNAUTHORIZED, Json(json!({ "error": "management token required" }))));
+    };
+
+    let found: Result<Option<Option<String>>, _> = sqlx::query_scalar("SELECT token_hash FROM webhooks WHERE id = ?")
+        .bind(id)
+        .fetch_optional(pool)
+        .await;
+
+    match found {
+        Ok(Some(Some(hash))) if hash == token_hash(token.trim()) => None,
+        Ok(Some(_)) => Some((StatusCode::FORBIDDEN, Json(json!({ "error": "invalid management token" })))),
+        Ok(None) => Some((StatusCode::NOT_FOUND, Json(json!({ "error": "webhook not found" })))),
+        Err(e) => {
+            tracing::error!("DB error: {}", e);
+            Some((StatusCode::INTERNAL_SERVER_ERROR, Json(json!({ "error": "db error" }))))
+        }
+    }
+}
+
+async fn load_webhook(pool: &SqlitePool, id: &str) -> Result<Option<SqliteRow>, sqlx::Error> {
+    sqlx::query(&format!("SELECT {} FROM webhooks WHERE id = ?", WEBHOOK_COLUMNS))
+        .bind(id)
+        .fetch_optional(pool)
+        .await
+}
+
+/// Only a hash of each management token is stored
+fn token_hash(token: &str) -> String {
+    hex::encode(Blake2b256::digest(token.as_bytes()))
+}
+
+/// Signing secret for a webhook; shared with notification webhooks
+pub(crate) fn new_secret() -> String {
+    format!("whsec_{}{}", Uuid::new_v4().simple(), Uuid::new_v4().simple())
+}
+
+fn webhook_json(r: &SqliteRow) -> serde_json::Value {
+    json!({
+        "id": r.get::<String, _>("id"),
+        "owner": r.get::<String, _>("owner"),
+        "url": r.get::<String, _>("url"),
+        "events": r.get::<String, _>("events").split(',').collect::<Vec<_>>(),
+        "active": r.get::<bool, _>("active"),
+        "created_at": r.get::<String, _>("created_at"),
+        "updated_at": r.get::<String, _>("updated_at"),
+    })
+}
+
+fn delivery_json(r: &SqliteRow) -> serde_json::Value {
+    let status: String = r.get("status");
+    json!({
+        "id": r.get::<i64, _>("id"),
+        "event": r.get::<String, _>("event"),
+        "source_ref": r.get::<String, _>("source_ref"),
+        "payload": parse_payload(r),
+        "attempts": r.get::<i64, _>("attempts"),
+        "next_attempt_at": (status == "pending").then(|| r.get::<String, _>("next_attempt_at")),
+        "status": status,
+        "response_status": r.get::<Option<i64>, _>("response_status"),
+        "last_error": r.get::<Option<String>, _>("last_error"),
+        "created_at": r.get::<String, _>("created_at"),
+        "delivered_at": r.get::<Option<String>, _>("delivered_at"),
+    })
+}
+
+fn parse_payload(r: &SqliteRow) -> serde_json::Value {
+    serde_json::from_str(&r.get::<String, _>("payload")).unwrap_or(serde_json::Value::Null)
+}
+
+/// Id to pass as `before` for the next page, when the page was full
+fn next_before(rows: &[SqliteRow], limit: i64, column: &str) -> Option<i64> {
+    if rows.len() as i64 == limit {
+        rows.last().map(|r| r.get::<i64, _>(column))
+    } else {
+        None
+    }
+}
@@ -0,0 +1,88 @@
+// ============ CONSTANTS (mirrors suiter::creator_lifeline) ============
+
+/// 0.01 SUI in MIST
+pub const MIN_SUPPORT_AMOUNT: i64 = 10_000_000;
+/// 100 SUI in MIST, per supporter per UTC day across all lifelines
+pub const MAX_DAILY_SUPPORT: i64 = 100_000_000_000;
+/// Min reputation to receive a lifeline
+pub const LIFELINE_THRESHOLD: i64 = 50;
+
+// ============ ACTIVITY DEADLINES (design.md §5.6) ============
+
+/// Creators commit to posting at least once every this many days
+pub const DEFAULT_CADENCE_DAYS: i64 = 7;
+pub const MIN_CADENCE_DAYS: i64 = 1;
+pub const MAX_CADENCE_DAYS: i64 = 30;
+
+/// Why a support payment was refused
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SupportRejection {
+    BelowMinimum,
+    DailyLimitExceeded,
+    SelfSupport,
+}
+
+impl SupportRejection {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SupportRejection::BelowMinimum => "below_min_support",
+            SupportRejection::DailyLimitExceeded => "daily_limit_exceeded",
+            SupportRejection::SelfSupport => "self_support",
+        }
+    }
+}
+
+/// Check a support payment given what the supporter already sent today
+pub fn check_support(
+    supporter: &str,
+    recipient: &str,
+    amount: i64,
+    sent_today: i64,
+) -> Result<(), SupportRejection> {
+    if supporter == recipient {
+        return Err(SupportRejection::SelfSupport);
+    }
+    if amount < MIN_SUPPORT_AMOUNT {
+        return Err(SupportRejection::BelowMinimum);
+    }
+    if sent_today.saturating_add(amount) > MAX_DAILY_SUPPORT {
+        return Err(SupportRejection::DailyLimitExceeded);
+    }
+    Ok(())
+}
+
+/// What the supporter can still send today
+pub fn remaining_today(sent_today: i64) -> i64 {
+    (MAX_DAILY_SUPPORT - sent_today).max(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn check_support_refuses_self_support_dust_and_overspending() {
+        assert_eq!(check_support("0xa", "0xb", MIN_SUPPORT_AMOUNT, 0), Ok(()));
+        assert_eq!(check_support("0xa", "0xa", MIN_SUPPORT_AMOUNT, 0), Err(SupportRejection::SelfSupport));
+        assert_eq!(check_support("0xa", "0xb", MIN_SUPPORT_AMOUNT - 1, 0), Err(SupportRejection::BelowMinimum));
+        // Self-support is reported before the amount is looked at
+        assert_eq!(check_support("0xa", "0xa", 0, MAX_DAILY_SUPPORT), Err(SupportRejection::SelfSupport));
+
+        // The limit is inclusive
+        assert_eq!(check_support("0xa", "0xb", MIN_SUPPORT_AMOUNT, MAX_DAILY_SUPPORT - MIN_SUPPORT_AMOUNT), Ok(()));
+        assert_eq!(
+            check_support("0xa", "0xb", MIN_SUPPORT_AMOUNT, MAX_DAILY_SUPPORT - MIN_SUPPORT_AMOUNT + 1),
+            Err(SupportRejection::DailyLimitExceeded)
+        );
+        assert_eq!(check_support("0xa", "0xb", MAX_DAILY_SUPPORT + 1, 0), Err(SupportRejection::DailyLimitExceeded));
+        assert_eq!(check_support("0xa", "0xb", i64::MAX, MAX_DAILY_SUPPORT), Err(SupportRejection::DailyLimitExceeded));
+    }
+
+    #[test]
+    fn remaining_today_never_goes_negative() {
+        assert_eq!(remaining_today(0), MAX_DAILY_SUPPORT);
+        assert_eq!(remaining_today(MAX_DAILY_SUPPORT - 1), 1);
+        assert_eq!(remaining_today(MAX_DAILY_SUPPORT), 0);
+        assert_eq!(remaining_today(MAX_DAILY_SUPPORT + 5), 0);
+    }
+}
@@ -1,22 +1,59 @@
 use axum::{
-    extract::{Path, Query},
-    http::StatusCode,
-    response::IntoResponse,
-    routing::{get, post},
-    Json, Router,
+    routing::{delete, get, post, put},
+    Router,
 };
-use serde::{Deserialize, Serialize};
 use sqlx::SqlitePool;
+use suiter_ranker::FeedRanker;
 use std::sync::Arc;
 use tower_http::cors::CorsLayer;
 use tracing::info;
 
+mod affinity;
+mod attention_rules;
+mod claim_rules;
+mod extract;
+mod feed_cache;
 mod handlers;
+mod lifeline_rules;
 mod models;
+mod settlement;
+mod sponsor;
+mod sponsor_rules;
+mod stream_bus;
+mod sui_rpc;
+mod sui_tx;
+mod treasury;
 
 /// Application state
 pub struct AppState {
     pub pool: SqlitePool,
+    pub feed_cache: feed_cache::FeedCache,
+    pub ranker: FeedRanker,
+    /// Scale rewards down as the attention pool nears depletion
+    pub reward_throttling: bool,
+    pub sui: sui_rpc::SuiRpc,
+    /// Published `suiter` package; transaction building is off without it
+    pub package_id: Option<String>,
+    /// Gas sponsor; sponsorship is off without a key
+    pub sponsor: Option<sponsor::Sponsor>,
+    /// Live events for `GET /api/stream`
+    pub stream: stream_bus::StreamBus,
+}
+
+/// State over a freshly migrated in-memory database, for handler tests
+#[cfg(test)]
+pub(crate) async fn test_state() -> Arc<AppState> {
+    let pool = suiter_core::testing::migrated_pool().await;
+    Arc::new(AppState {
+        pool: pool.clone(),
+        feed_cache: feed_cache::FeedCache::default(),
+        ranker: FeedRanker::new(pool.clone()),
+        reward_throttling: false,
+        sui: sui_rpc::SuiRpc::new("http://127.0.0.1:9".to_string()),
+        package_id: None,
+        sponsor: None,
+        stream: stream_bus::StreamBus::default(),
+    })
 }
 
 #[tokio::main]
@@ -31,6 +68,10 @@ async fn main() {
     // Load environment variables
     dotenv::dotenv().ok();
     let database_url = std::env::var("DATABASE_URL").expect("DATABASE_URL must be set");
+    let reward_throttling = std::env::var("REWARD_THROTTLING").is_ok_and(|v| v == "1" || v == "true");
+    let sui_rpc_url = std::env::var("SUI_RPC_URL")
+        .unwrap_or_else(|_| "https://fullnode.testnet.sui.io:443".to_string());
+    let package_id = std::env::var("PACKAGE_ID").ok();
 
     // Setup database
     let pool = sqlx::sqlite::SqlitePoolOptions::new()
@@ -39,7 +80,32 @@ async fn main() {
         .await
         .expect("Failed to connect to database");
 
-    let state = Arc::new(AppState { pool });
+    let state = Arc::new(AppState {
+        pool: pool.clone(),
+        feed_cache: feed_cache::FeedCache::default(),
+        ranker: FeedRanker::new(pool.clone()),
+        reward_throttling,
+        sui: sui_rpc::SuiRpc::new(sui_rpc_url),
+        package_id,
+        sponsor: sponsor::Sponsor::from_env(),
+        stream: stream_bus::StreamBus::default(),
+    });
+
+    // Resolve truth claims when voting ends and pay out their stakes
+    tokio::spawn(settlement::run(pool.clone()));
+
+    // Relay stream events from the API and the indexer to subscribers
+    let relay_state = state.clone();
+    let relay_pool = pool.clone();
+    tokio::spawn(async move {
+        relay_state.stream.run_relay(relay_pool).await;
+    });
+
+    // Keep the hot feed cache in sync with FeedRanker's ranking version
+    let refresher_state = state.clone();
+    tokio::spawn(async move {
+        refresher_state.feed_cache.run_refresher(pool).await;
+    });
 
     // Build router
     let app = Router::new()
@@ -48,21 +114,75 @@ async fn main() {
         .route("/api/posts", get(handlers::posts::get_feed))
         .route("/api/posts/:id", get(handlers::posts::get_post))
         .route("/api/posts/feed", get(handlers::posts::get_feed))
+        .route("/api/posts/:id/ranking", get(handlers::posts::get_ranking))
         
         // Profile endpoints
         .route("/api/profiles/:address", get(handlers::profiles::get_profile))
+        .route("/api/profiles/:address", put(handlers::profiles::update_profile))
         .route("/api/profiles/:address/reputation", get(handlers::profiles::get_reputation))
+        .route("/api/profiles/:address/balance", get(handlers::ledger::get_balance))
+        .route("/api/profiles/:address/payouts", get(handlers::ledger::get_payouts))
+        .route("/api/profiles/:address/earnings", get(handlers::ledger::get_earnings))
+        .route("/api/profiles/:address/refunds", get(handlers::lifelines::get_supporter_refunds))
         
         // Attention endpoints
         .route("/api/attention/session/start", post(handlers::attention::start_session))
+        .route("/api/attention/session/:id/heartbeat", post(handlers::attention::heartbeat))
         .route("/api/attention/session/:id/end", post(handlers::attention::end_session))
         .route("/api/attention/claim/:id", post(handlers::attention::claim_reward))
+        .route("/api/attention/pool", get(handlers::ledger::get_attention_pool))
+        // Unsigned transactions for wallets
+        .route("/api/tx/:module/:function", post(handlers::tx::build_tx))
+        .route("/api/tx/sponsor/:module/:function", post(handlers::sponsor::sponsor_tx))
+        .route("/api/tx/submit", post(handlers::submit::submit_tx))
+        .route("/api/tx/status/:digest", get(handlers::submit::get_tx_status))
+        .route("/api/profiles/:address/transactions", get(handlers::submit::list_transactions))
+        .route("/api/profiles/:address/sponsorship", get(handlers::sponsor::get_quota))
         
         // Truth claim endpoints
         .route("/api/claims", post(handlers::claims::create_claim))
         .route("/api/claims/:id", get(handlers::claims::get_claim))
         .route("/api/claims/:id/vote", post(handlers::claims::vote))
+
+        // Creator lifeline endpoints
+        .route("/api/lifelines", get(handlers::lifelines::list_lifelines))
+        .route("/api/lifelines", post(handlers::lifelines::create_lifeline))
+        .route("/api/lifelines/:id", get(handlers::lifelines::get_lifeline))
+        .route("/api/lifelines/:id/supporters", get(handlers::lifelines::get_supporters))
+        .route("/api/lifelines/:id/daily", get(handlers::lifelines::get_daily_totals))
+        .route("/api/lifelines/:id/support", post(handlers::lifelines::send_support))
+        .route("/api/lifelines/:id/support/:pending_id", get(handlers::lifelines::get_pending_support))
+        .route("/api/lifelines/:id/refunds", get(handlers::lifelines::get_lifeline_refunds))
+        .route("/api/creators/:address/dashboard", get(handlers::lifelines::get_dashboard))
         
+        // Search
+        .route("/api/search", get(handlers::search::search))
+
+        // Tags
+        .route("/api/tags/trending", get(handlers::tags::get_trending_tags))
+        .route("/api/tags/:tag/feed", get(handlers::tags::get_tag_feed))
+
+        // Live updates
+        .route("/api/stream", get(handlers::stream::stream))
+
+        // Notifications
+        .route("/api/notifications", get(handlers::notifications::list_notifications))
+        .route("/api/notifications/unread", get(handlers::notifications::get_unread))
+        .route("/api/notifications/read", post(handlers::notifications::mark_read))
+        .route("/api/notifications/preferences", get(handlers::notifications::get_preferences))
+        .route("/api/notifications/preferences", put(handlers::notifications::update_preferences))
+
+        // Outgoing webhooks
+        .route("/api/webhooks", post(handlers::webhooks::create_webhook))
+        .route("/api/webhooks", get(handlers::webhooks::list_webhooks))
+        .route("/api/webhooks/:id", get(handlers::webhooks::get_webhook))
+        .route("/api/webhooks/:id", put(handlers::webhooks::update_webhook))
+        .route("/api/webhooks/:id", delete(handlers::webhooks::delete_webhook))
+        .route("/api/webhooks/:id/secret", post(handlers::webhooks::rotate_secret))
+        .route("/api/webhooks/:id/deliveries", get(handlers::webhooks::list_deliveries))
+        .route("/api/webhooks/:id/deliveries/:delivery_id/retry", post(handlers::webhooks::retry_delivery))
+        .route("/api/webhooks/:id/dead-letters", get(handlers::webhooks::list_dead_letters))
+
         // Debug endpoints
         .route("/api/debug/health", get(handlers::debug::health))
         .route("/api/debug/stats", get(handlers::debug::stats))
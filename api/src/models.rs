@@ -1,57 +1,31 @@
 use serde::{Deserialize, Serialize};
+use suiter_core::address::SuiAddress;
+use std::collections::HashMap;
 
 // ============ PROFILE MODELS ============
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
-pub struct Profile {
-    pub address: String,
-    pub reputation: i64,
-    pub total_posts: i64,
-    pub total_attention_earned: i64,
+/// Body of `PUT /api/profiles/:address`; an empty or null name clears it
+#[derive(Debug, Deserialize)]
+pub struct UpdateProfileRequest {
+    pub display_name: Option<String>,
 }
 
 // ============ POST MODELS ============
 
-#[derive(Debug, Serialize, Deserialize)]
-pub struct CreatePostRequest {
-    pub author: String,
-    pub content_hash: String,
-}
-
-#[derive(Debug, Serialize, Deserialize, Clone)]
-pub struct Post {
-    pub id: String,
-    pub author: String,
-    pub level: i16,
-    pub attention_accumulated: i64,
-    pub reply_count: i64,
-}
-
-#[derive(Debug, Serialize, Deserialize)]
-pub struct FeedPost {
-    pub post: Post,
-    pub author_reputation: i64,
-    pub ranking_score: f64,
+#[derive(Debug, Deserialize)]
+pub struct FeedQuery {
+    /// Reader address; when set, the feed is personalized for them
+    pub reader: Option<SuiAddress>,
 }
 
 // ============ ATTENTION MODELS ============
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct StartSessionRequest {
-    pub reader: String,
+    pub reader: SuiAddress,
     pub post_id: String,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-pub struct AttentionSession {
-    pub id: String,
-    pub reader: String,
-    pub post_id: String,
-    pub duration_ms: i64,
-    pub reward: i64,
-    pub claimed: bool,
-}
-
 #[derive(Debug, Serialize, Deserialize)]
 pub struct EndSessionRequest {
     pub duration_ms: i64,
@@ -62,39 +36,298 @@ pub struct EndSessionRequest {
 #[derive(Debug, Serialize, Deserialize)]
 pub struct CreateClaimRequest {
     pub post_id: String,
-    pub claimer: String,
+    pub claimer: SuiAddress,
     pub claim_text: String,
-}
-
-#[derive(Debug, Serialize, Deserialize)]
-pub struct TruthClaim {
-    pub id: String,
-    pub post_id: String,
-    pub claimer: String,
-    pub votes_yes: i64,
-    pub votes_no: i64,
-    pub resolved: bool,
+    /// MIST locked until the claim settles
+    #[serde(default)]
+    pub stake: i64,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct VoteRequest {
-    pub voter: String,
+    pub voter: SuiAddress,
     pub vote: bool, // true = yes, false = no
+    /// Optional MIST put behind the vote; slashed if it loses
+    #[serde(default)]
+    pub stake: i64,
 }
 
-// ============ DEBUG MODELS ============
+// ============ LIFELINE MODELS ============
 
 #[derive(Debug, Serialize, Deserialize)]
-pub struct HealthStatus {
-    pub status: String,
-    pub database: String,
-    pub version: String,
+pub struct CreateLifelineRequest {
+    pub recipient: SuiAddress,
+    /// Posting cadence the creator commits to, in days
+    pub cadence_days: Option<i64>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
-pub struct Stats {
-    pub total_users: i64,
-    pub total_posts: i64,
-    pub total_attention_claimed: i64,
-    pub avg_reputation: f64,
+pub struct SupportRequest {
+    pub supporter: SuiAddress,
+    /// MIST
+    pub amount: i64,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct LifelineQuery {
+    pub recipient: Option<SuiAddress>,
+    pub active: Option<bool>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DailyQuery {
+    /// Number of UTC days, ending today
+    pub days: Option<i64>,
+}
+
+// ============ LEDGER MODELS ============
+
+#[derive(Debug, Deserialize)]
+pub struct LedgerQuery {
+    pub limit: Option<i64>,
+    /// Return entries older than this entry id
+    pub before: Option<i64>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct EarningsQuery {
+    /// Bucket size: "day" (default), "week" or "month"
+    pub period: Option<String>,
+    /// Number of most recent buckets
+    pub limit: Option<i64>,
+}
+
+// ============ TRANSACTION MODELS ============
+
+/// Body of `POST /api/tx/:module/:function`; the path supplies `function`
+#[derive(Debug, Deserialize)]
+pub struct BuildTxRequest {
+    pub sender: SuiAddress,
+    #[serde(flatten)]
+    pub call: TxCall,
+}
+
+/// A `suiter` entry point and the arguments the client supplies. Reputation,
+/// timestamps and the sender's own address are filled in by the server.
+#[derive(Debug, Deserialize)]
+#[serde(tag = "function")]
+pub enum TxCall {
+    #[serde(rename = "profile::create_profile")]
+    CreateProfile,
+    #[serde(rename = "post::create_post")]
+    CreatePost { content_hash: String },
+    #[serde(rename = "attention::start_session")]
+    StartSession { post_id: String },
+    #[serde(rename = "attention::end_session")]
+    EndSession { session_id: String },
+    #[serde(rename = "attention::claim_reward")]
+    ClaimReward { session_id: String, pool_id: String },
+    #[serde(rename = "attention::fund_pool")]
+    FundPool { pool_id: String, amount: u64 },
+    #[serde(rename = "truth_claim::create_claim")]
+    CreateClaim { post_id: String, claim_text: String },
+    #[serde(rename = "truth_claim::vote_on_claim")]
+    VoteOnClaim { claim_id: String, vote: bool },
+    #[serde(rename = "truth_claim::resolve_claim")]
+    ResolveClaim { claim_id: String },
+    #[serde(rename = "creator_lifeline::create_lifeline")]
+    CreateLifeline,
+    #[serde(rename = "creator_lifeline::send_support")]
+    SendSupport { lifeline_id: String, amount: u64 },
+}
+
+impl TxCall {
+    /// Every `module::function` a TxCall variant covers
+    pub const ENTRY_POINTS: &'static [(&'static str, &'static str)] = &[
+        ("profile", "create_profile"),
+        ("post", "create_post"),
+        ("attention", "start_session"),
+        ("attention", "end_session"),
+        ("attention", "claim_reward"),
+        ("attention", "fund_pool"),
+        ("truth_claim", "create_claim"),
+        ("truth_claim", "vote_on_claim"),
+        ("truth_claim", "resolve_claim"),
+        ("creator_lifeline", "create_lifeline"),
+        ("creator_lifeline", "send_support"),
+    ];
+
+    pub fn module_function(&self) -> (&'static str, &'static str) {
+        match self {
+            TxCall::CreateProfile => ("profile", "create_profile"),
+            TxCall::CreatePost { .. } => ("post", "create_post"),
+            TxCall::StartSession { .. } => ("attention", "start_session"),
+            TxCall::EndSession { .. } => ("attention", "end_session"),
+            TxCall::ClaimReward { .. } => ("attention", "claim_reward"),
+            TxCall::FundPool { .. } => ("attention", "fund_pool"),
+            TxCall::CreateClaim { .. } => ("truth_claim", "create_claim"),
+            TxCall::VoteOnClaim { .. } => ("truth_claim", "vote_on_claim"),
+            TxCall::ResolveClaim { .. } => ("truth_claim", "resolve_claim"),
+            TxCall::CreateLifeline => ("creator_lifeline", "create_lifeline"),
+            TxCall::SendSupport { .. } => ("creator_lifeline", "send_support"),
+        }
+    }
+
+    /// The function the transaction calls. Calls that create a key-only
+    /// object go through the contract's `entry` wrapper, which transfers or
+    /// shares it from inside its module as Sui requires.
+    pub fn entry_function(&self) -> String {
+        let (_, function) = self.module_function();
+        match self {
+            TxCall::CreateProfile
+            | TxCall::CreatePost { .. }
+            | TxCall::StartSession { .. }
+            | TxCall::CreateClaim { .. }
+            | TxCall::CreateLifeline => format!("{}{}", function, ENTRY_SUFFIX),
+            _ => function.to_string(),
+        }
+    }
+}
+
+/// Suffix of the contract's `entry` wrappers around its constructors
+pub const ENTRY_SUFFIX: &str = "_entry";
+
+/// The public name of a called function, mapping entry wrappers back to the
+/// function they wrap
+pub fn public_function(function: &str) -> &str {
+    function.strip_suffix(ENTRY_SUFFIX).unwrap_or(function)
+}
+
+/// Body of `POST /api/tx/submit`
+#[derive(Debug, Deserialize)]
+pub struct SubmitTxRequest {
+    /// Base64 BCS transaction data, as returned by the build endpoints
+    pub tx_bytes: String,
+    /// Sender's signature, plus the sponsor's for sponsored transactions
+    pub signatures: Vec<String>,
+    /// Nonce the matching provisional row was created with, if any
+    pub nonce: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TransactionsQuery {
+    /// "pending", "confirmed" or "failed"
+    pub status: Option<String>,
+    pub limit: Option<i64>,
+}
+
+// ============ STREAM MODELS ============
+
+#[derive(Debug, Deserialize)]
+pub struct StreamQuery {
+    /// Comma-separated: `feed`, `post:<id>`, `claim:<id>`, `profile:<address>`
+    pub topics: Option<String>,
+    /// Id of the last event received; SSE clients may send `Last-Event-ID` instead
+    pub resume: Option<i64>,
+}
+
+/// WebSocket frames from the client
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StreamCommand {
+    Subscribe(Vec<String>),
+    Unsubscribe(Vec<String>),
+}
+
+// ============ NOTIFICATION MODELS ============
+
+#[derive(Debug, Deserialize)]
+pub struct NotificationsQuery {
+    pub address: SuiAddress,
+    /// Only unread notifications
+    #[serde(default)]
+    pub unread: bool,
+    /// Id to page back from, as returned in `next_before`
+    pub before: Option<i64>,
+    pub limit: Option<i64>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AddressQuery {
+    pub address: SuiAddress,
+}
+
+/// Body of `POST /api/notifications/read`: either `ids` or `all`
+#[derive(Debug, Deserialize)]
+pub struct MarkReadRequest {
+    pub address: SuiAddress,
+    #[serde(default)]
+    pub ids: Vec<i64>,
+    #[serde(default)]
+    pub all: bool,
+}
+
+/// Body of `PUT /api/notifications/preferences`; omitted fields are unchanged
+#[derive(Debug, Deserialize)]
+pub struct UpdatePreferencesRequest {
+    pub address: SuiAddress,
+    /// Per kind, e.g. `{"claim_created": {"webhook": false}}`
+    #[serde(default)]
+    pub kinds: HashMap<String, KindPreference>,
+    /// URL the webhook sink posts to; an empty string removes it. The
+    /// response to registering one carries its signing secret.
+    pub webhook_url: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct KindPreference {
+    pub in_app: Option<bool>,
+    pub webhook: Option<bool>,
+}
+
+// ============ WEBHOOK MODELS ============
+
+#[derive(Debug, Deserialize)]
+pub struct CreateWebhookRequest {
+    pub owner: SuiAddress,
+    pub url: String,
+    /// e.g. `["post.created", "claim.resolved"]`; every event when omitted
+    pub events: Option<Vec<String>>,
+}
+
+/// Body of `PUT /api/webhooks/:id`; omitted fields are unchanged
+#[derive(Debug, Deserialize)]
+pub struct UpdateWebhookRequest {
+    pub url: Option<String>,
+    pub events: Option<Vec<String>>,
+    pub active: Option<bool>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct OwnerRequest {
+    pub owner: SuiAddress,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DeliveriesQuery {
+    /// "pending", "delivered" or "dead"
+    pub status: Option<String>,
+    pub before: Option<i64>,
+    pub limit: Option<i64>,
+}
+
+// ============ SEARCH MODELS ============
+
+#[derive(Debug, Deserialize)]
+pub struct SearchQuery {
+    pub q: String,
+    /// Comma-separated: `post`, `claim`, `profile`; all when omitted
+    pub types: Option<String>,
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+}
+
+// ============ TAG MODELS ============
+
+#[derive(Debug, Deserialize)]
+pub struct TagFeedQuery {
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TrendingTagsQuery {
+    /// Hours of attention compared against the window before; default 6
+    pub window_hours: Option<i64>,
+    pub limit: Option<i64>,
 }
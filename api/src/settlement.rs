@@ -0,0 +1,247 @@
+use sqlx::{Row, Sqlite, SqlitePool, Transaction};
+use std::time::Duration;
+use suiter_core::{
+    ledger::{self, LedgerTxn},
+    notifications::{self, Kind},
+    reputation::{self, Reason, ReputationLedger},
+    stream,
+    webhooks::{self, Event as WebhookEvent},
+};
+use suiter_ranker::FeedRanker;
+use tokio::time::sleep;
+
+use crate::claim_rules::{self as rules, Outcome, Stake, Tally};
+
+/// How often due claims are resolved and settled
+const SETTLE_INTERVAL: Duration = Duration::from_secs(30);
+/// Claims handled per sweep
+const SETTLE_BATCH: i64 = 100;
+
+/// Resolves truth claims once voting ends and pays out their escrow
+/// Both steps are guarded by row state (`resolved`, `settled_at`) and the
+/// ledger's unique `txn_id`, so a claim settles exactly once no matter how
+/// many sweeps or readers race on it.
+pub async fn run(pool: SqlitePool) {
+    loop {
+        match settle_due(&pool).await {
+            Ok(0) => {}
+            Ok(n) => tracing::info!("Settled {} claims", n),
+            Err(e) => tracing::error!("Claim settlement failed: {}", e),
+        }
+
+        sleep(SETTLE_INTERVAL).await;
+    }
+}
+
+async fn settle_due(pool: &SqlitePool) -> Result<usize, sqlx::Error> {
+    let due: Vec<String> = sqlx::query_scalar(
+        "SELECT id FROM truth_claims WHERE (resolved = FALSE AND voting_end <= CURRENT_TIMESTAMP) OR (resolved = TRUE AND settled_at IS NULL) LIMIT ?"
+    )
+    .bind(SETTLE_BATCH)
+    .fetch_all(pool)
+    .await?;
+
+    let mut settled = 0;
+    for id in due {
+        if resolve_and_settle(pool, &id).await? {
+            settled += 1;
+        }
+    }
+
+    Ok(settled)
+}
+
+/// Resolve the claim if its voting has ended, then settle it if resolved.
+/// Returns true if this call settled it.
+pub async fn resolve_and_settle(pool: &SqlitePool, claim_id: &str) -> Result<bool, sqlx::Error> {
+    resolve_if_due(pool, claim_id).await?;
+    settle(pool, claim_id).await
+}
+
+pub async fn load_tally(pool: &SqlitePool, claim_id: &str) -> Result<Tally, sqlx::Error> {
+    let (yes, no, weighted_yes, weighted_no): (i64, i64, i64, i64) = sqlx::query_as(
+        r#"
+        SELECT
+            COALESCE(SUM(CASE WHEN vote THEN 1 ELSE 0 END), 0),
+            COALESCE(SUM(CASE WHEN vote THEN 0 ELSE 1 END), 0),
+            COALESCE(SUM(CASE WHEN vote THEN reputation_snapshot ELSE 0 END), 0),
+            COALESCE(SUM(CASE WHEN vote THEN 0 ELSE reputation_snapshot END), 0)
+        FROM claim_votes WHERE claim_id = ?
+        "#
+    )
+    .bind(claim_id)
+    .fetch_one(pool)
+    .await?;
+
+    Ok(Tally { yes, no, weighted_yes, weighted_no })
+}
+
+/// Resolve a claim whose voting period has ended. Returns the outcome if this
+/// call resolved it.
+async fn resolve_if_due(pool: &SqlitePool, claim_id: &str) -> Result<Option<Outcome>, sqlx::Error> {
+    let due: Option<String> = sqlx::query_scalar(
+        "SELECT post_id FROM truth_claims WHERE id = ? AND resolved = FALSE AND voting_end <= CURRENT_TIMESTAMP"
+    )
+    .bind(claim_id)
+    .fetch_optional(pool)
+    .await?;
+    let Some(post_id) = due else {
+        return Ok(None);
+    };
+
+    let tally = load_tally(pool, claim_id).await?;
+    let outcome = tally.outcome();
+
+    // Guarded on resolved so concurrent readers resolve it only once
+    let updated = sqlx::query(
+        "UPDATE truth_claims SET resolved = TRUE, accepted = ?, outcome = ?, resolved_at = CURRENT_TIMESTAMP, votes_yes = ?, votes_no = ? WHERE id = ? AND resolved = FALSE"
+    )
+    .bind(outcome.accepted())
+    .bind(outcome.as_str())
+    .bind(tally.yes)
+    .bind(tally.no)
+    .bind(claim_id)
+    .execute(pool)
+    .await?;
+
+    if updated.rows_affected() == 0 {
+        return Ok(None);
+    }
+
+    tracing::info!("Claim {} resolved as {}", claim_id, outcome.as_str());
+    stream::publish(pool, &stream::claim_topic(claim_id), "resolved", &serde_json::json!({
+        "claim_id": claim_id,
+        "post_id": post_id,
+        "outcome": outcome.as_str(),
+        "votes_yes": tally.yes,
+        "votes_no": tally.no,
+    })).await?;
+
+    let parties = sqlx::query("SELECT c.claimer, p.author FROM truth_claims c LEFT JOIN posts p ON p.id = c.post_id WHERE c.id = ?")
+        .bind(claim_id)
+        .fetch_one(pool)
+        .await?;
+    let mut recipients = vec![parties.get::<String, _>("claimer")];
+    recipients.extend(parties.get::<Option<String>, _>("author"));
+    recipients.dedup();
+    let event = serde_json::json!({ "claim_id": claim_id, "post_id": post_id, "outcome": outcome.as_str() });
+    for recipient in recipients {
+        if let Err(e) = notifications::notify(pool, &recipient, Kind::ClaimResolved, &format!("claim:{}", claim_id), &event).await {
+            tracing::error!("Failed to notify {}: {}", recipient, e);
+        }
+    }
+    let resolved = serde_json::json!({
+        "claim_id": claim_id,
+        "post_id": post_id,
+        "outcome": outcome.as_str(),
+        "accepted": outcome.accepted(),
+        "votes_yes": tally.yes,
+        "votes_no": tally.no,
+    });
+    if let Err(e) = webhooks::enqueue(pool, WebhookEvent::ClaimResolved, claim_id, &resolved).await {
+        tracing::error!("Failed to queue webhooks for claim {}: {}", claim_id, e);
+    }
+    if let Err(e) = FeedRanker::mark_dirty(pool, &post_id, "claim").await {
+        tracing::error!("Failed to mark post {} dirty: {}", post_id, e);
+    }
+
+    Ok(Some(outcome))
+}
+
+/// Pay out a resolved claim's escrow. Returns true if this call settled it.
+async fn settle(pool: &SqlitePool, claim_id: &str) -> Result<bool, sqlx::Error> {
+    let mut tx = pool.begin().await?;
+
+    let row = sqlx::query("SELECT c.claimer, c.stake, c.outcome, c.votes_yes, p.author FROM truth_claims c LEFT JOIN posts p ON p.id = c.post_id WHERE c.id = ? AND c.resolved = TRUE AND c.settled_at IS NULL")
+        .bind(claim_id)
+        .fetch_optional(&mut *tx)
+        .await?;
+    let Some(row) = row else {
+        return Ok(false);
+    };
+
+    // Claims resolved on-chain before stakes were tracked carry no outcome
+    let outcome = row
+        .get::<Option<String>, _>("outcome")
+        .and_then(|o| Outcome::parse(&o))
+        .unwrap_or(Outcome::NoQuorum);
+
+    let mut stakes = vec![Stake {
+        address: row.get("claimer"),
+        side: true,
+        amount: row.get("stake"),
+    }];
+    let votes = sqlx::query("SELECT voter, vote, stake FROM claim_votes WHERE claim_id = ? AND stake > 0")
+        .bind(claim_id)
+        .fetch_all(&mut *tx)
+        .await?;
+    stakes.extend(votes.iter().map(|r| Stake {
+        address: r.get("voter"),
+        side: r.get("vote"),
+        amount: r.get("stake"),
+    }));
+
+    let plan = rules::settle(outcome, &stakes);
+    let escrow = ledger::claim_escrow(claim_id);
+    let mut txn = LedgerTxn::new();
+    for (address, amount) in &plan.payouts {
+        txn.transfer(&escrow, &ledger::user(address), *amount);
+    }
+    txn.transfer(&escrow, ledger::TREASURY, plan.treasury);
+    txn.post(&mut tx, &format!("settle:claim:{}", claim_id), "settlement", Some(claim_id))
+        .await?;
+
+    let claimer = row.get::<String, _>("claimer");
+    let author = row.get::<Option<String>, _>("author");
+    apply_reputation(&mut tx, claim_id, outcome, &claimer, author.as_deref(), row.get("votes_yes")).await?;
+
+    sqlx::query("UPDATE truth_claims SET settled_at = CURRENT_TIMESTAMP WHERE id = ?")
+        .bind(claim_id)
+        .execute(&mut *tx)
+        .await?;
+    tx.commit().await?;
+
+    if outcome != Outcome::NoQuorum {
+        for address in std::iter::once(&claimer).chain(&author) {
+            if let Err(e) = FeedRanker::mark_author_dirty(pool, address, "reputation").await {
+                tracing::error!("Failed to mark author {} dirty: {}", address, e);
+            }
+        }
+    }
+
+    tracing::info!(
+        "Settled claim {} ({}): {} payouts, {} to treasury",
+        claim_id,
+        outcome.as_str(),
+        plan.payouts.len(),
+        plan.treasury
+    );
+    Ok(true)
+}
+
+/// Reputation consequences of a resolved claim: an accepted claim rewards the
+/// claimer and costs the post's author in proportion to the votes doubting
+/// it; a rejected one costs the claimer. Claims without quorum change nothing.
+async fn apply_reputation(
+    tx: &mut Transaction<'_, Sqlite>,
+    claim_id: &str,
+    outcome: Outcome,
+    claimer: &str,
+    author: Option<&str>,
+    doubt_votes: i64,
+) -> Result<(), sqlx::Error> {
+    let source_ref = format!("claim:{}", claim_id);
+    match outcome {
+        Outcome::Accepted => {
+            ReputationLedger::apply(tx, claimer, reputation::CLAIM_ACCEPTED_GAIN, Reason::ClaimOutcome, &source_ref).await?;
+            if let Some(author) = author {
+                ReputationLedger::apply(tx, author, -reputation::doubt_loss(doubt_votes), Reason::DoubtLoss, &source_ref).await?;
+            }
+        }
+        Outcome::Rejected => {
+            ReputationLedger::apply(tx, claimer, -reputation::CLAIM_REJECTED_LOSS, Reason::ClaimOutcome, &source_ref).await?;
+        }
+        Outcome::NoQuorum => {}
+    }
+    Ok(())
+}
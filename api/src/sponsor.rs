@@ -0,0 +1,71 @@
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use blake2::Digest as _;
+use ed25519_dalek::{Signer, SigningKey};
+
+use crate::sui_tx::{Address, Blake2b256};
+
+/// Signature scheme flag for Ed25519
+const ED25519_FLAG: u8 = 0x00;
+/// Intent prefix for transaction data: scope, version, app id
+const TRANSACTION_INTENT: [u8; 3] = [0, 0, 0];
+
+/// Gas sponsor key
+/// Loaded from `SPONSOR_PRIVATE_KEY`, either 32 hex-encoded bytes or a Sui
+/// keystore entry (base64 of the scheme flag and the key).
+pub struct Sponsor {
+    key: SigningKey,
+    address: Address,
+}
+
+impl Sponsor {
+    pub fn from_env() -> Option<Self> {
+        let raw = std::env::var("SPONSOR_PRIVATE_KEY").ok()?;
+        let seed = parse_seed(raw.trim());
+        if seed.is_none() {
+            tracing::warn!("SPONSOR_PRIVATE_KEY is not a valid Ed25519 key - sponsorship disabled");
+        }
+
+        let key = SigningKey::from_bytes(&seed?);
+        let mut hasher = Blake2b256::new();
+        hasher.update([ED25519_FLAG]);
+        hasher.update(key.verifying_key().as_bytes());
+
+        Some(Sponsor {
+            key,
+            address: Address(hasher.finalize().into()),
+        })
+    }
+
+    pub fn address(&self) -> Address {
+        self.address
+    }
+
+    /// Serialized signature over the transaction's intent message:
+    /// base64(flag || signature || public key)
+    pub fn sign(&self, tx_bytes: &[u8]) -> String {
+        let mut hasher = Blake2b256::new();
+        hasher.update(TRANSACTION_INTENT);
+        hasher.update(tx_bytes);
+        let signature = self.key.sign(&hasher.finalize());
+
+        let mut serialized = vec![ED25519_FLAG];
+        serialized.extend_from_slice(&signature.to_bytes());
+        serialized.extend_from_slice(self.key.verifying_key().as_bytes());
+        BASE64.encode(serialized)
+    }
+}
+
+fn parse_seed(raw: &str) -> Option<[u8; 32]> {
+    let mut seed = [0u8; 32];
+    if hex::decode_to_slice(raw.strip_prefix("0x").unwrap_or(raw), &mut seed).is_ok() {
+        return Some(seed);
+    }
+
+    match BASE64.decode(raw).ok()?.as_slice() {
+        [ED25519_FLAG, key @ ..] if key.len() == 32 => {
+            seed.copy_from_slice(key);
+            Some(seed)
+        }
+        _ => None,
+    }
+}
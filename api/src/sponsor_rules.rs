@@ -0,0 +1,102 @@
+// ============ ALLOWLIST ============
+
+/// `suiter` entry points the sponsor pays for. Calls that spend coins
+/// (`fund_pool`, `send_support`) would be paid out of the sponsor's gas coin
+/// and are never sponsored.
+pub const SPONSORED_FUNCTIONS: &[&str] = &[
+    "profile::create_profile",
+    "post::create_post",
+    "attention::start_session",
+    "attention::end_session",
+    "attention::claim_reward",
+    "truth_claim::create_claim",
+    "truth_claim::vote_on_claim",
+    "truth_claim::resolve_claim",
+    "creator_lifeline::create_lifeline",
+];
+
+// ============ QUOTAS ============
+
+/// Sponsored transactions per address per UTC day at the starting reputation
+pub const BASE_DAILY_QUOTA: i64 = 5;
+/// One more per this much reputation
+pub const REPUTATION_PER_EXTRA_TX: i64 = 100;
+pub const MAX_DAILY_QUOTA: i64 = 50;
+
+// ============ ABUSE LIMITS ============
+
+/// Most the sponsor pays for one transaction, in MIST (0.01 SUI)
+pub const MAX_SPONSORED_BUDGET: u64 = 10_000_000;
+/// Most the sponsor pays across all addresses per UTC day (10 SUI)
+pub const MAX_DAILY_SPONSOR_SPEND: i64 = 10_000_000_000;
+/// Minimum gap between two sponsorships for the same address
+pub const MIN_SPONSOR_INTERVAL_SECS: i64 = 10;
+
+/// Why a sponsorship was refused
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SponsorRejection {
+    NotAllowlisted,
+    QuotaExhausted,
+    TooFrequent,
+    RiskFlagged,
+    BudgetTooHigh,
+    SponsorBudgetExhausted,
+}
+
+impl SponsorRejection {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SponsorRejection::NotAllowlisted => "function_not_sponsored",
+            SponsorRejection::QuotaExhausted => "daily_quota_exhausted",
+            SponsorRejection::TooFrequent => "too_frequent",
+            SponsorRejection::RiskFlagged => "risk_flagged",
+            SponsorRejection::BudgetTooHigh => "gas_budget_too_high",
+            SponsorRejection::SponsorBudgetExhausted => "sponsor_budget_exhausted",
+        }
+    }
+}
+
+/// What an address has already used, as counted before sponsoring
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Usage {
+    pub today: i64,
+    pub secs_since_last: Option<i64>,
+    pub sponsor_spent_today: i64,
+}
+
+/// Daily sponsored transactions for an address with this reputation
+pub fn daily_quota(reputation: i64) -> i64 {
+    (BASE_DAILY_QUOTA + reputation.max(0) / REPUTATION_PER_EXTRA_TX).min(MAX_DAILY_QUOTA)
+}
+
+pub fn is_allowlisted(function: &str) -> bool {
+    SPONSORED_FUNCTIONS.contains(&function)
+}
+
+/// Checks that don't depend on the gas estimate
+pub fn check_request(function: &str, reputation: i64, risk: f64, usage: &Usage) -> Result<(), SponsorRejection> {
+    if !is_allowlisted(function) {
+        return Err(SponsorRejection::NotAllowlisted);
+    }
+    if risk >= crate::attention_rules::RISK_BLOCK_THRESHOLD {
+        return Err(SponsorRejection::RiskFlagged);
+    }
+    if usage.today >= daily_quota(reputation) {
+        return Err(SponsorRejection::QuotaExhausted);
+    }
+    if usage.secs_since_last.is_some_and(|s| s < MIN_SPONSOR_INTERVAL_SECS) {
+        return Err(SponsorRejection::TooFrequent);
+    }
+    Ok(())
+}
+
+/// Checks on the estimated budget
+pub fn check_budget(budget: u64, usage: &Usage) -> Result<(), SponsorRejection> {
+    if budget > MAX_SPONSORED_BUDGET {
+        return Err(SponsorRejection::BudgetTooHigh);
+    }
+    if usage.sponsor_spent_today.saturating_add(budget as i64) > MAX_DAILY_SPONSOR_SPEND {
+        return Err(SponsorRejection::SponsorBudgetExhausted);
+    }
+    Ok(())
+}
@@ -0,0 +1,159 @@
+use serde::Serialize;
+use sqlx::{Row, SqlitePool};
+use std::sync::atomic::{AtomicI64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use suiter_core::stream;
+use tokio::sync::{broadcast, Notify};
+use tokio::time::sleep;
+
+/// Events buffered per subscriber; one that falls further behind catches up
+/// from `stream_events` instead
+pub const BUS_CAPACITY: usize = 1024;
+/// How often the relay looks for rows the indexer wrote
+const RELAY_INTERVAL: Duration = Duration::from_millis(500);
+/// Rows relayed per query
+const RELAY_BATCH: i64 = 500;
+/// Resume tokens stay usable this long
+const RETENTION: &str = "-1 hour";
+const PRUNE_INTERVAL: Duration = Duration::from_secs(60);
+
+/// One `stream_events` row
+#[derive(Debug, Clone, Serialize)]
+pub struct StreamEvent {
+    pub id: i64,
+    pub topic: String,
+    pub kind: String,
+    pub payload: serde_json::Value,
+    pub created_at: String,
+}
+
+/// In-process broadcast bus for `GET /api/stream`
+/// Every writer, the API and the indexer alike, appends to `stream_events`;
+/// a background task relays new rows to all subscribers in id order. API
+/// writes wake the relay so their events go out without waiting for a poll.
+pub struct StreamBus {
+    sender: broadcast::Sender<Arc<StreamEvent>>,
+    wake: Notify,
+    /// Highest id sent to subscribers
+    relayed: AtomicI64,
+}
+
+impl Default for StreamBus {
+    fn default() -> Self {
+        let (sender, _) = broadcast::channel(BUS_CAPACITY);
+        StreamBus {
+            sender,
+            wake: Notify::new(),
+            relayed: AtomicI64::new(0),
+        }
+    }
+}
+
+impl StreamBus {
+    pub fn subscribe(&self) -> broadcast::Receiver<Arc<StreamEvent>> {
+        self.sender.subscribe()
+    }
+
+    /// Append an event for subscribers. Failures are logged rather than
+    /// returned: a missed stream event must not fail the write it describes.
+    pub async fn publish(&self, pool: &SqlitePool, topic: &str, kind: &str, payload: serde_json::Value) {
+        if let Err(e) = stream::publish(pool, topic, kind, &payload).await {
+            tracing::error!("Failed to publish {} to {}: {}", kind, topic, e);
+            return;
+        }
+        self.wake.notify_one();
+    }
+
+    /// Relay new rows to subscribers and prune old ones
+    pub async fn run_relay(&self, pool: SqlitePool) {
+        // Only events written from now on are relayed; older ones are replayed on request
+        match bounds(&pool).await {
+            Ok((_, latest)) => self.relayed.store(latest, Ordering::SeqCst),
+            Err(e) => tracing::error!("Failed to read stream position: {}", e),
+        }
+
+        let mut last_prune = Instant::now();
+        loop {
+            if let Err(e) = self.relay(&pool).await {
+                tracing::error!("Failed to relay stream events: {}", e);
+            }
+
+            if last_prune.elapsed() >= PRUNE_INTERVAL {
+                last_prune = Instant::now();
+                match sqlx::query("DELETE FROM stream_events WHERE created_at < datetime('now', ?)").bind(RETENTION).execute(&pool).await {
+                    Ok(r) if r.rows_affected() > 0 => tracing::debug!("Pruned {} stream events", r.rows_affected()),
+                    Ok(_) => {}
+                    Err(e) => tracing::error!("Failed to prune stream events: {}", e),
+                }
+            }
+
+            tokio::select! {
+                _ = self.wake.notified() => {}
+                _ = sleep(RELAY_INTERVAL) => {}
+            }
+        }
+    }
+
+    async fn relay(&self, pool: &SqlitePool) -> Result<(), sqlx::Error> {
+        loop {
+            let after = self.relayed.load(Ordering::SeqCst);
+            let rows = sqlx::query("SELECT * FROM stream_events WHERE id > ? ORDER BY id LIMIT ?")
+                .bind(after)
+                .bind(RELAY_BATCH)
+                .fetch_all(pool)
+                .await?;
+
+            for r in &rows {
+                let event = event_from_row(r);
+                self.relayed.store(event.id, Ordering::SeqCst);
+                // Err only means nobody is subscribed
+                let _ = self.sender.send(Arc::new(event));
+            }
+
+            if (rows.len() as i64) < RELAY_BATCH {
+                return Ok(());
+            }
+        }
+    }
+}
+
+/// Events on `topics` after `after`, oldest first, for resuming or
+/// catching up
+pub async fn replay(pool: &SqlitePool, topics: &[String], after: i64, limit: i64) -> Result<Vec<StreamEvent>, sqlx::Error> {
+    if topics.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let placeholders = vec!["?"; topics.len()].join(", ");
+    let query = format!("SELECT * FROM stream_events WHERE id > ? AND topic IN ({}) ORDER BY id LIMIT ?", placeholders);
+    let mut q = sqlx::query(&query).bind(after);
+    for topic in topics {
+        q = q.bind(topic);
+    }
+    let rows = q.bind(limit).fetch_all(pool).await?;
+
+    Ok(rows.iter().map(event_from_row).collect())
+}
+
+/// (oldest retained id, newest id ever written). AUTOINCREMENT ids are never
+/// reused, so the newest id holds even after every row is pruned.
+pub async fn bounds(pool: &SqlitePool) -> Result<(Option<i64>, i64), sqlx::Error> {
+    let row = sqlx::query(
+        "SELECT (SELECT MIN(id) FROM stream_events) as oldest, COALESCE((SELECT seq FROM sqlite_sequence WHERE name = 'stream_events'), 0) as latest"
+    )
+    .fetch_one(pool)
+    .await?;
+
+    Ok((row.get("oldest"), row.get("latest")))
+}
+
+fn event_from_row(r: &sqlx::sqlite::SqliteRow) -> StreamEvent {
+    StreamEvent {
+        id: r.get("id"),
+        topic: r.get("topic"),
+        kind: r.get("kind"),
+        payload: serde_json::from_str(&r.get::<String, _>("payload")).unwrap_or(serde_json::Value::Null),
+        created_at: r.get("created_at"),
+    }
+}
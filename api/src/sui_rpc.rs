@@ -0,0 +1,185 @@
+use anyhow::{anyhow, Result};
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use serde_json::{json, Value};
+
+use crate::sui_tx::{Address, Digest, ObjectArg, ObjectRef};
+
+/// Gas coins considered when paying for a transaction
+const MAX_GAS_COINS: usize = 16;
+
+/// Gas a dry run reported
+#[derive(Debug, Clone)]
+pub struct DryRun {
+    /// None on success, otherwise the execution error
+    pub error: Option<String>,
+    pub computation_cost: u64,
+    pub storage_cost: u64,
+    pub storage_rebate: u64,
+}
+
+/// Outcome of submitting a signed transaction
+#[derive(Debug, Clone)]
+pub enum Execution {
+    /// Executed; `error` is set if it aborted (gas is still charged)
+    Executed { digest: String, error: Option<String> },
+    /// Refused before execution, e.g. a bad signature or stale object
+    Rejected(String),
+}
+
+/// Minimal Sui JSON-RPC client
+/// `SUI_RPC_URL` may point at a full node or at a local stand-in such as
+/// `sui_rpc_stub.py`.
+#[derive(Debug, Clone)]
+pub struct SuiRpc {
+    url: String,
+    http: reqwest::Client,
+}
+
+impl SuiRpc {
+    pub fn new(url: String) -> Self {
+        SuiRpc {
+            url,
+            http: reqwest::Client::new(),
+        }
+    }
+
+    pub async fn call(&self, method: &str, params: Value) -> Result<Value> {
+        let resp = self.send(method, params).await?;
+        if let Some(err) = resp.get("error") {
+            return Err(anyhow!("{} failed: {}", method, err));
+        }
+
+        Ok(resp["result"].clone())
+    }
+
+    async fn send(&self, method: &str, params: Value) -> Result<Value> {
+        let body = json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": method,
+            "params": params
+        });
+
+        Ok(self.http.post(&self.url).json(&body).send().await?.json().await?)
+    }
+
+    pub async fn reference_gas_price(&self) -> Result<u64> {
+        let result = self.call("suix_getReferenceGasPrice", json!([])).await?;
+        as_u64(&result).ok_or_else(|| anyhow!("unexpected gas price {}", result))
+    }
+
+    /// The owner's SUI coins with their balances, largest first
+    pub async fn gas_coins(&self, owner: &Address) -> Result<Vec<(ObjectRef, u64)>> {
+        let result = self
+            .call("suix_getCoins", json!([owner.to_string(), "0x2::sui::SUI", null, MAX_GAS_COINS]))
+            .await?;
+
+        let mut coins = result["data"]
+            .as_array()
+            .ok_or_else(|| anyhow!("unexpected coin page {}", result))?
+            .iter()
+            .map(|c| Ok((object_ref(c, "coinObjectId")?, as_u64(&c["balance"]).unwrap_or(0))))
+            .collect::<Result<Vec<_>>>()?;
+
+        coins.sort_by_key(|c| std::cmp::Reverse(c.1));
+        Ok(coins)
+    }
+
+    /// Resolve an object to a call argument: shared objects by their initial
+    /// version, everything else by its current reference
+    pub async fn object_arg(&self, id: &Address, mutable: bool) -> Result<ObjectArg> {
+        let result = self
+            .call("sui_getObject", json!([id.to_string(), { "showOwner": true }]))
+            .await?;
+        let data = result
+            .get("data")
+            .ok_or_else(|| anyhow!("object {} not found", id))?;
+
+        if let Some(version) = data["owner"]["Shared"].get("initial_shared_version") {
+            return Ok(ObjectArg::SharedObject {
+                id: *id,
+                initial_shared_version: as_u64(version).ok_or_else(|| anyhow!("bad shared version for {}", id))?,
+                mutable,
+            });
+        }
+
+        Ok(ObjectArg::ImmOrOwnedObject(object_ref(data, "objectId")?))
+    }
+
+    pub async fn dry_run(&self, tx_bytes: &[u8]) -> Result<DryRun> {
+        let result = self
+            .call("sui_dryRunTransactionBlock", json!([BASE64.encode(tx_bytes)]))
+            .await?;
+        let effects = &result["effects"];
+        let gas = &effects["gasUsed"];
+
+        let error = match effects["status"]["status"].as_str() {
+            Some("success") => None,
+            _ => Some(
+                effects["status"]["error"]
+                    .as_str()
+                    .unwrap_or("dry run failed")
+                    .to_string(),
+            ),
+        };
+
+        Ok(DryRun {
+            error,
+            computation_cost: as_u64(&gas["computationCost"]).unwrap_or(0),
+            storage_cost: as_u64(&gas["storageCost"]).unwrap_or(0),
+            storage_rebate: as_u64(&gas["storageRebate"]).unwrap_or(0),
+        })
+    }
+
+    /// Submit a signed transaction and wait for it to execute on the node.
+    /// Errors the node reports are rejections; only transport failures are
+    /// returned as `Err`.
+    pub async fn execute(&self, tx_bytes: &[u8], signatures: &[String]) -> Result<Execution> {
+        let resp = self
+            .send(
+                "sui_executeTransactionBlock",
+                json!([BASE64.encode(tx_bytes), signatures, { "showEffects": true }, "WaitForLocalExecution"]),
+            )
+            .await?;
+        if let Some(err) = resp.get("error") {
+            let message = err["message"].as_str().map(str::to_string).unwrap_or_else(|| err.to_string());
+            return Ok(Execution::Rejected(message));
+        }
+
+        let result = &resp["result"];
+        let digest = result["digest"]
+            .as_str()
+            .ok_or_else(|| anyhow!("execution result without a digest: {}", result))?
+            .to_string();
+        let status = &result["effects"]["status"];
+        let error = match status["status"].as_str() {
+            Some("success") => None,
+            _ => Some(status["error"].as_str().unwrap_or("execution failed").to_string()),
+        };
+
+        Ok(Execution::Executed { digest, error })
+    }
+}
+
+/// u64s arrive as strings or numbers depending on the endpoint
+fn as_u64(value: &Value) -> Option<u64> {
+    match value {
+        Value::String(s) => s.parse().ok(),
+        Value::Number(n) => n.as_u64(),
+        _ => None,
+    }
+}
+
+fn object_ref(data: &Value, id_key: &str) -> Result<ObjectRef> {
+    let id = data[id_key]
+        .as_str()
+        .and_then(Address::parse)
+        .ok_or_else(|| anyhow!("bad object id in {}", data))?;
+    let version = as_u64(&data["version"]).ok_or_else(|| anyhow!("bad version for {}", id))?;
+    let digest = data["digest"]
+        .as_str()
+        .and_then(Digest::parse)
+        .ok_or_else(|| anyhow!("bad digest for {}", id))?;
+
+    Ok((id, version, digest))
+}
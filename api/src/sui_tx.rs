@@ -0,0 +1,495 @@
+use blake2::{digest::consts::U32, Blake2b, Digest as _};
+use serde::{Deserialize, Serialize};
+use suiter_core::address::SuiAddress;
+
+pub type Blake2b256 = Blake2b<U32>;
+
+// ============ BCS TYPES (mirrors sui_types::transaction) ============
+//
+// Only what programmable move calls need. Enum variants are serialized by
+// position, so variants are kept in Sui's order even where unused.
+
+/// 32-byte address or object id; BCS-encoded as fixed bytes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Address(pub [u8; 32]);
+
+impl Address {
+    pub const SUI_FRAMEWORK: Address = Address::from_u8(2);
+
+    const fn from_u8(n: u8) -> Self {
+        let mut bytes = [0u8; 32];
+        bytes[31] = n;
+        Address(bytes)
+    }
+
+    /// Parse `0x`-prefixed hex, left-padding short forms such as `0x2`
+    pub fn parse(s: &str) -> Option<Self> {
+        let digits = s.strip_prefix("0x").unwrap_or(s);
+        if digits.is_empty() || digits.len() > 64 {
+            return None;
+        }
+
+        let padded = format!("{:0>64}", digits);
+        let mut bytes = [0u8; 32];
+        hex::decode_to_slice(padded, &mut bytes).ok()?;
+        Some(Address(bytes))
+    }
+}
+
+impl From<&SuiAddress> for Address {
+    fn from(address: &SuiAddress) -> Self {
+        Address(address.to_bytes())
+    }
+}
+
+impl std::fmt::Display for Address {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "0x{}", hex::encode(self.0))
+    }
+}
+
+/// Object digest; BCS-encoded as length-prefixed bytes
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Digest(Vec<u8>);
+
+impl Digest {
+    /// Digests arrive from the RPC in base58
+    pub fn parse(s: &str) -> Option<Self> {
+        let bytes = bs58::decode(s).into_vec().ok()?;
+        (bytes.len() == 32).then_some(Digest(bytes))
+    }
+}
+
+/// (object id, version, digest)
+pub type ObjectRef = (Address, u64, Digest);
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum ObjectArg {
+    ImmOrOwnedObject(ObjectRef),
+    SharedObject {
+        id: Address,
+        initial_shared_version: u64,
+        mutable: bool,
+    },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum CallArg {
+    Pure(Vec<u8>),
+    Object(ObjectArg),
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub enum Argument {
+    GasCoin,
+    Input(u16),
+    Result(u16),
+    NestedResult(u16, u16),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StructTag {
+    pub address: Address,
+    pub module: String,
+    pub name: String,
+    pub type_params: Vec<TypeTag>,
+}
+
+#[allow(dead_code)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum TypeTag {
+    Bool,
+    U8,
+    U64,
+    U128,
+    Address,
+    Signer,
+    Vector(Box<TypeTag>),
+    Struct(Box<StructTag>),
+}
+
+impl TypeTag {
+    /// `0x2::sui::SUI`
+    pub fn sui() -> Self {
+        TypeTag::Struct(Box::new(StructTag {
+            address: Address::SUI_FRAMEWORK,
+            module: "sui".to_string(),
+            name: "SUI".to_string(),
+            type_params: vec![],
+        }))
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProgrammableMoveCall {
+    pub package: Address,
+    pub module: String,
+    pub function: String,
+    pub type_arguments: Vec<TypeTag>,
+    pub arguments: Vec<Argument>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum Command {
+    MoveCall(Box<ProgrammableMoveCall>),
+    TransferObjects(Vec<Argument>, Argument),
+    SplitCoins(Argument, Vec<Argument>),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProgrammableTransaction {
+    pub inputs: Vec<CallArg>,
+    pub commands: Vec<Command>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum TransactionKind {
+    ProgrammableTransaction(ProgrammableTransaction),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GasData {
+    pub payment: Vec<ObjectRef>,
+    pub owner: Address,
+    pub price: u64,
+    pub budget: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum TransactionExpiration {
+    None,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TransactionDataV1 {
+    pub kind: TransactionKind,
+    pub sender: Address,
+    pub gas_data: GasData,
+    pub expiration: TransactionExpiration,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum TransactionData {
+    V1(TransactionDataV1),
+}
+
+impl TransactionData {
+    pub fn new(ptb: ProgrammableTransaction, sender: Address, gas_data: GasData) -> Self {
+        TransactionData::V1(TransactionDataV1 {
+            kind: TransactionKind::ProgrammableTransaction(ptb),
+            sender,
+            gas_data,
+            expiration: TransactionExpiration::None,
+        })
+    }
+
+    /// Decode transaction bytes; only the commands above are understood,
+    /// which covers everything `PtbBuilder` produces
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        bcs::from_bytes(bytes).ok()
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        bcs::to_bytes(self).expect("transaction data is always serializable")
+    }
+
+    /// The digest the chain will know the transaction by
+    pub fn digest(&self) -> String {
+        digest(&self.to_bytes())
+    }
+
+    pub fn sender(&self) -> Address {
+        let TransactionData::V1(data) = self;
+        data.sender
+    }
+
+    /// The first move call into `package` and the input behind each of its
+    /// arguments, by position. Framework calls such as `coin::into_balance`
+    /// are skipped over.
+    pub fn move_call(&self, package: Address) -> Option<(&ProgrammableMoveCall, Vec<Option<&CallArg>>)> {
+        let TransactionData::V1(data) = self;
+        let TransactionKind::ProgrammableTransaction(ptb) = &data.kind;
+        ptb.commands.iter().enumerate().find_map(|(i, command)| match command {
+            Command::MoveCall(call) if call.package == package => {
+                let inputs = call.arguments.iter().map(|arg| ptb.resolve(*arg, i)).collect();
+                Some((call.as_ref(), inputs))
+            }
+            _ => None,
+        })
+    }
+}
+
+impl ProgrammableTransaction {
+    /// The input an argument of command `at` derives from. A command's result
+    /// is traced back through its first argument, so a coin split off an
+    /// input or a balance made from it resolves to that input; the gas coin
+    /// resolves to nothing.
+    fn resolve(&self, arg: Argument, at: usize) -> Option<&CallArg> {
+        match arg {
+            Argument::GasCoin => None,
+            Argument::Input(i) => self.inputs.get(i as usize),
+            Argument::Result(i) | Argument::NestedResult(i, _) => {
+                let i = i as usize;
+                // Results only ever refer to earlier commands
+                if i >= at {
+                    return None;
+                }
+                let first = match self.commands.get(i)? {
+                    Command::MoveCall(call) => call.arguments.first(),
+                    Command::TransferObjects(objects, _) => objects.first(),
+                    Command::SplitCoins(coin, _) => Some(coin),
+                };
+                self.resolve(*first?, i)
+            }
+        }
+    }
+}
+
+/// Digest of BCS-encoded transaction data
+pub fn digest(tx_bytes: &[u8]) -> String {
+    let mut hasher = Blake2b256::new();
+    hasher.update(b"TransactionData::");
+    hasher.update(tx_bytes);
+    bs58::encode(hasher.finalize()).into_string()
+}
+
+impl CallArg {
+    /// The object id or pure address this argument refers to
+    pub fn address(&self) -> Option<Address> {
+        match self {
+            CallArg::Pure(bytes) => bcs::from_bytes(bytes).ok(),
+            CallArg::Object(ObjectArg::ImmOrOwnedObject((id, _, _))) => Some(*id),
+            CallArg::Object(ObjectArg::SharedObject { id, .. }) => Some(*id),
+        }
+    }
+}
+
+// ============ BUILDER ============
+
+/// Programmable transaction block builder
+/// Inputs and commands are appended in call order; each call returns the
+/// `Argument` later commands use to refer to its result.
+#[derive(Debug, Default)]
+pub struct PtbBuilder {
+    inputs: Vec<CallArg>,
+    commands: Vec<Command>,
+}
+
+impl PtbBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A pure (non-object) input, BCS-encoded
+    pub fn pure<T: Serialize>(&mut self, value: &T) -> Argument {
+        let bytes = bcs::to_bytes(value).expect("pure inputs are always serializable");
+        self.input(CallArg::Pure(bytes))
+    }
+
+    pub fn object(&mut self, arg: ObjectArg) -> Argument {
+        self.input(CallArg::Object(arg))
+    }
+
+    pub fn move_call(
+        &mut self,
+        package: Address,
+        module: &str,
+        function: &str,
+        type_arguments: Vec<TypeTag>,
+        arguments: Vec<Argument>,
+    ) -> Argument {
+        let i = self.command(Command::MoveCall(Box::new(ProgrammableMoveCall {
+            package,
+            module: module.to_string(),
+            function: function.to_string(),
+            type_arguments,
+            arguments,
+        })));
+        Argument::Result(i)
+    }
+
+    /// Split `amount` MIST off the gas coin into a new coin
+    pub fn split_gas(&mut self, amount: u64) -> Argument {
+        let amount = self.pure(&amount);
+        let i = self.command(Command::SplitCoins(Argument::GasCoin, vec![amount]));
+        Argument::NestedResult(i, 0)
+    }
+
+    /// Turn a `Coin<SUI>` into the `Balance<SUI>` suiter functions take
+    pub fn coin_into_balance(&mut self, coin: Argument) -> Argument {
+        self.move_call(Address::SUI_FRAMEWORK, "coin", "into_balance", vec![TypeTag::sui()], vec![coin])
+    }
+
+    pub fn finish(self) -> ProgrammableTransaction {
+        ProgrammableTransaction {
+            inputs: self.inputs,
+            commands: self.commands,
+        }
+    }
+
+    fn input(&mut self, arg: CallArg) -> Argument {
+        self.inputs.push(arg);
+        Argument::Input((self.inputs.len() - 1) as u16)
+    }
+
+    fn command(&mut self, command: Command) -> u16 {
+        self.commands.push(command);
+        (self.commands.len() - 1) as u16
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn address(n: u8) -> Address {
+        Address::from_u8(n)
+    }
+
+    /// `creator_lifeline::send_support` as `compose` builds it: a shared
+    /// object, a coin split off gas and turned into a balance, and a pure
+    /// address
+    fn send_support() -> TransactionData {
+        let mut ptb = PtbBuilder::new();
+        let lifeline = ptb.object(ObjectArg::SharedObject { id: address(5), initial_shared_version: 7, mutable: true });
+        let coin = ptb.split_gas(5);
+        let arguments = vec![lifeline, ptb.pure(&address(0xaa)), ptb.coin_into_balance(coin)];
+        ptb.move_call(address(0xab), "creator_lifeline", "send_support", vec![], arguments);
+
+        let gas_data = GasData {
+            payment: vec![(address(9), 3, Digest(vec![0x11; 32]))],
+            owner: address(0xaa),
+            price: 1_000,
+            budget: 5_000_000,
+        };
+        TransactionData::new(ptb.finish(), address(0xaa), gas_data)
+    }
+
+    /// The same transaction encoded by hand from Sui's BCS layout
+    fn send_support_bytes() -> Vec<u8> {
+        fn string(out: &mut Vec<u8>, s: &str) {
+            out.push(s.len() as u8);
+            out.extend_from_slice(s.as_bytes());
+        }
+
+        let mut out = vec![0x00, 0x00]; // TransactionData::V1, ProgrammableTransaction
+        out.push(3); // inputs
+        out.extend([0x01, 0x01]); // CallArg::Object, ObjectArg::SharedObject
+        out.extend(address(5).0);
+        out.extend(7u64.to_le_bytes());
+        out.push(0x01);
+        out.extend([0x00, 0x08]); // CallArg::Pure, 8 bytes
+        out.extend(5u64.to_le_bytes());
+        out.extend([0x00, 0x20]); // CallArg::Pure, 32 bytes
+        out.extend(address(0xaa).0);
+
+        out.push(3); // commands
+        out.extend([0x02, 0x00, 0x01, 0x01, 0x01, 0x00]); // SplitCoins(GasCoin, [Input(1)])
+        out.push(0x00); // MoveCall
+        out.extend(address(2).0);
+        string(&mut out, "coin");
+        string(&mut out, "into_balance");
+        out.extend([0x01, 0x07]); // one type argument, TypeTag::Struct
+        out.extend(address(2).0);
+        string(&mut out, "sui");
+        string(&mut out, "SUI");
+        out.push(0x00);
+        out.extend([0x01, 0x03, 0x00, 0x00, 0x00, 0x00]); // [NestedResult(0, 0)]
+        out.push(0x00); // MoveCall
+        out.extend(address(0xab).0);
+        string(&mut out, "creator_lifeline");
+        string(&mut out, "send_support");
+        out.push(0x00);
+        out.extend([0x03, 0x01, 0x00, 0x00, 0x01, 0x02, 0x00, 0x02, 0x01, 0x00]); // [Input(0), Input(2), Result(1)]
+
+        out.extend(address(0xaa).0); // sender
+        out.push(1); // payment
+        out.extend(address(9).0);
+        out.extend(3u64.to_le_bytes());
+        out.push(0x20);
+        out.extend([0x11; 32]);
+        out.extend(address(0xaa).0); // gas owner
+        out.extend(1_000u64.to_le_bytes());
+        out.extend(5_000_000u64.to_le_bytes());
+        out.push(0x00); // TransactionExpiration::None
+        out
+    }
+
+    #[test]
+    fn address_parse_pads_short_forms() {
+        assert_eq!(Address::parse("0x2"), Some(Address::SUI_FRAMEWORK));
+        assert_eq!(Address::parse("2"), Some(Address::SUI_FRAMEWORK));
+        assert_eq!(Address::parse("0x"), None);
+        assert_eq!(Address::parse(&format!("0x{}", "0".repeat(65))), None);
+        assert_eq!(Address::parse("0xzz"), None);
+    }
+
+    #[test]
+    fn transaction_data_matches_sui_bcs_layout() {
+        assert_eq!(send_support().to_bytes(), send_support_bytes());
+    }
+
+    #[test]
+    fn transaction_data_round_trips() {
+        let bytes = send_support_bytes();
+        let decoded = TransactionData::from_bytes(&bytes).unwrap();
+
+        assert_eq!(decoded.to_bytes(), bytes);
+        assert_eq!(decoded.sender(), address(0xaa));
+        assert!(TransactionData::from_bytes(&bytes[..bytes.len() - 1]).is_none());
+    }
+
+    #[test]
+    fn digest_hashes_the_type_prefixed_bytes() {
+        // blake2b-256 of "TransactionData::" followed by the bytes, in base58
+        assert_eq!(send_support().digest(), "9uyhYS4JjAkEm1NwUS5Ni5hcRuHagHfb2thanoje8wz");
+        assert_eq!(digest(&send_support_bytes()), send_support().digest());
+    }
+
+    #[test]
+    fn move_call_skips_framework_calls_for_the_package() {
+        let tx = send_support();
+        let (call, inputs) = tx.move_call(address(0xab)).unwrap();
+
+        assert_eq!(call.function, "send_support");
+        assert_eq!(inputs.len(), 3);
+        assert_eq!(inputs[0].and_then(CallArg::address), Some(address(5)));
+        assert_eq!(inputs[1].and_then(CallArg::address), Some(address(0xaa)));
+        // The balance comes from a coin split off gas, which is no input
+        assert!(inputs[2].is_none());
+
+        assert_eq!(tx.move_call(Address::SUI_FRAMEWORK).unwrap().0.function, "into_balance");
+        assert!(tx.move_call(address(0xac)).is_none());
+    }
+
+    #[test]
+    fn move_call_traces_results_back_to_their_input() {
+        let mut ptb = PtbBuilder::new();
+        let coin = ptb.object(ObjectArg::ImmOrOwnedObject((address(7), 1, Digest(vec![0; 32]))));
+        let balance = ptb.coin_into_balance(coin);
+        let pool = ptb.object(ObjectArg::SharedObject { id: address(8), initial_shared_version: 1, mutable: true });
+        ptb.move_call(address(0xab), "attention", "fund_pool", vec![], vec![pool, balance]);
+        let gas_data = GasData { payment: vec![], owner: address(0xaa), price: 1, budget: 1 };
+        let tx = TransactionData::new(ptb.finish(), address(0xaa), gas_data);
+
+        let (_, inputs) = tx.move_call(address(0xab)).unwrap();
+        assert_eq!(inputs[0].and_then(CallArg::address), Some(address(8)));
+        assert_eq!(inputs[1].and_then(CallArg::address), Some(address(7)));
+    }
+
+    #[test]
+    fn results_of_later_commands_resolve_to_nothing() {
+        let ptb = ProgrammableTransaction {
+            inputs: vec![CallArg::Pure(vec![])],
+            commands: vec![Command::SplitCoins(Argument::Result(0), vec![Argument::Input(0)])],
+        };
+        assert!(ptb.resolve(Argument::Result(0), 0).is_none());
+        assert!(ptb.resolve(Argument::NestedResult(3, 0), 5).is_none());
+    }
+
+    #[test]
+    fn pure_address_inputs_resolve_to_their_address() {
+        assert_eq!(CallArg::Pure(address(0xaa).0.to_vec()).address(), Some(address(0xaa)));
+        assert_eq!(CallArg::Pure(5u64.to_le_bytes().to_vec()).address(), None);
+    }
+}
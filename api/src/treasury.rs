@@ -0,0 +1,159 @@
+use sqlx::SqlitePool;
+use suiter_core::ledger;
+
+use crate::attention_rules as rules;
+
+/// Where the attention pool stands, from the ledger
+#[derive(Debug, Clone, Copy)]
+pub struct PoolStatus {
+    /// Funding received less what claimed rewards drew out
+    pub balance: i64,
+    /// What ended-but-unclaimed sessions will draw out once claimed
+    pub outstanding: i64,
+    /// Average daily draw over the burn window
+    pub burn_per_day: i64,
+}
+
+impl PoolStatus {
+    /// Balance not yet promised to unclaimed sessions
+    pub fn available(&self) -> i64 {
+        self.balance - self.outstanding
+    }
+
+    /// Days until the available balance runs out at the current burn rate
+    pub fn runway_days(&self) -> Option<f64> {
+        (self.burn_per_day > 0).then(|| self.available().max(0) as f64 / self.burn_per_day as f64)
+    }
+
+    pub fn throttle_factor(&self) -> u64 {
+        rules::throttle_factor(self.available(), self.burn_per_day)
+    }
+}
+
+/// A claim draws its whole reward from ATTENTION_REWARDS and returns the
+/// pool's share to ATTENTION_POOL, so the pool's balance and draw are both
+/// sums over the two accounts
+///
+/// The balance is the ledger's, not the chain's: funding comes in through the
+/// pool tracker's snapshots, but claims settle here. The on-chain balance only
+/// drops when a reward is paid out on chain (`total_rewarded`), so the two
+/// drift apart by whatever claims settled here that the chain hasn't paid;
+/// see [`drift`].
+pub async fn load(pool: &SqlitePool) -> Result<PoolStatus, sqlx::Error> {
+    let (balance, drawn, outstanding): (i64, i64, i64) = sqlx::query_as(
+        r#"
+        SELECT
+            (SELECT COALESCE(SUM(amount), 0) FROM ledger_entries WHERE account IN (?1, ?2)),
+            (SELECT COALESCE(-SUM(amount), 0) FROM ledger_entries
+                WHERE account IN (?1, ?2) AND entry_type = 'attention_reward'
+                  AND created_at >= datetime(CURRENT_TIMESTAMP, '-' || ?3 || ' days')),
+            (SELECT COALESCE(SUM(reward), 0) FROM attention_sessions WHERE validity = 'valid' AND status = 'ended' AND claimed = FALSE)
+        "#
+    )
+    .bind(ledger::ATTENTION_POOL)
+    .bind(ledger::ATTENTION_REWARDS)
+    .bind(rules::BURN_WINDOW_DAYS)
+    .fetch_one(pool)
+    .await?;
+
+    // The pool's share of outstanding rewards comes straight back
+    let outstanding = outstanding - rules::split_reward(outstanding).pool;
+
+    Ok(PoolStatus {
+        balance,
+        outstanding,
+        burn_per_day: drawn / rules::BURN_WINDOW_DAYS,
+    })
+}
+
+/// How far the on-chain pool balance runs ahead of the ledger's
+pub fn drift(status: &PoolStatus, chain_balance: i64) -> i64 {
+    chain_balance - status.balance
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_state;
+    use suiter_core::ledger::LedgerTxn;
+
+    fn status(balance: i64, outstanding: i64, burn_per_day: i64) -> PoolStatus {
+        PoolStatus { balance, outstanding, burn_per_day }
+    }
+
+    async fn post(pool: &SqlitePool, txn_id: &str, entry_type: &str, legs: &[(&str, &str, i64)]) {
+        let mut txn = LedgerTxn::new();
+        for (from, to, amount) in legs {
+            txn.transfer(from, to, *amount);
+        }
+        let mut tx = pool.begin().await.unwrap();
+        txn.post(&mut tx, txn_id, entry_type, None).await.unwrap();
+        tx.commit().await.unwrap();
+    }
+
+    #[test]
+    fn runway_counts_days_of_burn_left_in_the_available_balance() {
+        assert_eq!(status(1_000, 400, 100).available(), 600);
+        assert_eq!(status(1_000, 400, 100).runway_days(), Some(6.0));
+        assert_eq!(status(1_000, 0, 400).runway_days(), Some(2.5));
+        // Overcommitted pools have no runway left, idle ones an unbounded one
+        assert_eq!(status(100, 400, 100).runway_days(), Some(0.0));
+        assert_eq!(status(1_000, 0, 0).runway_days(), None);
+    }
+
+    #[test]
+    fn throttle_factor_reads_the_available_balance() {
+        let burn = 100;
+        let full = burn * rules::THROTTLE_RUNWAY_DAYS;
+        assert_eq!(status(full, 0, burn).throttle_factor(), 10_000);
+        assert_eq!(status(full, full / 2, burn).throttle_factor(), 5_000);
+        assert_eq!(status(full, full, burn).throttle_factor(), 0);
+    }
+
+    #[tokio::test]
+    async fn load_nets_claims_and_unclaimed_sessions_against_funding() {
+        let state = test_state().await;
+        let pool = &state.pool;
+
+        post(pool, "fund:attention:1", "pool_funding", &[(ledger::POOL_FUNDING, ledger::ATTENTION_POOL, 1_000_000)]).await;
+        // A claimed 7_000 reward: 5_600 goes out, the pool's 1_400 comes back
+        let split = rules::split_reward(7_000);
+        post(pool, "reward:session:s1", "attention_reward", &[
+            (ledger::ATTENTION_REWARDS, &ledger::user("0xauthor"), split.creator),
+            (ledger::ATTENTION_REWARDS, &ledger::user("0xreader"), split.reader),
+            (ledger::ATTENTION_REWARDS, ledger::ATTENTION_POOL, split.pool),
+        ]).await;
+
+        sqlx::query("INSERT INTO profiles(address, reputation) VALUES ('0xauthor', 50), ('0xreader', 50)")
+            .execute(pool)
+            .await
+            .unwrap();
+        sqlx::query("INSERT INTO posts(id, author, content_hash, created_at) VALUES ('0xpost', '0xauthor', 'h', CURRENT_TIMESTAMP)")
+            .execute(pool)
+            .await
+            .unwrap();
+        // Only valid, ended, unclaimed sessions are outstanding
+        sqlx::query(
+            r#"
+            INSERT INTO attention_sessions(id, reader, post_id, duration_ms, reward, claimed, status, validity) VALUES
+                ('s1', '0xreader', '0xpost', 1, 7000, TRUE, 'ended', 'valid'),
+                ('s2', '0xreader', '0xpost', 1, 1000, FALSE, 'ended', 'valid'),
+                ('s3', '0xreader', '0xpost', 1, 1000, FALSE, 'ended', 'invalid'),
+                ('s4', '0xreader', '0xpost', 1, 1000, FALSE, 'active', 'valid')
+            "#
+        )
+        .execute(pool)
+        .await
+        .unwrap();
+
+        let status = load(pool).await.unwrap();
+        assert_eq!(status.balance, 1_000_000 - 5_600);
+        assert_eq!(status.outstanding, 800);
+        assert_eq!(status.burn_per_day, 5_600 / rules::BURN_WINDOW_DAYS);
+        assert_eq!(status.available(), 1_000_000 - 5_600 - 800);
+
+        // The chain hasn't paid the claim out, so it still holds the 5_600
+        assert_eq!(drift(&status, 1_000_000), 5_600);
+        assert_eq!(drift(&status, status.balance), 0);
+    }
+}
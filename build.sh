@@ -49,7 +49,7 @@ echo ""
 echo "✅ Build complete!"
 echo ""
 echo "Next steps:"
-echo "1. Setup database: sudo -u postgres createdb suiter && psql suiter < database/migrations/001_init.sql"
+echo "1. Setup database: apply database/migrations/ in order with sqlite3 suiter.db < <file>, skipping 002_monitoring.sql"
 echo "2. Start indexer: ./indexer/target/release/suiter-indexer"
 echo "3. Start API: ./api/target/release/suiter-api"
 echo "4. Deploy contracts: sui client publish --gas-budget 200000000 ./contracts"
@@ -1,6 +1,7 @@
 module suiter::attention {
     use sui::object::{Self, UID, ID};
     use sui::tx_context::{Self, TxContext};
+    use sui::transfer;
     use sui::balance::{Self, Balance};
     use sui::sui::SUI;
 
@@ -85,6 +86,17 @@ module suiter::attention {
         session
     }
 
+    /// Start a session owned by the sender, who ends it and claims the reward
+    entry fun start_session_entry(
+        reader: address,
+        post_id: ID,
+        reader_rep: u64,
+        ctx: &mut TxContext,
+    ) {
+        let session = start_session(reader, post_id, reader_rep, ctx);
+        transfer::transfer(session, tx_context::sender(ctx));
+    }
+
     /// End session and calculate reward
     /// Formula: Reward = BASE × W_time × W_rep
     public fun end_session(
@@ -72,6 +72,16 @@ module suiter::creator_lifeline {
         lifeline
     }
 
+    /// Create a lifeline and share it so anyone can send support
+    entry fun create_lifeline_entry(
+        recipient: address,
+        recipient_rep: u64,
+        ctx: &mut TxContext,
+    ) {
+        let lifeline = create_lifeline(recipient, recipient_rep, ctx);
+        transfer::share_object(lifeline);
+    }
+
     /// Send support to creator
     public fun send_support(
         lifeline: &mut LifelineSupport,
@@ -1,6 +1,7 @@
 module suiter::post {
     use sui::object::{Self, UID, ID};
     use sui::tx_context::{Self, TxContext};
+    use sui::transfer;
 
     // ============ CONSTANTS ============
     const LEVEL_1_THRESHOLD: u64 = 0;      // Any post starts at L1
@@ -44,6 +45,12 @@ module suiter::post {
         attention: u64,
     }
 
+    public struct ReplyAdded has copy, drop {
+        post_id: ID,
+        reply_id: ID,
+        author: address,
+    }
+
     // ============ PUBLIC FUNCTIONS ============
 
     /// Create a new post
@@ -76,6 +83,47 @@ module suiter::post {
         post
     }
 
+    /// Create a post owned by the sender
+    entry fun create_post_entry(
+        author: address,
+        content_hash: vector<u8>,
+        ctx: &mut TxContext,
+    ) {
+        let post = create_post(author, content_hash, ctx);
+        transfer::transfer(post, tx_context::sender(ctx));
+    }
+
+    /// Create a post replying to `parent`. Posts are owned by their authors,
+    /// so the parent is named by id rather than borrowed; `reply_count` is
+    /// only advanced by the parent's owner through `add_reply`.
+    public fun create_reply(
+        parent: ID,
+        author: address,
+        content_hash: vector<u8>,
+        ctx: &mut TxContext,
+    ): Post {
+        let reply = create_post(author, content_hash, ctx);
+
+        sui::event::emit(ReplyAdded {
+            post_id: parent,
+            reply_id: object::id(&reply),
+            author,
+        });
+
+        reply
+    }
+
+    /// Create a reply owned by the sender
+    entry fun create_reply_entry(
+        parent: ID,
+        author: address,
+        content_hash: vector<u8>,
+        ctx: &mut TxContext,
+    ) {
+        let reply = create_reply(parent, author, content_hash, ctx);
+        transfer::transfer(reply, tx_context::sender(ctx));
+    }
+
     /// Add attention to post and auto-level up if thresholds met
     public fun add_attention(post: &mut Post, amount: u64): u8 {
         let old_attention = post.attention_accumulated;
@@ -73,6 +73,13 @@ module suiter::profile {
         profile
     }
 
+    /// Create a profile owned by the sender. `Profile` is key-only, so only
+    /// this module can transfer it.
+    entry fun create_profile_entry(ctx: &mut TxContext) {
+        let profile = create_profile(ctx);
+        transfer::transfer(profile, tx_context::sender(ctx));
+    }
+
     /// Add reputation (capped at MAX_REPUTATION)
     public fun add_reputation(profile: &mut Profile, amount: u64): u64 {
         let old_rep = profile.reputation;
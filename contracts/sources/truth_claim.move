@@ -1,6 +1,7 @@
 module suiter::truth_claim {
     use sui::object::{Self, UID, ID};
     use sui::tx_context::{Self, TxContext};
+    use sui::transfer;
 
     // ============ CONSTANTS ============
     const MIN_REPUTATION_TO_VOTE: u64 = 50;
@@ -86,6 +87,18 @@ module suiter::truth_claim {
         claim
     }
 
+    /// Create a claim and share it so anyone can vote on it
+    entry fun create_claim_entry(
+        post_id: ID,
+        claimer: address,
+        claim_text: vector<u8>,
+        claimer_rep: u64,
+        ctx: &mut TxContext,
+    ) {
+        let claim = create_claim(post_id, claimer, claim_text, claimer_rep, ctx);
+        transfer::share_object(claim);
+    }
+
     /// Cast a vote on a claim (quadratic: votes = floor(sqrt(rep)))
     public fun vote_on_claim(
         claim: &mut TruthClaim,
@@ -97,6 +110,7 @@ module suiter::truth_claim {
         assert!(voter_rep >= MIN_REPUTATION_TO_VOTE, 1);
         assert!(current_time < claim.voting_end, 2);
         assert!(!has_voted(claim, voter), 3);
+        assert!(voter != claim.claimer, 4);
 
         // Quadratic voting: votes = floor(sqrt(rep))
         let voting_power = sqrt_u64(voter_rep);
@@ -2,6 +2,7 @@
 module suiter::post_tests {
     use suiter::post;
     use sui::tx_context;
+    use sui::object;
 
     #[test]
     fun test_create_post() {
@@ -65,4 +66,18 @@ module suiter::post_tests {
         
         let _ = post_obj;
     }
+
+    #[test]
+    fun test_create_reply() {
+        let ctx = &mut tx_context::dummy();
+        let parent = post::create_post(@0x1, b"parent", ctx);
+
+        let reply = post::create_reply(object::id(&parent), @0x2, b"reply", ctx);
+
+        assert!(post::get_author(&reply) == @0x2, 1);
+        assert!(post::get_level(&reply) == 1, 2);
+
+        let _ = parent;
+        let _ = reply;
+    }
 }
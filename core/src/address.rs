@@ -0,0 +1,119 @@
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use sqlx::{
+    encode::IsNull,
+    error::BoxDynError,
+    sqlite::{SqliteArgumentValue, SqliteTypeInfo, SqliteValueRef},
+    Decode, Encode, Sqlite, Type,
+};
+use std::fmt;
+
+/// Hex digits in a 32-byte address
+const ADDRESS_HEX_LEN: usize = 64;
+
+/// A Sui address or object id in canonical form: `0x` followed by 64
+/// lowercase hex digits. Short forms such as `0x2` are left-padded, so every
+/// spelling of an address maps to one profile.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct SuiAddress(String);
+
+/// Input that isn't `0x` followed by 1 to 64 hex digits
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvalidAddress(pub String);
+
+impl fmt::Display for InvalidAddress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid Sui address {:?}: expected 0x followed by up to 64 hex digits", self.0)
+    }
+}
+
+impl std::error::Error for InvalidAddress {}
+
+impl SuiAddress {
+    pub fn parse(s: &str) -> Result<Self, InvalidAddress> {
+        let trimmed = s.trim();
+        let digits = trimmed
+            .strip_prefix("0x")
+            .or_else(|| trimmed.strip_prefix("0X"))
+            .ok_or_else(|| InvalidAddress(s.to_string()))?;
+        if digits.is_empty() || digits.len() > ADDRESS_HEX_LEN || !digits.bytes().all(|b| b.is_ascii_hexdigit()) {
+            return Err(InvalidAddress(s.to_string()));
+        }
+
+        Ok(SuiAddress(format!("0x{:0>64}", digits.to_ascii_lowercase())))
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    pub fn to_bytes(&self) -> [u8; 32] {
+        let mut bytes = [0u8; 32];
+        let digits = &self.0.as_bytes()[2..];
+        for (i, byte) in bytes.iter_mut().enumerate() {
+            *byte = (hex_value(digits[2 * i]) << 4) | hex_value(digits[2 * i + 1]);
+        }
+        bytes
+    }
+}
+
+/// Value of a lowercase hex digit; `parse` guarantees nothing else gets here
+fn hex_value(digit: u8) -> u8 {
+    match digit {
+        b'0'..=b'9' => digit - b'0',
+        _ => digit - b'a' + 10,
+    }
+}
+
+impl fmt::Display for SuiAddress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl AsRef<str> for SuiAddress {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl std::str::FromStr for SuiAddress {
+    type Err = InvalidAddress;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+impl Serialize for SuiAddress {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.0)
+    }
+}
+
+impl<'de> Deserialize<'de> for SuiAddress {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let raw = String::deserialize(deserializer)?;
+        SuiAddress::parse(&raw).map_err(de::Error::custom)
+    }
+}
+
+// Stored as TEXT, so addresses bind and decode like the strings they replace
+
+impl Type<Sqlite> for SuiAddress {
+    fn type_info() -> SqliteTypeInfo {
+        <String as Type<Sqlite>>::type_info()
+    }
+}
+
+impl<'q> Encode<'q, Sqlite> for SuiAddress {
+    fn encode_by_ref(&self, args: &mut Vec<SqliteArgumentValue<'q>>) -> IsNull {
+        <String as Encode<'q, Sqlite>>::encode(self.0.clone(), args)
+    }
+}
+
+impl<'r> Decode<'r, Sqlite> for SuiAddress {
+    fn decode(value: SqliteValueRef<'r>) -> Result<Self, BoxDynError> {
+        let raw = <String as Decode<'r, Sqlite>>::decode(value)?;
+        Ok(SuiAddress::parse(&raw)?)
+    }
+}
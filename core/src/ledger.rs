@@ -0,0 +1,165 @@
+use sqlx::{Sqlite, Transaction};
+use std::collections::BTreeMap;
+
+/// Slashed stakes that no correct participant could receive
+pub const TREASURY: &str = "treasury";
+
+/// Attention rewards as they are paid out of the on-chain AttentionPool
+pub const ATTENTION_REWARDS: &str = "rewards:attention";
+/// The attention pool's funding and its share of each reward; together with
+/// ATTENTION_REWARDS it sums to what the pool has left
+pub const ATTENTION_POOL: &str = "pool:attention";
+/// Where pool funding comes from
+pub const POOL_FUNDING: &str = "funding:attention";
+
+/// Funds an address stakes from; claims top it up from `user` as needed
+pub fn wallet(address: &str) -> String {
+    format!("wallet:{}", address)
+}
+
+/// Funds owed to an address
+pub fn user(address: &str) -> String {
+    format!("user:{}", address)
+}
+
+/// Stakes locked in a claim until it settles
+pub fn claim_escrow(claim_id: &str) -> String {
+    format!("escrow:claim:{}", claim_id)
+}
+
+/// Current balance of one account, read inside the caller's transaction
+pub async fn balance(tx: &mut Transaction<'_, Sqlite>, account: &str) -> Result<i64, sqlx::Error> {
+    sqlx::query_scalar("SELECT COALESCE(SUM(amount), 0) FROM ledger_entries WHERE account = ?")
+        .bind(account)
+        .fetch_one(&mut **tx)
+        .await
+}
+
+/// Double-entry ledger writer
+/// A transaction is a set of legs that sum to zero, written under a
+/// caller-chosen `txn_id`. Posting the same `txn_id` twice is a no-op, which
+/// makes settlement jobs safe to retry.
+#[derive(Debug, Default)]
+pub struct LedgerTxn {
+    legs: BTreeMap<String, i64>,
+}
+
+impl LedgerTxn {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Move `amount` from one account to another
+    pub fn transfer(&mut self, from: &str, to: &str, amount: i64) -> &mut Self {
+        *self.legs.entry(from.to_string()).or_insert(0) -= amount;
+        *self.legs.entry(to.to_string()).or_insert(0) += amount;
+        self
+    }
+
+    /// Write the legs; returns false if `txn_id` was already posted
+    pub async fn post(
+        &self,
+        tx: &mut Transaction<'_, Sqlite>,
+        txn_id: &str,
+        entry_type: &str,
+        claim_id: Option<&str>,
+    ) -> Result<bool, sqlx::Error> {
+        if self.legs.values().sum::<i64>() != 0 {
+            return Err(sqlx::Error::Protocol(format!("unbalanced ledger transaction {}", txn_id)));
+        }
+
+        let posted: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM ledger_entries WHERE txn_id = ?")
+            .bind(txn_id)
+            .fetch_one(&mut **tx)
+            .await?;
+        if posted > 0 {
+            return Ok(false);
+        }
+
+        for (account, amount) in self.legs.iter().filter(|(_, a)| **a != 0) {
+            sqlx::query("INSERT INTO ledger_entries(txn_id, account, amount, entry_type, claim_id, created_at) VALUES (?, ?, ?, ?, ?, CURRENT_TIMESTAMP)")
+                .bind(txn_id)
+                .bind(account)
+                .bind(amount)
+                .bind(entry_type)
+                .bind(claim_id)
+                .execute(&mut **tx)
+                .await?;
+        }
+
+        Ok(true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::migrated_pool;
+
+    async fn entries(tx: &mut Transaction<'_, Sqlite>, txn_id: &str) -> i64 {
+        sqlx::query_scalar("SELECT COUNT(*) FROM ledger_entries WHERE txn_id = ?")
+            .bind(txn_id)
+            .fetch_one(&mut **tx)
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn post_moves_funds_between_accounts() {
+        let pool = migrated_pool().await;
+        let mut tx = pool.begin().await.unwrap();
+
+        let posted = LedgerTxn::new()
+            .transfer(ATTENTION_REWARDS, &user("0xa"), 70)
+            .transfer(ATTENTION_REWARDS, &user("0xb"), 30)
+            .post(&mut tx, "t1", "attention_reward", None)
+            .await
+            .unwrap();
+        assert!(posted);
+        assert_eq!(balance(&mut tx, &user("0xa")).await.unwrap(), 70);
+        assert_eq!(balance(&mut tx, &user("0xb")).await.unwrap(), 30);
+        assert_eq!(balance(&mut tx, ATTENTION_REWARDS).await.unwrap(), -100);
+        assert_eq!(balance(&mut tx, TREASURY).await.unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn legs_on_one_account_are_netted_and_zero_legs_dropped() {
+        let pool = migrated_pool().await;
+        let mut tx = pool.begin().await.unwrap();
+
+        // Out and straight back in: only the net movement is written
+        LedgerTxn::new()
+            .transfer(&wallet("0xa"), &claim_escrow("c1"), 50)
+            .transfer(&claim_escrow("c1"), &wallet("0xa"), 50)
+            .transfer(&wallet("0xa"), TREASURY, 10)
+            .post(&mut tx, "t1", "stake", Some("c1"))
+            .await
+            .unwrap();
+        assert_eq!(entries(&mut tx, "t1").await, 2);
+        assert_eq!(balance(&mut tx, &claim_escrow("c1")).await.unwrap(), 0);
+        assert_eq!(balance(&mut tx, &wallet("0xa")).await.unwrap(), -10);
+    }
+
+    #[tokio::test]
+    async fn posting_a_txn_id_twice_is_a_no_op() {
+        let pool = migrated_pool().await;
+        let mut tx = pool.begin().await.unwrap();
+        let mut txn = LedgerTxn::new();
+        txn.transfer(TREASURY, &user("0xa"), 5);
+
+        assert!(txn.post(&mut tx, "t1", "settlement", None).await.unwrap());
+        assert!(!txn.post(&mut tx, "t1", "settlement", None).await.unwrap());
+        assert_eq!(balance(&mut tx, &user("0xa")).await.unwrap(), 5);
+    }
+
+    #[tokio::test]
+    async fn unbalanced_transactions_are_refused() {
+        let pool = migrated_pool().await;
+        let mut tx = pool.begin().await.unwrap();
+        let mut txn = LedgerTxn::new();
+        txn.legs.insert(user("0xa"), 5);
+
+        assert!(txn.post(&mut tx, "t1", "settlement", None).await.is_err());
+        assert_eq!(entries(&mut tx, "t1").await, 0);
+    }
+}
@@ -0,0 +1,11 @@
+pub mod address;
+pub mod ledger;
+pub mod notifications;
+pub mod reputation;
+pub mod schema;
+pub mod search;
+pub mod stream;
+pub mod tags;
+#[cfg(any(test, feature = "testing"))]
+pub mod testing;
+pub mod webhooks;
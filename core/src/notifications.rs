@@ -0,0 +1,82 @@
+use sqlx::{Sqlite, SqlitePool, Transaction};
+
+/// What a notification is about
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Kind {
+    /// The recipient's post reached a new level
+    LevelUp,
+    /// Someone filed a truth claim against the recipient's post
+    ClaimCreated,
+    /// A claim the recipient filed, or one against their post, was resolved
+    ClaimResolved,
+    /// The recipient's lifeline received support
+    SupportReceived,
+    /// Someone replied to the recipient's post
+    Reply,
+}
+
+impl Kind {
+    pub const ALL: [Kind; 5] = [Kind::LevelUp, Kind::ClaimCreated, Kind::ClaimResolved, Kind::SupportReceived, Kind::Reply];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Kind::LevelUp => "level_up",
+            Kind::ClaimCreated => "claim_created",
+            Kind::ClaimResolved => "claim_resolved",
+            Kind::SupportReceived => "support_received",
+            Kind::Reply => "reply",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        Kind::ALL.into_iter().find(|k| k.as_str() == s)
+    }
+}
+
+/// Record a notification for `recipient` unless they muted `kind` for every
+/// sink. `source_ref` identifies the cause, so replayed events notify once.
+/// Returns whether a notification was recorded.
+pub async fn notify(
+    pool: &SqlitePool,
+    recipient: &str,
+    kind: Kind,
+    source_ref: &str,
+    payload: &serde_json::Value,
+) -> Result<bool, sqlx::Error> {
+    let mut tx = pool.begin().await?;
+    let notified = notify_in(&mut tx, recipient, kind, source_ref, payload).await?;
+    tx.commit().await?;
+    Ok(notified)
+}
+
+/// `notify` inside the caller's transaction
+pub async fn notify_in(
+    tx: &mut Transaction<'_, Sqlite>,
+    recipient: &str,
+    kind: Kind,
+    source_ref: &str,
+    payload: &serde_json::Value,
+) -> Result<bool, sqlx::Error> {
+    sqlx::query("INSERT OR IGNORE INTO profiles(address, reputation, total_posts, total_attention_earned, joined_at, updated_at) VALUES (?, 50, 0, 0, CURRENT_TIMESTAMP, CURRENT_TIMESTAMP)")
+        .bind(recipient)
+        .execute(&mut **tx)
+        .await?;
+
+    let inserted = sqlx::query(
+        r#"
+        INSERT OR IGNORE INTO notifications(recipient, kind, source_ref, payload, in_app, created_at)
+        SELECT ?1, ?2, ?3, ?4, COALESCE(p.in_app, TRUE), CURRENT_TIMESTAMP
+        FROM (SELECT 1)
+        LEFT JOIN notification_preferences p ON p.address = ?1 AND p.kind = ?2
+        WHERE COALESCE(p.in_app, TRUE) OR COALESCE(p.webhook, TRUE)
+        "#
+    )
+    .bind(recipient)
+    .bind(kind.as_str())
+    .bind(source_ref)
+    .bind(payload.to_string())
+    .execute(&mut **tx)
+    .await?;
+
+    Ok(inserted.rows_affected() > 0)
+}
@@ -0,0 +1,329 @@
+use sqlx::{Row, Sqlite, SqlitePool, Transaction};
+
+// ============ CONSTANTS (mirrors suiter::profile) ============
+
+/// Every profile starts here; its reputation is this plus the sum of its events
+pub const STARTING_REPUTATION: i64 = 50;
+pub const REPUTATION_FLOOR: i64 = 50;
+pub const MAX_REPUTATION: i64 = 100_000;
+
+// ============ RULES (mirrors suiter::reputation) ============
+
+const BASE_GAIN: i64 = 10;
+const BASE_LOSS: i64 = 5;
+/// Min votes backing a claim before its post's author is penalized
+const DOUBT_THRESHOLD: i64 = 3;
+const DOUBT_MULTIPLIER: i64 = 2;
+
+/// Posts with no valid attention this many days after publishing are abandoned
+pub const ABANDONMENT_DAYS: i64 = 7;
+/// Claimer reward for an accepted claim and penalty for a rejected one
+pub const CLAIM_ACCEPTED_GAIN: i64 = 20;
+pub const CLAIM_REJECTED_LOSS: i64 = 10;
+
+/// gain = BASE_GAIN × ⌊log2(reward + 1)⌋, reward in MIST
+pub fn attention_gain(reward: i64) -> i64 {
+    let n = reward.max(0) + 1;
+    BASE_GAIN * (63 - n.leading_zeros() as i64)
+}
+
+/// loss = BASE_LOSS × √days_inactive. `calculate_abandonment_loss` divides
+/// by 100 as well, which rounds every realistic loss to zero.
+pub fn abandonment_loss(days_inactive: i64) -> i64 {
+    BASE_LOSS * (days_inactive.max(0) as f64).sqrt() as i64
+}
+
+/// loss = BASE_LOSS × (1 + doubt_votes) × 2, once doubt reaches the threshold
+pub fn doubt_loss(doubt_votes: i64) -> i64 {
+    if doubt_votes < DOUBT_THRESHOLD {
+        return 0;
+    }
+    BASE_LOSS * (1 + doubt_votes) * DOUBT_MULTIPLIER
+}
+
+/// Why reputation changed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Reason {
+    AttentionGain,
+    AbandonmentLoss,
+    DoubtLoss,
+    ClaimOutcome,
+    Decay,
+    AdminAdjustment,
+    /// Balance reported by a chain event
+    ChainSync,
+    /// Backfilled difference for balances that predate the ledger
+    OpeningBalance,
+}
+
+impl Reason {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Reason::AttentionGain => "attention_gain",
+            Reason::AbandonmentLoss => "abandonment_loss",
+            Reason::DoubtLoss => "doubt_loss",
+            Reason::ClaimOutcome => "claim_outcome",
+            Reason::Decay => "decay",
+            Reason::AdminAdjustment => "admin_adjustment",
+            Reason::ChainSync => "chain_sync",
+            Reason::OpeningBalance => "opening_balance",
+        }
+    }
+}
+
+/// A profile whose materialized reputation disagrees with its events
+#[derive(Debug, Clone)]
+pub struct Inconsistency {
+    pub address: String,
+    pub materialized: i64,
+    pub ledger: i64,
+    /// `balance_after` of the latest event, if any
+    pub last_balance: Option<i64>,
+}
+
+/// Reputation event ledger
+/// `reputation_events` is append-only and `profiles.reputation` is its
+/// materialized sum. All changes go through `apply`/`set` so the two move
+/// together in one transaction; `check` and `rebuild` audit and repair them.
+pub struct ReputationLedger;
+
+impl ReputationLedger {
+    /// Change an address's reputation by `delta` and append the event.
+    /// The result is clamped to [REPUTATION_FLOOR, MAX_REPUTATION] and the
+    /// recorded delta is what was actually applied. Returns the new balance,
+    /// or None if this `reason`/`source_ref` was already recorded or nothing
+    /// changed.
+    pub async fn apply(
+        tx: &mut Transaction<'_, Sqlite>,
+        address: &str,
+        delta: i64,
+        reason: Reason,
+        source_ref: &str,
+    ) -> Result<Option<i64>, sqlx::Error> {
+        sqlx::query("INSERT OR IGNORE INTO profiles(address, reputation, total_posts, total_attention_earned, joined_at, updated_at) VALUES (?, ?, 0, 0, CURRENT_TIMESTAMP, CURRENT_TIMESTAMP)")
+            .bind(address)
+            .bind(STARTING_REPUTATION)
+            .execute(&mut **tx)
+            .await?;
+
+        let row = sqlx::query(
+            "SELECT reputation, EXISTS(SELECT 1 FROM reputation_events WHERE address = ?1 AND reason = ?2 AND source_ref = ?3) as recorded FROM profiles WHERE address = ?1"
+        )
+        .bind(address)
+        .bind(reason.as_str())
+        .bind(source_ref)
+        .fetch_one(&mut **tx)
+        .await?;
+        if row.get::<bool, _>("recorded") {
+            return Ok(None);
+        }
+
+        let current = row.get::<i64, _>("reputation");
+        let balance = (current + delta).clamp(REPUTATION_FLOOR, MAX_REPUTATION);
+        if balance == current {
+            return Ok(None);
+        }
+
+        sqlx::query("UPDATE profiles SET reputation = ?, updated_at = CURRENT_TIMESTAMP WHERE address = ?")
+            .bind(balance)
+            .bind(address)
+            .execute(&mut **tx)
+            .await?;
+        sqlx::query("INSERT INTO reputation_events(address, delta, reason, source_ref, balance_after, created_at) VALUES (?, ?, ?, ?, ?, CURRENT_TIMESTAMP)")
+            .bind(address)
+            .bind(balance - current)
+            .bind(reason.as_str())
+            .bind(source_ref)
+            .bind(balance)
+            .execute(&mut **tx)
+            .await?;
+
+        Ok(Some(balance))
+    }
+
+    /// Move an address's reputation to an absolute `balance`, e.g. one
+    /// reported by the chain. The balance is authoritative: gains recorded
+    /// here that the chain hasn't seen are overwritten, and the event's delta
+    /// records what they came to.
+    pub async fn set(
+        tx: &mut Transaction<'_, Sqlite>,
+        address: &str,
+        balance: i64,
+        reason: Reason,
+        source_ref: &str,
+    ) -> Result<Option<i64>, sqlx::Error> {
+        let current: Option<i64> = sqlx::query_scalar("SELECT reputation FROM profiles WHERE address = ?")
+            .bind(address)
+            .fetch_optional(&mut **tx)
+            .await?;
+
+        Self::apply(tx, address, balance - current.unwrap_or(STARTING_REPUTATION), reason, source_ref).await
+    }
+
+    /// Profiles whose reputation differs from STARTING_REPUTATION plus the
+    /// sum of their events, or from the balance their last event recorded
+    pub async fn check(pool: &SqlitePool) -> Result<Vec<Inconsistency>, sqlx::Error> {
+        let rows = sqlx::query(
+            r#"
+            SELECT p.address, p.reputation,
+                ?1 + COALESCE(SUM(e.delta), 0) as ledger,
+                (SELECT balance_after FROM reputation_events WHERE address = p.address ORDER BY id DESC LIMIT 1) as last_balance
+            FROM profiles p
+            LEFT JOIN reputation_events e ON e.address = p.address
+            GROUP BY p.address
+            HAVING p.reputation != ledger OR (last_balance IS NOT NULL AND last_balance != p.reputation)
+            "#
+        )
+        .bind(STARTING_REPUTATION)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(rows
+            .iter()
+            .map(|r| Inconsistency {
+                address: r.get("address"),
+                materialized: r.get("reputation"),
+                ledger: r.get("ledger"),
+                last_balance: r.get("last_balance"),
+            })
+            .collect())
+    }
+
+    /// Rewrite `profiles.reputation` from the ledger; returns profiles changed
+    pub async fn rebuild(pool: &SqlitePool) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query(
+            r#"
+            UPDATE profiles
+            SET reputation = ?1 + (SELECT COALESCE(SUM(delta), 0) FROM reputation_events WHERE address = profiles.address),
+                updated_at = CURRENT_TIMESTAMP
+            WHERE reputation != ?1 + (SELECT COALESCE(SUM(delta), 0) FROM reputation_events WHERE address = profiles.address)
+            "#
+        )
+        .bind(STARTING_REPUTATION)
+        .execute(pool)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::migrated_pool;
+
+    async fn apply(pool: &SqlitePool, address: &str, delta: i64, reason: Reason, source_ref: &str) -> Option<i64> {
+        let mut tx = pool.begin().await.unwrap();
+        let balance = ReputationLedger::apply(&mut tx, address, delta, reason, source_ref).await.unwrap();
+        tx.commit().await.unwrap();
+        balance
+    }
+
+    async fn set(pool: &SqlitePool, address: &str, balance: i64, source_ref: &str) -> Option<i64> {
+        let mut tx = pool.begin().await.unwrap();
+        let balance = ReputationLedger::set(&mut tx, address, balance, Reason::ChainSync, source_ref).await.unwrap();
+        tx.commit().await.unwrap();
+        balance
+    }
+
+    async fn deltas(pool: &SqlitePool, address: &str) -> Vec<i64> {
+        sqlx::query_scalar("SELECT delta FROM reputation_events WHERE address = ? ORDER BY id")
+            .bind(address)
+            .fetch_all(pool)
+            .await
+            .unwrap()
+    }
+
+    #[test]
+    fn gains_and_losses_follow_the_contract() {
+        assert_eq!(attention_gain(0), 0);
+        assert_eq!(attention_gain(1), BASE_GAIN);
+        assert_eq!(attention_gain(1_000_000), BASE_GAIN * 19);
+        assert_eq!(attention_gain(-5), 0);
+        assert_eq!(abandonment_loss(16), BASE_LOSS * 4);
+        assert_eq!(abandonment_loss(-1), 0);
+        assert_eq!(doubt_loss(DOUBT_THRESHOLD - 1), 0);
+        assert_eq!(doubt_loss(DOUBT_THRESHOLD), BASE_LOSS * (1 + DOUBT_THRESHOLD) * DOUBT_MULTIPLIER);
+    }
+
+    #[tokio::test]
+    async fn apply_clamps_and_records_what_was_applied() {
+        let pool = migrated_pool().await;
+
+        // Unknown addresses start at STARTING_REPUTATION
+        assert_eq!(apply(&pool, "0xa", 100, Reason::AttentionGain, "s1").await, Some(STARTING_REPUTATION + 100));
+        assert_eq!(apply(&pool, "0xa", -1_000, Reason::DoubtLoss, "c1").await, Some(REPUTATION_FLOOR));
+        // Nothing left to lose: no event
+        assert_eq!(apply(&pool, "0xa", -5, Reason::Decay, "d1").await, None);
+        assert_eq!(apply(&pool, "0xa", 1_000_000, Reason::AdminAdjustment, "a1").await, Some(MAX_REPUTATION));
+
+        assert_eq!(deltas(&pool, "0xa").await, [100, -100, MAX_REPUTATION - REPUTATION_FLOOR]);
+    }
+
+    #[tokio::test]
+    async fn replayed_causes_are_ignored() {
+        let pool = migrated_pool().await;
+
+        assert_eq!(apply(&pool, "0xa", 10, Reason::AttentionGain, "s1").await, Some(60));
+        assert_eq!(apply(&pool, "0xa", 10, Reason::AttentionGain, "s1").await, None);
+        // The same reference under another reason is a different cause
+        assert_eq!(apply(&pool, "0xa", 10, Reason::ClaimOutcome, "s1").await, Some(70));
+        assert_eq!(set(&pool, "0xa", 500, "0xdigest:0").await, Some(500));
+        assert_eq!(set(&pool, "0xa", 900, "0xdigest:0").await, None);
+
+        assert_eq!(deltas(&pool, "0xa").await, [10, 10, 430]);
+    }
+
+    #[tokio::test]
+    async fn set_overwrites_gains_the_chain_has_not_seen() {
+        let pool = migrated_pool().await;
+
+        // The chain knows of 60; an off-chain gain takes the profile to 90
+        set(&pool, "0xa", 60, "0xdigest:0").await;
+        apply(&pool, "0xa", 30, Reason::AttentionGain, "s1").await;
+
+        // The chain's next balance wins, and the event records the difference
+        assert_eq!(set(&pool, "0xa", 70, "0xdigest:1").await, Some(70));
+        assert_eq!(deltas(&pool, "0xa").await, [10, 30, -20]);
+        // Whereas the same change applied as a delta would have kept the gain
+        assert_eq!(apply(&pool, "0xb", 10, Reason::ChainSync, "0xdigest:0").await, Some(60));
+        assert_eq!(apply(&pool, "0xb", 30, Reason::AttentionGain, "s1").await, Some(90));
+        assert_eq!(apply(&pool, "0xb", 10, Reason::ChainSync, "0xdigest:1").await, Some(100));
+
+        assert!(ReputationLedger::check(&pool).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn check_finds_what_rebuild_repairs() {
+        let pool = migrated_pool().await;
+
+        apply(&pool, "0xa", 40, Reason::AttentionGain, "s1").await;
+        apply(&pool, "0xb", 20, Reason::AttentionGain, "s2").await;
+        sqlx::query("INSERT INTO profiles(address, reputation) VALUES ('0xc', ?)")
+            .bind(STARTING_REPUTATION)
+            .execute(&pool)
+            .await
+            .unwrap();
+        assert!(ReputationLedger::check(&pool).await.unwrap().is_empty());
+        assert_eq!(ReputationLedger::rebuild(&pool).await.unwrap(), 0);
+
+        // Writes that bypass the ledger
+        sqlx::query("UPDATE profiles SET reputation = 1000 WHERE address IN ('0xa', '0xc')")
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let mut found = ReputationLedger::check(&pool).await.unwrap();
+        found.sort_by(|a, b| a.address.cmp(&b.address));
+        let found: Vec<_> = found.iter().map(|i| (i.address.as_str(), i.materialized, i.ledger, i.last_balance)).collect();
+        assert_eq!(found, [("0xa", 1000, 90, Some(90)), ("0xc", 1000, STARTING_REPUTATION, None)]);
+
+        assert_eq!(ReputationLedger::rebuild(&pool).await.unwrap(), 2);
+        assert!(ReputationLedger::check(&pool).await.unwrap().is_empty());
+        let reputation: Vec<i64> = sqlx::query_scalar("SELECT reputation FROM profiles ORDER BY address")
+            .fetch_all(&pool)
+            .await
+            .unwrap();
+        assert_eq!(reputation, [90, 70, STARTING_REPUTATION]);
+    }
+}
@@ -0,0 +1,33 @@
+use sqlx::{Executor, SqlitePool};
+use std::path::{Path, PathBuf};
+
+/// database/migrations in this repository
+pub const MIGRATIONS_DIR: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/../database/migrations");
+
+/// Holds monitoring queries rather than schema
+const NOT_SCHEMA: &str = "002_monitoring.sql";
+
+/// Every migration in `dir` that creates schema, in the order they apply
+pub fn migrations(dir: &Path) -> std::io::Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        let is_sql = path.extension().is_some_and(|ext| ext == "sql");
+        if is_sql && path.file_name().is_some_and(|name| name != NOT_SCHEMA) {
+            files.push(path);
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+/// Apply every migration in `dir` to `pool`, in order. For databases that
+/// start empty, like the in-memory ones tests and benchmarks use; a
+/// deployed database is migrated once, by hand.
+pub async fn apply(pool: &SqlitePool, dir: &Path) -> Result<(), sqlx::Error> {
+    for path in migrations(dir)? {
+        let sql = std::fs::read_to_string(&path)?;
+        pool.execute(sql.as_str()).await?;
+    }
+    Ok(())
+}
@@ -0,0 +1,127 @@
+use sqlx::{Sqlite, SqlitePool, Transaction};
+
+/// What a search result is
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Doc {
+    /// A post's content
+    Post,
+    /// A truth claim's text
+    Claim,
+    /// A profile's display name
+    Profile,
+}
+
+impl Doc {
+    pub const ALL: [Doc; 3] = [Doc::Post, Doc::Claim, Doc::Profile];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Doc::Post => "post",
+            Doc::Claim => "claim",
+            Doc::Profile => "profile",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        Doc::ALL.into_iter().find(|d| d.as_str() == s)
+    }
+
+    /// (table, key column, text column) the document is read from. Post
+    /// content is what `content_hash` carries, as the affinity model reads it.
+    fn source(&self) -> (&'static str, &'static str, &'static str) {
+        match self {
+            Doc::Post => ("posts", "id", "content_hash"),
+            Doc::Claim => ("truth_claims", "id", "claim_text"),
+            Doc::Profile => ("profiles", "address", "display_name"),
+        }
+    }
+}
+
+/// Bring one document's index entry in line with its source row: index it,
+/// replace it, or drop it when the row is gone or has no text. Called after
+/// every write to the row; safe to repeat.
+pub async fn index(pool: &SqlitePool, doc: Doc, id: &str) -> Result<(), sqlx::Error> {
+    let mut tx = pool.begin().await?;
+    index_in(&mut tx, doc, id).await?;
+    tx.commit().await
+}
+
+/// `index` inside the caller's transaction, for writers that update the
+/// source row in the same one
+pub async fn index_in(tx: &mut Transaction<'_, Sqlite>, doc: Doc, id: &str) -> Result<(), sqlx::Error> {
+    let (table, key, text) = doc.source();
+    let body: Option<String> = sqlx::query_scalar(&format!("SELECT {} FROM {} WHERE {} = ?", text, table, key))
+        .bind(id)
+        .fetch_optional(&mut **tx)
+        .await?
+        .flatten()
+        .filter(|b: &String| !b.trim().is_empty());
+
+    match body {
+        Some(body) => {
+            let rowid: i64 = sqlx::query_scalar(
+                r#"
+                INSERT INTO search_documents(kind, ref_id, indexed_at) VALUES (?, ?, CURRENT_TIMESTAMP)
+                ON CONFLICT (kind, ref_id) DO UPDATE SET indexed_at = CURRENT_TIMESTAMP
+                RETURNING id
+                "#
+            )
+            .bind(doc.as_str())
+            .bind(id)
+            .fetch_one(&mut **tx)
+            .await?;
+            sqlx::query("DELETE FROM search_index WHERE rowid = ?")
+                .bind(rowid)
+                .execute(&mut **tx)
+                .await?;
+            sqlx::query("INSERT INTO search_index(rowid, body) VALUES (?, ?)")
+                .bind(rowid)
+                .bind(&body)
+                .execute(&mut **tx)
+                .await?;
+        }
+        None => {
+            let rowid: Option<i64> = sqlx::query_scalar("DELETE FROM search_documents WHERE kind = ? AND ref_id = ? RETURNING id")
+                .bind(doc.as_str())
+                .bind(id)
+                .fetch_optional(&mut **tx)
+                .await?;
+            if let Some(rowid) = rowid {
+                sqlx::query("DELETE FROM search_index WHERE rowid = ?")
+                    .bind(rowid)
+                    .execute(&mut **tx)
+                    .await?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Rebuild the whole index from the source tables; returns the documents
+/// indexed
+pub async fn rebuild(pool: &SqlitePool) -> Result<u64, sqlx::Error> {
+    let mut tx = pool.begin().await?;
+    sqlx::query("DELETE FROM search_index").execute(&mut *tx).await?;
+    sqlx::query("DELETE FROM search_documents").execute(&mut *tx).await?;
+
+    let mut indexed = 0;
+    for doc in Doc::ALL {
+        let (table, key, text) = doc.source();
+        sqlx::query(&format!(
+            "INSERT INTO search_documents(kind, ref_id, indexed_at) SELECT ?, {key}, CURRENT_TIMESTAMP FROM {table} WHERE TRIM(COALESCE({text}, '')) != ''"
+        ))
+        .bind(doc.as_str())
+        .execute(&mut *tx)
+        .await?;
+        indexed += sqlx::query(&format!(
+            "INSERT INTO search_index(rowid, body) SELECT d.id, s.{text} FROM search_documents d JOIN {table} s ON s.{key} = d.ref_id WHERE d.kind = ?"
+        ))
+        .bind(doc.as_str())
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+    }
+
+    tx.commit().await?;
+    Ok(indexed)
+}
@@ -0,0 +1,34 @@
+use sqlx::{Executor, Sqlite};
+
+/// Every post as it is created or re-ranked
+pub const FEED: &str = "feed";
+
+pub fn post_topic(post_id: &str) -> String {
+    format!("post:{}", post_id)
+}
+
+pub fn claim_topic(claim_id: &str) -> String {
+    format!("claim:{}", claim_id)
+}
+
+pub fn profile_topic(address: &str) -> String {
+    format!("profile:{}", address)
+}
+
+/// Live stream outbox
+/// Writers append events to `stream_events`, inside their own transaction
+/// where they have one; the API relays new rows to `GET /api/stream`
+/// subscribers. The row id is the resume token.
+pub async fn publish<'e, E>(executor: E, topic: &str, kind: &str, payload: &serde_json::Value) -> Result<(), sqlx::Error>
+where
+    E: Executor<'e, Database = Sqlite>,
+{
+    sqlx::query("INSERT INTO stream_events(topic, kind, payload, created_at) VALUES (?, ?, ?, CURRENT_TIMESTAMP)")
+        .bind(topic)
+        .bind(kind)
+        .bind(payload.to_string())
+        .execute(executor)
+        .await?;
+
+    Ok(())
+}
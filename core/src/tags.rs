@@ -0,0 +1,189 @@
+use sqlx::{Sqlite, SqlitePool, Transaction};
+
+/// Longest tag kept; longer ones are dropped, not truncated
+pub const MAX_TAG_LEN: usize = 50;
+/// Tags kept per post, in order of appearance
+pub const MAX_TAGS_PER_POST: usize = 10;
+
+/// Hashtags in `content`, lowercased and deduplicated in order of
+/// appearance. A tag is `#` followed by letters, digits or `_`, and must
+/// not start mid-word, so `C#` and `page#anchor` aren't tags.
+pub fn extract(content: &str) -> Vec<String> {
+    let mut tags: Vec<String> = Vec::new();
+    let mut prev: Option<char> = None;
+    let mut chars = content.char_indices().peekable();
+
+    while let Some((i, c)) = chars.next() {
+        let starts_tag = c == '#' && !prev.is_some_and(|p| p.is_alphanumeric() || p == '_' || p == '#');
+        prev = Some(c);
+        if !starts_tag {
+            continue;
+        }
+
+        let start = i + c.len_utf8();
+        let mut end = start;
+        while let Some(&(j, d)) = chars.peek() {
+            if !(d.is_alphanumeric() || d == '_') {
+                break;
+            }
+            end = j + d.len_utf8();
+            prev = Some(d);
+            chars.next();
+        }
+
+        if let Some(tag) = normalize(&content[start..end]) {
+            if !tags.contains(&tag) {
+                tags.push(tag);
+            }
+        }
+        if tags.len() == MAX_TAGS_PER_POST {
+            break;
+        }
+    }
+
+    tags
+}
+
+/// Canonical form of a tag as a user writes it, with or without the `#`.
+/// None unless it's 1 to `MAX_TAG_LEN` letters, digits or `_` with at least
+/// one letter, so `#1` and `#___` aren't tags.
+pub fn normalize(tag: &str) -> Option<String> {
+    let tag = tag.strip_prefix('#').unwrap_or(tag).to_lowercase();
+    let valid = !tag.is_empty()
+        && tag.chars().count() <= MAX_TAG_LEN
+        && tag.chars().all(|c| c.is_alphanumeric() || c == '_')
+        && tag.chars().any(char::is_alphabetic);
+    valid.then_some(tag)
+}
+
+/// Bring a post's `post_tags` rows in line with its content; drops them
+/// when the post is gone. Called after every write to the post; safe to
+/// repeat.
+pub async fn tag_post(pool: &SqlitePool, post_id: &str) -> Result<(), sqlx::Error> {
+    let mut tx = pool.begin().await?;
+    tag_post_in(&mut tx, post_id).await?;
+    tx.commit().await
+}
+
+/// `tag_post` inside the caller's transaction
+pub async fn tag_post_in(tx: &mut Transaction<'_, Sqlite>, post_id: &str) -> Result<(), sqlx::Error> {
+    let content: Option<String> = sqlx::query_scalar("SELECT content_hash FROM posts WHERE id = ?")
+        .bind(post_id)
+        .fetch_optional(&mut **tx)
+        .await?;
+    let tags = content.as_deref().map(extract).unwrap_or_default();
+
+    sqlx::query("DELETE FROM post_tags WHERE post_id = ?")
+        .bind(post_id)
+        .execute(&mut **tx)
+        .await?;
+    for tag in &tags {
+        sqlx::query("INSERT INTO post_tags(post_id, tag, created_at) VALUES (?, ?, CURRENT_TIMESTAMP)")
+            .bind(post_id)
+            .bind(tag)
+            .execute(&mut **tx)
+            .await?;
+    }
+    Ok(())
+}
+
+/// Re-tag every post; returns the tags stored
+pub async fn rebuild(pool: &SqlitePool) -> Result<u64, sqlx::Error> {
+    let posts: Vec<(String, String)> = sqlx::query_as("SELECT id, content_hash FROM posts")
+        .fetch_all(pool)
+        .await?;
+
+    let mut tx = pool.begin().await?;
+    sqlx::query("DELETE FROM post_tags").execute(&mut *tx).await?;
+
+    let mut tagged = 0;
+    for (post_id, content) in &posts {
+        for tag in extract(content) {
+            sqlx::query("INSERT INTO post_tags(post_id, tag, created_at) VALUES (?, ?, CURRENT_TIMESTAMP)")
+                .bind(post_id)
+                .bind(&tag)
+                .execute(&mut *tx)
+                .await?;
+            tagged += 1;
+        }
+    }
+
+    tx.commit().await?;
+    Ok(tagged)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn extract_finds_tags_at_word_starts() {
+        assert_eq!(extract("#web3 is here, again: (#Sui) #move_lang."), vec!["web3", "sui", "move_lang"]);
+    }
+
+    #[test]
+    fn extract_skips_hashes_mid_word() {
+        assert!(extract("I write C# and link page#anchor").is_empty());
+        assert!(extract("##double").is_empty());
+    }
+
+    #[test]
+    fn extract_lowercases_and_dedupes_in_order() {
+        assert_eq!(extract("#SUITER #Web3 #suiter #WEB3 #sui"), vec!["suiter", "web3", "sui"]);
+    }
+
+    #[test]
+    fn extract_drops_invalid_tags() {
+        assert!(extract("#1 #2024 #___ # #").is_empty());
+        assert_eq!(extract("#1 #a1 #_x"), vec!["a1", "_x"]);
+    }
+
+    #[test]
+    fn extract_keeps_at_most_max_tags_per_post() {
+        let content: Vec<String> = (0..MAX_TAGS_PER_POST + 5).map(|i| format!("#tag{}", i)).collect();
+        let tags = extract(&content.join(" "));
+
+        assert_eq!(tags.len(), MAX_TAGS_PER_POST);
+        assert_eq!(tags[0], "tag0");
+        assert_eq!(tags[MAX_TAGS_PER_POST - 1], format!("tag{}", MAX_TAGS_PER_POST - 1));
+    }
+
+    #[test]
+    fn extract_counts_duplicates_once_toward_the_limit() {
+        let mut content = vec!["#same".to_string(); MAX_TAGS_PER_POST];
+        content.push("#other".to_string());
+
+        assert_eq!(extract(&content.join(" ")), vec!["same", "other"]);
+    }
+
+    #[test]
+    fn extract_drops_overlong_tags_instead_of_truncating() {
+        let long = "a".repeat(MAX_TAG_LEN + 1);
+        assert_eq!(extract(&format!("#{} #short", long)), vec!["short"]);
+        assert_eq!(extract(&format!("#{}", "a".repeat(MAX_TAG_LEN))).len(), 1);
+    }
+
+    #[test]
+    fn extract_handles_unicode_tags() {
+        assert_eq!(extract("#Café #日本語 #ÜBER #café"), vec!["café", "日本語", "über"]);
+        // Letters glued to the hash from another script still start mid-word
+        assert!(extract("東京#タグ").is_empty());
+    }
+
+    #[test]
+    fn normalize_accepts_tags_with_or_without_hash() {
+        assert_eq!(normalize("#Web3").as_deref(), Some("web3"));
+        assert_eq!(normalize("Web3").as_deref(), Some("web3"));
+        assert_eq!(normalize("ÉTÉ").as_deref(), Some("été"));
+    }
+
+    #[test]
+    fn normalize_rejects_non_tags() {
+        for tag in ["", "#", "#1", "123", "#___", "_", "we b", "web-3", "#c#", "##web"] {
+            assert_eq!(normalize(tag), None, "{:?}", tag);
+        }
+        assert_eq!(normalize(&"a".repeat(MAX_TAG_LEN + 1)), None);
+        // The limit counts characters, not bytes
+        assert!(normalize(&"é".repeat(MAX_TAG_LEN)).is_some());
+    }
+}
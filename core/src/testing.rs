@@ -0,0 +1,50 @@
+use sqlx::{sqlite::SqlitePoolOptions, SqlitePool};
+use std::path::Path;
+
+use crate::schema;
+
+/// A fresh in-memory database with every migration applied, so tests run
+/// against the real schema rather than hand-written tables. An in-memory
+/// database lives on one connection, so the pool holds exactly one.
+pub async fn migrated_pool() -> SqlitePool {
+    let pool = SqlitePoolOptions::new()
+        .max_connections(1)
+        .connect("sqlite::memory:")
+        .await
+        .expect("in-memory database");
+    schema::apply(&pool, Path::new(schema::MIGRATIONS_DIR))
+        .await
+        .expect("applying database/migrations");
+    pool
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn migrations_skip_monitoring_queries_and_apply_in_order() {
+        let files = schema::migrations(Path::new(schema::MIGRATIONS_DIR)).unwrap();
+        let names: Vec<String> = files.iter().map(|f| f.file_name().unwrap().to_string_lossy().into_owned()).collect();
+        assert_eq!(names.first().map(String::as_str), Some("001_init.sql"));
+        assert!(!names.iter().any(|n| n == "002_monitoring.sql"));
+        assert!(names.windows(2).all(|w| w[0] < w[1]));
+    }
+
+    #[tokio::test]
+    async fn every_migration_applies_to_a_fresh_database() {
+        let pool = migrated_pool().await;
+        let tables: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM sqlite_master WHERE type = 'table' AND name IN ('posts', 'feed_rankings', 'ledger_entries', 'reputation_events')")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(tables, 4);
+
+        // A later migration only applies if every statement before it did
+        let token_hash: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM pragma_table_info('webhooks') WHERE name = 'token_hash'")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(token_hash, 1);
+    }
+}
@@ -0,0 +1,172 @@
+use sqlx::{Executor, Sqlite};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+
+/// Set to 1 to let webhooks reach loopback and private networks, e.g. a
+/// local `webhook_receiver.py`; never in production
+pub const ALLOW_PRIVATE_NETWORKS_ENV: &str = "WEBHOOK_ALLOW_PRIVATE_NETWORKS";
+
+/// Events integrators can subscribe a webhook to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Event {
+    /// A post was confirmed on chain
+    PostCreated,
+    /// A post reached a new level
+    PostLeveledUp,
+    /// A truth claim was resolved
+    ClaimResolved,
+    /// A lifeline received support
+    SupportSent,
+}
+
+impl Event {
+    pub const ALL: [Event; 4] = [Event::PostCreated, Event::PostLeveledUp, Event::ClaimResolved, Event::SupportSent];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Event::PostCreated => "post.created",
+            Event::PostLeveledUp => "post.leveled_up",
+            Event::ClaimResolved => "claim.resolved",
+            Event::SupportSent => "support.sent",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        Event::ALL.into_iter().find(|e| e.as_str() == s)
+    }
+}
+
+/// Queue `event` for every active webhook subscribed to it. `source_ref`
+/// identifies the cause, so the same event seen twice (replays, or both the
+/// API and the indexer) is delivered once. Returns the deliveries queued.
+pub async fn enqueue<'e, E>(
+    executor: E,
+    event: Event,
+    source_ref: &str,
+    payload: &serde_json::Value,
+) -> Result<u64, sqlx::Error>
+where
+    E: Executor<'e, Database = Sqlite>,
+{
+    let queued = sqlx::query(
+        r#"
+        INSERT OR IGNORE INTO webhook_deliveries(webhook_id, event, source_ref, payload, status, attempts, next_attempt_at, created_at)
+        SELECT id, ?1, ?2, ?3, 'pending', 0, CURRENT_TIMESTAMP, CURRENT_TIMESTAMP
+        FROM webhooks
+        WHERE active AND instr(',' || events || ',', ',' || ?1 || ',') > 0
+        "#
+    )
+    .bind(event.as_str())
+    .bind(source_ref)
+    .bind(payload.to_string())
+    .execute(executor)
+    .await?;
+
+    Ok(queued.rows_affected())
+}
+
+/// Whether webhooks may target private networks, from
+/// ALLOW_PRIVATE_NETWORKS_ENV
+pub fn allow_private_networks() -> bool {
+    std::env::var(ALLOW_PRIVATE_NETWORKS_ENV).is_ok_and(|v| v == "1" || v == "true")
+}
+
+/// Resolve a webhook host and make sure every address it resolves to is
+/// public, so a webhook can't be pointed at the API's own network. Callers
+/// connect to the returned addresses rather than resolving again, which a
+/// rebinding DNS server could answer differently.
+pub async fn resolve_public(host: &str, port: u16, allow_private: bool) -> Result<Vec<SocketAddr>, String> {
+    // Url::host_str keeps IPv6 literals bracketed
+    let host = host.trim_start_matches('[').trim_end_matches(']');
+    let addrs: Vec<SocketAddr> = tokio::net::lookup_host((host, port))
+        .await
+        .map_err(|e| format!("can't resolve webhook host {}: {}", host, e))?
+        .collect();
+
+    if addrs.is_empty() {
+        return Err(format!("webhook host {} has no addresses", host));
+    }
+    if let Some(blocked) = addrs.iter().find(|a| !allow_private && !is_public(a.ip())) {
+        return Err(format!("webhook host {} resolves to non-public address {}", host, blocked.ip()));
+    }
+    Ok(addrs)
+}
+
+/// False for loopback, link-local, private, shared, reserved and other
+/// special-purpose addresses
+pub fn is_public(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(v4) => is_public_v4(v4),
+        IpAddr::V6(v6) => match v6.to_ipv4_mapped() {
+            Some(v4) => is_public_v4(v4),
+            None => is_public_v6(v6),
+        },
+    }
+}
+
+fn is_public_v4(ip: Ipv4Addr) -> bool {
+    let [a, b, c, _] = ip.octets();
+    !(ip.is_unspecified()
+        || ip.is_loopback()
+        || ip.is_private()
+        || ip.is_link_local()
+        || ip.is_broadcast()
+        || ip.is_multicast()
+        || ip.is_documentation()
+        // 0.0.0.0/8 "this network"
+        || a == 0
+        // 100.64.0.0/10 carrier-grade NAT
+        || (a == 100 && (64..128).contains(&b))
+        // 192.0.0.0/24 protocol assignments
+        || (a == 192 && b == 0 && c == 0)
+        // 198.18.0.0/15 benchmarking
+        || (a == 198 && (b == 18 || b == 19))
+        // 240.0.0.0/4 reserved
+        || a >= 240)
+}
+
+fn is_public_v6(ip: Ipv6Addr) -> bool {
+    let first = ip.segments()[0];
+    !(ip.is_unspecified()
+        || ip.is_loopback()
+        || ip.is_multicast()
+        // fc00::/7 unique local
+        || (first & 0xfe00) == 0xfc00
+        // fe80::/10 link-local
+        || (first & 0xffc0) == 0xfe80
+        // 2001:db8::/32 documentation
+        || (first == 0x2001 && ip.segments()[1] == 0x0db8))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn public(ip: &str) -> bool {
+        is_public(ip.parse().unwrap())
+    }
+
+    #[test]
+    fn is_public_rejects_loopback_link_local_and_private_addresses() {
+        for ip in [
+            "127.0.0.1", "10.1.2.3", "172.16.0.1", "192.168.1.1", "169.254.169.254", "100.64.0.1", "0.0.0.0",
+            "::1", "::", "fe80::1", "fd00::1", "::ffff:127.0.0.1", "::ffff:10.0.0.1",
+        ] {
+            assert!(!public(ip), "{} should not be public", ip);
+        }
+    }
+
+    #[test]
+    fn is_public_accepts_public_addresses() {
+        for ip in ["1.1.1.1", "93.184.216.34", "172.32.0.1", "2606:4700:4700::1111", "::ffff:8.8.8.8"] {
+            assert!(public(ip), "{} should be public", ip);
+        }
+    }
+
+    #[tokio::test]
+    async fn resolve_public_checks_literal_hosts() {
+        assert!(resolve_public("127.0.0.1", 80, false).await.is_err());
+        assert!(resolve_public("[::1]", 80, false).await.is_err());
+        assert_eq!(resolve_public("127.0.0.1", 80, true).await.unwrap(), vec!["127.0.0.1:80".parse().unwrap()]);
+        assert!(resolve_public("8.8.8.8", 443, false).await.is_ok());
+    }
+}
@@ -7,8 +7,8 @@ CREATE TABLE IF NOT EXISTS profiles (
     reputation BIGINT NOT NULL DEFAULT 50,
     total_posts BIGINT NOT NULL DEFAULT 0,
     total_attention_earned BIGINT NOT NULL DEFAULT 0,
-    joined_at TIMESTAMP NOT NULL DEFAULT NOW(),
-    updated_at TIMESTAMP NOT NULL DEFAULT NOW()
+    joined_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
 );
 
 -- Posts created by users
@@ -19,7 +19,7 @@ CREATE TABLE IF NOT EXISTS posts (
     attention_accumulated BIGINT NOT NULL DEFAULT 0,
     level SMALLINT NOT NULL DEFAULT 1 CHECK (level >= 1 AND level <= 5),
     created_at TIMESTAMP NOT NULL,
-    updated_at TIMESTAMP NOT NULL DEFAULT NOW()
+    updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
 );
 
 -- Attention sessions (reading a post)
@@ -30,7 +30,7 @@ CREATE TABLE IF NOT EXISTS attention_sessions (
     duration_ms BIGINT NOT NULL,
     reward BIGINT NOT NULL,
     claimed BOOLEAN NOT NULL DEFAULT FALSE,
-    created_at TIMESTAMP NOT NULL DEFAULT NOW(),
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
     claimed_at TIMESTAMP
 );
 
@@ -42,7 +42,7 @@ CREATE TABLE IF NOT EXISTS feed_rankings (
     reputation_weight DECIMAL(10,6),
     attention_weight DECIMAL(10,6),
     trend_weight DECIMAL(10,6),
-    computed_at TIMESTAMP NOT NULL DEFAULT NOW()
+    computed_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
 );
 
 -- Truth claims on posts
@@ -56,7 +56,7 @@ CREATE TABLE IF NOT EXISTS truth_claims (
     resolved BOOLEAN NOT NULL DEFAULT FALSE,
     accepted BOOLEAN,
     voting_end TIMESTAMP NOT NULL,
-    created_at TIMESTAMP NOT NULL DEFAULT NOW()
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
 );
 
 -- Creator lifeline support
@@ -66,7 +66,7 @@ CREATE TABLE IF NOT EXISTS creator_lifeines (
     total_received BIGINT NOT NULL DEFAULT 0,
     supporter_count BIGINT NOT NULL DEFAULT 0,
     active BOOLEAN NOT NULL DEFAULT TRUE,
-    created_at TIMESTAMP NOT NULL DEFAULT NOW()
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
 );
 
 -- Indexes for performance
@@ -81,7 +81,7 @@ CREATE INDEX IF NOT EXISTS idx_truth_claims_post ON truth_claims(post_id);
 CREATE INDEX IF NOT EXISTS idx_truth_claims_resolved ON truth_claims(resolved);
 
 -- Views for common queries
-CREATE OR REPLACE VIEW posts_with_ranking AS
+CREATE VIEW IF NOT EXISTS posts_with_ranking AS
 SELECT
     p.*,
     fr.score as ranking_score,
@@ -6,7 +6,7 @@ SELECT
     reputation,
     total_posts,
     total_attention_earned,
-    ROUND(EXTRACT(EPOCH FROM (NOW() - joined_at))/86400) as days_active
+    ROUND(julianday('now') - julianday(joined_at)) as days_active
 FROM profiles
 ORDER BY reputation DESC
 LIMIT 20;
@@ -18,7 +18,7 @@ SELECT
     p.level,
     p.attention_accumulated,
     COUNT(DISTINCT a.reader) as unique_readers,
-    ROUND(EXTRACT(EPOCH FROM (NOW() - p.created_at))/3600) as hours_old
+    ROUND((julianday('now') - julianday(p.created_at)) * 24) as hours_old
 FROM posts p
 LEFT JOIN attention_sessions a ON p.id = a.post_id
 GROUP BY p.id, p.author, p.level, p.attention_accumulated, p.created_at
@@ -62,7 +62,7 @@ SELECT
         WHEN tc.votes_no > tc.votes_yes THEN 'NO'
         ELSE 'TIE'
     END as leading_vote,
-    ROUND(EXTRACT(EPOCH FROM (COALESCE(tc.voting_end, NOW()) - NOW()))/3600) as hours_until_end
+    ROUND((julianday(tc.voting_end) - julianday('now')) * 24) as hours_until_end
 FROM truth_claims tc
 WHERE NOT tc.resolved
 ORDER BY tc.created_at DESC;
@@ -80,3 +80,14 @@ SELECT
 FROM profiles
 WHERE reputation != 50 OR total_posts > 0
 ORDER BY reputation DESC;
+
+-- Monitor Sybil / collusion flags raised by SybilDetector
+SELECT
+    rf.address,
+    rf.flag_type,
+    rf.score,
+    rf.evidence,
+    p.reputation
+FROM risk_flags rf
+LEFT JOIN profiles p ON rf.address = p.address
+ORDER BY rf.score DESC;
@@ -0,0 +1,25 @@
+-- Incremental feed ranking
+-- Posts whose ranking inputs changed are queued here and re-scored by FeedRanker
+
+-- Posts waiting to be re-scored (attention, level, reputation or claims changed)
+CREATE TABLE IF NOT EXISTS ranking_dirty (
+    post_id VARCHAR(100) PRIMARY KEY REFERENCES posts(id),
+    reason VARCHAR(32) NOT NULL,
+    marked_at BIGINT NOT NULL
+);
+
+-- Event polling cursors, one per Move module
+CREATE TABLE IF NOT EXISTS indexer_cursors (
+    module VARCHAR(64) PRIMARY KEY,
+    tx_digest VARCHAR(100) NOT NULL,
+    event_seq VARCHAR(32) NOT NULL,
+    updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+-- On-chain Profile object IDs, so reputation events can be mapped to addresses
+CREATE TABLE IF NOT EXISTS profile_objects (
+    object_id VARCHAR(100) PRIMARY KEY,
+    owner VARCHAR(100) NOT NULL REFERENCES profiles(address)
+);
+
+CREATE INDEX IF NOT EXISTS idx_ranking_dirty_marked ON ranking_dirty(marked_at);
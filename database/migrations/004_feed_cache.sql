@@ -0,0 +1,10 @@
+-- Feed cache invalidation
+-- FeedRanker bumps the version whenever it writes rankings; API caches key off it
+
+CREATE TABLE IF NOT EXISTS ranking_state (
+    id INTEGER PRIMARY KEY CHECK (id = 1),
+    version BIGINT NOT NULL DEFAULT 0,
+    updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+INSERT OR IGNORE INTO ranking_state (id, version) VALUES (1, 0);
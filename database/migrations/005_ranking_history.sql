@@ -0,0 +1,15 @@
+-- Feed ranking history
+-- FeedRanker appends a row whenever a post's score changes, for explainability
+
+CREATE TABLE IF NOT EXISTS feed_ranking_history (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    post_id VARCHAR(100) NOT NULL REFERENCES posts(id),
+    score DECIMAL(10,6) NOT NULL,
+    level_score DECIMAL(10,6),
+    reputation_score DECIMAL(10,6),
+    attention_score DECIMAL(10,6),
+    time_score DECIMAL(10,6),
+    calculated_at BIGINT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_ranking_history_post ON feed_ranking_history(post_id, id DESC);
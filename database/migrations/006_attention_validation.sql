@@ -0,0 +1,17 @@
+-- Attention session validation (design.md §5.3, §8)
+-- Sessions are driven by client heartbeats and carry a validity flag
+
+ALTER TABLE attention_sessions ADD COLUMN started_at BIGINT;
+ALTER TABLE attention_sessions ADD COLUMN ended_at BIGINT;
+ALTER TABLE attention_sessions ADD COLUMN last_heartbeat_at BIGINT;
+ALTER TABLE attention_sessions ADD COLUMN heartbeat_count BIGINT NOT NULL DEFAULT 0;
+-- Reading time covered by heartbeats, in milliseconds
+ALTER TABLE attention_sessions ADD COLUMN verified_ms BIGINT NOT NULL DEFAULT 0;
+-- 'active' while heartbeats are expected, then 'ended'
+ALTER TABLE attention_sessions ADD COLUMN status VARCHAR(16) NOT NULL DEFAULT 'ended';
+-- 'pending' until the session ends, then 'valid' or 'invalid'
+ALTER TABLE attention_sessions ADD COLUMN validity VARCHAR(16) NOT NULL DEFAULT 'valid';
+ALTER TABLE attention_sessions ADD COLUMN rejection_reason VARCHAR(64);
+
+CREATE INDEX IF NOT EXISTS idx_attention_reader_status ON attention_sessions(reader, status);
+CREATE INDEX IF NOT EXISTS idx_attention_reader_post ON attention_sessions(reader, post_id, validity);
@@ -0,0 +1,24 @@
+-- Sybil and collusion detection
+-- SybilDetector writes scored flags here; the ranker and reward paths consult them
+
+-- Individual truth-claim votes, as indexed from VoteCasted events
+CREATE TABLE IF NOT EXISTS claim_votes (
+    claim_id VARCHAR(100) NOT NULL,
+    voter VARCHAR(100) NOT NULL REFERENCES profiles(address),
+    vote BOOLEAN NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (claim_id, voter)
+);
+
+-- One row per address and detector; score in [0, 1]
+CREATE TABLE IF NOT EXISTS risk_flags (
+    address VARCHAR(100) NOT NULL REFERENCES profiles(address),
+    flag_type VARCHAR(32) NOT NULL,
+    score REAL NOT NULL,
+    evidence TEXT NOT NULL,
+    detected_at BIGINT NOT NULL,
+    PRIMARY KEY (address, flag_type)
+);
+
+CREATE INDEX IF NOT EXISTS idx_claim_votes_voter ON claim_votes(voter);
+CREATE INDEX IF NOT EXISTS idx_risk_flags_score ON risk_flags(score DESC);
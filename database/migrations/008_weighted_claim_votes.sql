@@ -0,0 +1,10 @@
+-- Reputation-weighted truth claim voting (design.md §5.5)
+-- Each vote keeps the voter's reputation at vote time; tallies weight by it
+
+ALTER TABLE claim_votes ADD COLUMN reputation_snapshot BIGINT NOT NULL DEFAULT 50;
+
+-- 'accepted', 'rejected' or 'no_quorum' once resolved
+ALTER TABLE truth_claims ADD COLUMN outcome VARCHAR(16);
+ALTER TABLE truth_claims ADD COLUMN resolved_at TIMESTAMP;
+
+CREATE INDEX IF NOT EXISTS idx_truth_claims_voting_end ON truth_claims(resolved, voting_end);
@@ -0,0 +1,25 @@
+-- Staked truth claims and the settlement ledger (design.md §5.5)
+-- Every movement of funds is a transaction of ledger legs summing to zero
+
+ALTER TABLE truth_claims ADD COLUMN stake BIGINT NOT NULL DEFAULT 0;
+ALTER TABLE truth_claims ADD COLUMN settled_at TIMESTAMP;
+ALTER TABLE claim_votes ADD COLUMN stake BIGINT NOT NULL DEFAULT 0;
+
+-- Accounts: 'wallet:<address>' (funds from the chain), 'user:<address>'
+-- (payouts owed), 'escrow:claim:<id>' (locked stakes), 'treasury'
+CREATE TABLE IF NOT EXISTS ledger_entries (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    txn_id VARCHAR(150) NOT NULL,
+    account VARCHAR(150) NOT NULL,
+    -- MIST; positive credits the account, negative debits it
+    amount BIGINT NOT NULL,
+    entry_type VARCHAR(32) NOT NULL,
+    claim_id VARCHAR(100),
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    -- A transaction touches each account once, so replays are no-ops
+    UNIQUE (txn_id, account)
+);
+
+CREATE INDEX IF NOT EXISTS idx_ledger_account ON ledger_entries(account, id);
+CREATE INDEX IF NOT EXISTS idx_ledger_claim ON ledger_entries(claim_id);
+CREATE INDEX IF NOT EXISTS idx_truth_claims_settled ON truth_claims(resolved, settled_at);
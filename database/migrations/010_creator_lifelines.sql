@@ -0,0 +1,25 @@
+-- Creator lifelines (design.md §5.6)
+-- Fixes the table name from 001 and records each support payment
+
+ALTER TABLE creator_lifeines RENAME TO creator_lifelines;
+ALTER TABLE creator_lifelines ADD COLUMN last_support_at TIMESTAMP;
+
+-- One row per support payment; chain rows are keyed '<tx_digest>:<event_seq>'
+CREATE TABLE IF NOT EXISTS lifeline_supports (
+    id VARCHAR(150) PRIMARY KEY,
+    lifeline_id VARCHAR(100) NOT NULL REFERENCES creator_lifelines(id),
+    supporter VARCHAR(100) NOT NULL REFERENCES profiles(address),
+    recipient VARCHAR(100) NOT NULL REFERENCES profiles(address),
+    -- MIST
+    amount BIGINT NOT NULL,
+    -- UTC day the support counts against for MAX_DAILY_SUPPORT
+    support_day DATE NOT NULL,
+    -- 'api' or 'chain'
+    source VARCHAR(16) NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_lifelines_recipient ON creator_lifelines(recipient, active);
+CREATE INDEX IF NOT EXISTS idx_lifeline_supports_lifeline ON lifeline_supports(lifeline_id, support_day);
+CREATE INDEX IF NOT EXISTS idx_lifeline_supports_supporter_day ON lifeline_supports(supporter, support_day);
+CREATE INDEX IF NOT EXISTS idx_lifeline_supports_recipient_day ON lifeline_supports(recipient, support_day);
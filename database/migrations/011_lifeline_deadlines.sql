@@ -0,0 +1,33 @@
+-- Lifeline activity deadlines (design.md §5.6)
+-- A lifeline commits its creator to post at least once every cadence_days;
+-- LifelineMonitor deactivates lifelines that miss it and owes supporters refunds
+
+ALTER TABLE creator_lifelines ADD COLUMN cadence_days INTEGER NOT NULL DEFAULT 7;
+ALTER TABLE creator_lifelines ADD COLUMN deactivated_at TIMESTAMP;
+ALTER TABLE creator_lifelines ADD COLUMN deactivation_reason VARCHAR(32);
+
+CREATE TABLE IF NOT EXISTS lifeline_missed_deadlines (
+    lifeline_id VARCHAR(100) NOT NULL REFERENCES creator_lifelines(id),
+    deadline TIMESTAMP NOT NULL,
+    -- Last post before the deadline, NULL if the creator never posted
+    last_post_at TIMESTAMP,
+    detected_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (lifeline_id, deadline)
+);
+
+-- Support sent after the creator's last post is owed back when a deadline is missed
+CREATE TABLE IF NOT EXISTS lifeline_refunds (
+    lifeline_id VARCHAR(100) NOT NULL REFERENCES creator_lifelines(id),
+    supporter VARCHAR(100) NOT NULL REFERENCES profiles(address),
+    recipient VARCHAR(100) NOT NULL REFERENCES profiles(address),
+    -- MIST
+    amount BIGINT NOT NULL,
+    supports BIGINT NOT NULL,
+    -- 'owed' until paid back
+    status VARCHAR(16) NOT NULL DEFAULT 'owed',
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (lifeline_id, supporter)
+);
+
+CREATE INDEX IF NOT EXISTS idx_lifeline_refunds_supporter ON lifeline_refunds(supporter, status);
+CREATE INDEX IF NOT EXISTS idx_posts_author_created ON posts(author, created_at);
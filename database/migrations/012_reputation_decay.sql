@@ -0,0 +1,20 @@
+-- Off-chain reputation decay (mirrors profile::apply_decay)
+-- Every change to profiles.reputation is recorded in reputation_events
+
+CREATE TABLE IF NOT EXISTS reputation_events (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    address VARCHAR(100) NOT NULL REFERENCES profiles(address),
+    delta BIGINT NOT NULL,
+    reason VARCHAR(32) NOT NULL,
+    -- What caused the change; replays of the same cause are ignored
+    source_ref VARCHAR(150) NOT NULL,
+    balance_after BIGINT NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE (address, reason, source_ref)
+);
+
+-- Inactive days up to this instant have already been decayed
+ALTER TABLE profiles ADD COLUMN last_decay_at TIMESTAMP;
+
+CREATE INDEX IF NOT EXISTS idx_reputation_events_address ON reputation_events(address, id);
+CREATE INDEX IF NOT EXISTS idx_attention_reader_created ON attention_sessions(reader, created_at);
@@ -0,0 +1,12 @@
+-- Reputation event ledger
+-- profiles.reputation is now STARTING_REPUTATION (50) plus the sum of the
+-- profile's reputation_events; backfill the difference for existing balances
+
+INSERT INTO reputation_events(address, delta, reason, source_ref, balance_after, created_at)
+SELECT p.address,
+    p.reputation - 50 - COALESCE((SELECT SUM(e.delta) FROM reputation_events e WHERE e.address = p.address), 0),
+    'opening_balance', 'migration:013', p.reputation, CURRENT_TIMESTAMP
+FROM profiles p
+WHERE p.reputation != 50 + COALESCE((SELECT SUM(e.delta) FROM reputation_events e WHERE e.address = p.address), 0);
+
+CREATE INDEX IF NOT EXISTS idx_reputation_events_reason ON reputation_events(reason, created_at);
@@ -0,0 +1,20 @@
+-- Attention reward split (design.md §6.1)
+-- Each claimed session's reward is divided 50/30/20 between creator, reader
+-- and the attention pool; the money moves in ledger_entries under
+-- 'reward:session:<id>', this table keeps the split for earnings reports
+
+CREATE TABLE IF NOT EXISTS attention_rewards (
+    session_id VARCHAR(100) PRIMARY KEY REFERENCES attention_sessions(id),
+    post_id VARCHAR(100) NOT NULL,
+    creator VARCHAR(100) NOT NULL REFERENCES profiles(address),
+    reader VARCHAR(100) NOT NULL REFERENCES profiles(address),
+    -- MIST
+    total BIGINT NOT NULL,
+    creator_amount BIGINT NOT NULL,
+    reader_amount BIGINT NOT NULL,
+    pool_amount BIGINT NOT NULL,
+    claimed_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_attention_rewards_creator ON attention_rewards(creator, claimed_at);
+CREATE INDEX IF NOT EXISTS idx_attention_rewards_reader ON attention_rewards(reader, claimed_at);
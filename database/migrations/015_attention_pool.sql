@@ -0,0 +1,19 @@
+-- Attention pool treasury
+-- `fund_pool` emits no event, so the indexer polls the AttentionPool object
+-- and infers funding from how balance and total_rewarded moved between polls;
+-- funding is posted to ledger_entries as 'fund:attention:<snapshot id>'
+
+CREATE TABLE IF NOT EXISTS attention_pool_snapshots (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    pool_id VARCHAR(100) NOT NULL,
+    -- MIST
+    balance BIGINT NOT NULL,
+    total_rewarded BIGINT NOT NULL,
+    session_count BIGINT NOT NULL,
+    -- Funding inferred since the previous snapshot
+    funded BIGINT NOT NULL DEFAULT 0,
+    observed_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_attention_pool_snapshots_pool ON attention_pool_snapshots(pool_id, id);
+CREATE INDEX IF NOT EXISTS idx_ledger_account_created ON ledger_entries(account, created_at);
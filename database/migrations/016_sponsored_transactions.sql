@@ -0,0 +1,20 @@
+-- Sponsored (gasless) transactions
+-- Audit trail of every transaction the sponsor key co-signed; also the
+-- source of per-address quotas and the sponsor's daily spend
+
+CREATE TABLE IF NOT EXISTS sponsored_transactions (
+    digest VARCHAR(64) PRIMARY KEY,
+    sender VARCHAR(100) NOT NULL,
+    sponsor VARCHAR(100) NOT NULL,
+    -- '<module>::<function>'
+    function VARCHAR(100) NOT NULL,
+    -- MIST
+    gas_budget BIGINT NOT NULL,
+    gas_price BIGINT NOT NULL,
+    -- Reputation the quota was computed from
+    reputation BIGINT NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_sponsored_sender_created ON sponsored_transactions(sender, created_at);
+CREATE INDEX IF NOT EXISTS idx_sponsored_created ON sponsored_transactions(created_at);
@@ -0,0 +1,24 @@
+-- Submitted transactions awaiting confirmation
+-- Rows start 'pending' when POST /api/tx/submit hands a signed transaction
+-- to the node; the indexer confirms them when their events arrive, or marks
+-- them 'failed' if the chain reports an abort or they never land
+
+CREATE TABLE IF NOT EXISTS pending_transactions (
+    digest VARCHAR(64) PRIMARY KEY,
+    sender VARCHAR(100) NOT NULL,
+    -- '<module>::<function>'
+    function VARCHAR(100) NOT NULL,
+    -- Object the call acts on: the post for create_claim, the claim for
+    -- vote_on_claim and resolve_claim, the session for end_session, ...
+    target_id VARCHAR(100),
+    -- Object the call created, once confirmed (post, claim, profile)
+    object_id VARCHAR(100),
+    -- 'pending', 'confirmed' or 'failed'
+    status VARCHAR(20) NOT NULL DEFAULT 'pending',
+    error TEXT,
+    submitted_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    resolved_at TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_pending_tx_sender ON pending_transactions(sender, submitted_at);
+CREATE INDEX IF NOT EXISTS idx_pending_tx_status ON pending_transactions(status, submitted_at);
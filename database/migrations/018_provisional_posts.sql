@@ -0,0 +1,17 @@
+-- Provisional posts
+-- Posts created through the API are provisional until the indexer sees the
+-- matching PostCreated event and re-keys them to the on-chain object id;
+-- ones that never land expire. Matching uses the client nonce passed to both
+-- POST /api/posts and POST /api/tx/submit, then author and content hash.
+
+ALTER TABLE posts ADD COLUMN provisional BOOLEAN NOT NULL DEFAULT FALSE;
+ALTER TABLE posts ADD COLUMN client_nonce VARCHAR(100);
+-- The API id a linked post was created under, so clients holding it still resolve
+ALTER TABLE posts ADD COLUMN provisional_id VARCHAR(100);
+ALTER TABLE posts ADD COLUMN linked_at TIMESTAMP;
+
+ALTER TABLE pending_transactions ADD COLUMN client_nonce VARCHAR(100);
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_posts_author_nonce ON posts(author, client_nonce) WHERE client_nonce IS NOT NULL;
+CREATE INDEX IF NOT EXISTS idx_posts_provisional ON posts(provisional, created_at);
+CREATE INDEX IF NOT EXISTS idx_posts_provisional_id ON posts(provisional_id);
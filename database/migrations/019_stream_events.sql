@@ -0,0 +1,19 @@
+-- Live stream
+-- Changes pushed to GET /api/stream subscribers. The API and the indexer
+-- append here; the API relays new rows onto its in-process broadcast bus.
+-- A row's id is the resume token clients reconnect with, so rows are kept
+-- for a while after delivery and then pruned by the API.
+
+CREATE TABLE IF NOT EXISTS stream_events (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    -- 'feed', 'post:<id>', 'claim:<id>' or 'profile:<address>'
+    topic VARCHAR(150) NOT NULL,
+    -- What happened, e.g. 'post_created', 'vote', 'ranking_updated'
+    kind VARCHAR(32) NOT NULL,
+    -- JSON
+    payload TEXT NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_stream_events_topic ON stream_events(topic, id);
+CREATE INDEX IF NOT EXISTS idx_stream_events_created ON stream_events(created_at);
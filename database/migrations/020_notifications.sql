@@ -0,0 +1,59 @@
+-- Notifications
+-- One row per thing a user should hear about: a post leveling up, a claim
+-- against a post, a claim resolving, lifeline support, a reply. Written by the
+-- indexer and the API where the event happens; the indexer's dispatcher
+-- then delivers each one through the sinks the recipient has enabled.
+
+CREATE TABLE IF NOT EXISTS notifications (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    recipient VARCHAR(100) NOT NULL REFERENCES profiles(address),
+    -- 'level_up', 'claim_created', 'claim_resolved', 'support_received' or 'reply'
+    kind VARCHAR(32) NOT NULL,
+    -- What caused it; replays of the same cause are ignored
+    source_ref VARCHAR(150) NOT NULL,
+    -- JSON
+    payload TEXT NOT NULL,
+    -- Listed by GET /api/notifications; FALSE when only other sinks want it
+    in_app BOOLEAN NOT NULL DEFAULT TRUE,
+    read_at TIMESTAMP,
+    -- Set once every sink has delivered or given up
+    dispatched_at TIMESTAMP,
+    -- When sinks that failed are retried
+    next_attempt_at TIMESTAMP,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE (recipient, kind, source_ref)
+);
+
+-- Missing rows mean every sink is on for that kind
+CREATE TABLE IF NOT EXISTS notification_preferences (
+    address VARCHAR(100) NOT NULL REFERENCES profiles(address),
+    kind VARCHAR(32) NOT NULL,
+    in_app BOOLEAN NOT NULL DEFAULT TRUE,
+    webhook BOOLEAN NOT NULL DEFAULT TRUE,
+    updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (address, kind)
+);
+
+-- Where the webhook sink posts a user's notifications
+CREATE TABLE IF NOT EXISTS notification_webhooks (
+    address VARCHAR(100) PRIMARY KEY REFERENCES profiles(address),
+    url TEXT NOT NULL,
+    updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+-- One row per notification and sink that tried to deliver it
+CREATE TABLE IF NOT EXISTS notification_deliveries (
+    notification_id INTEGER NOT NULL REFERENCES notifications(id),
+    -- 'in_app' or 'webhook'
+    sink VARCHAR(16) NOT NULL,
+    -- 'delivered' or 'failed'
+    status VARCHAR(16) NOT NULL,
+    attempts INTEGER NOT NULL DEFAULT 1,
+    error TEXT,
+    updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (notification_id, sink)
+);
+
+CREATE INDEX IF NOT EXISTS idx_notifications_recipient ON notifications(recipient, id DESC);
+CREATE INDEX IF NOT EXISTS idx_notifications_unread ON notifications(recipient, read_at);
+CREATE INDEX IF NOT EXISTS idx_notifications_undispatched ON notifications(dispatched_at, next_attempt_at);
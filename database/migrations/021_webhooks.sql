@@ -0,0 +1,56 @@
+-- Outgoing webhooks
+-- Third parties register a URL and the events they want. Each event is
+-- queued once per matching webhook in webhook_deliveries, where the
+-- indexer's dispatcher picks it up, signs it with the webhook's secret and
+-- retries failures with exponential backoff. Deliveries that run out of
+-- attempts are copied to webhook_dead_letters until someone retries them.
+
+CREATE TABLE IF NOT EXISTS webhooks (
+    id VARCHAR(66) PRIMARY KEY,
+    owner VARCHAR(100) NOT NULL REFERENCES profiles(address),
+    url TEXT NOT NULL,
+    -- HMAC-SHA256 key for the X-Suiter-Signature header
+    secret VARCHAR(100) NOT NULL,
+    -- Comma-separated, e.g. 'post.created,claim.resolved'
+    events TEXT NOT NULL,
+    active BOOLEAN NOT NULL DEFAULT TRUE,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Delivery log; one row per event and webhook
+CREATE TABLE IF NOT EXISTS webhook_deliveries (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    webhook_id VARCHAR(66) NOT NULL REFERENCES webhooks(id),
+    -- 'post.created', 'post.leveled_up', 'claim.resolved' or 'support.sent'
+    event VARCHAR(32) NOT NULL,
+    -- What caused it; replays of the same cause are ignored
+    source_ref VARCHAR(150) NOT NULL,
+    -- JSON
+    payload TEXT NOT NULL,
+    -- 'pending', 'delivered' or 'dead'
+    status VARCHAR(16) NOT NULL DEFAULT 'pending',
+    attempts INTEGER NOT NULL DEFAULT 0,
+    next_attempt_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    -- HTTP status of the last attempt, NULL if it got no response
+    response_status INTEGER,
+    last_error TEXT,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    delivered_at TIMESTAMP,
+    UNIQUE (webhook_id, event, source_ref)
+);
+
+CREATE TABLE IF NOT EXISTS webhook_dead_letters (
+    delivery_id INTEGER PRIMARY KEY REFERENCES webhook_deliveries(id),
+    webhook_id VARCHAR(66) NOT NULL REFERENCES webhooks(id),
+    event VARCHAR(32) NOT NULL,
+    payload TEXT NOT NULL,
+    attempts INTEGER NOT NULL,
+    last_error TEXT,
+    dead_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_webhooks_owner ON webhooks(owner);
+CREATE INDEX IF NOT EXISTS idx_webhook_deliveries_due ON webhook_deliveries(status, next_attempt_at);
+CREATE INDEX IF NOT EXISTS idx_webhook_deliveries_webhook ON webhook_deliveries(webhook_id, id DESC);
+CREATE INDEX IF NOT EXISTS idx_webhook_dead_letters_webhook ON webhook_dead_letters(webhook_id, delivery_id DESC);
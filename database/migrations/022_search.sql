@@ -0,0 +1,30 @@
+-- Full-text search
+-- GET /api/search matches post content, claim text and profile display
+-- names through one FTS5 index. search_documents maps each index row to
+-- what it came from; the API and the indexer re-index a document whenever
+-- they write its source row, and `reindex-search` rebuilds everything.
+-- The Postgres equivalent is database/postgres/022_search.sql.
+
+ALTER TABLE profiles ADD COLUMN display_name VARCHAR(50);
+
+CREATE TABLE IF NOT EXISTS search_documents (
+    -- rowid of the document in search_index
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    -- 'post', 'claim' or 'profile'
+    kind VARCHAR(16) NOT NULL,
+    -- Post id, claim id or profile address
+    ref_id VARCHAR(100) NOT NULL,
+    indexed_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE (kind, ref_id)
+);
+
+CREATE VIRTUAL TABLE IF NOT EXISTS search_index USING fts5(body, tokenize = 'unicode61 remove_diacritics 2');
+
+-- Index what already exists
+INSERT OR IGNORE INTO search_documents(kind, ref_id) SELECT 'post', id FROM posts WHERE TRIM(content_hash) != '';
+INSERT OR IGNORE INTO search_documents(kind, ref_id) SELECT 'claim', id FROM truth_claims WHERE TRIM(claim_text) != '';
+INSERT INTO search_index(rowid, body)
+SELECT d.id, COALESCE(p.content_hash, c.claim_text)
+FROM search_documents d
+LEFT JOIN posts p ON d.kind = 'post' AND p.id = d.ref_id
+LEFT JOIN truth_claims c ON d.kind = 'claim' AND c.id = d.ref_id;
@@ -0,0 +1,18 @@
+-- Hashtags
+-- Tags are parsed from post content (`#Web3 #SUITER`) when the API writes a
+-- post and again when the indexer sees its PostCreated event, and stored
+-- lowercased, one row per post and tag. Posts written before this
+-- migration are tagged by `suiter-indexer reindex-tags`.
+
+CREATE TABLE IF NOT EXISTS post_tags (
+    post_id VARCHAR(100) NOT NULL REFERENCES posts(id),
+    -- Lowercase, without the leading '#'
+    tag VARCHAR(50) NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (post_id, tag)
+);
+
+CREATE INDEX IF NOT EXISTS idx_post_tags_tag ON post_tags(tag);
+
+-- Trending tags scan valid sessions by when they ended
+CREATE INDEX IF NOT EXISTS idx_attention_validity_ended ON attention_sessions(validity, ended_at);
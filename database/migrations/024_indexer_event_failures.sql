@@ -0,0 +1,25 @@
+-- Indexer event failures
+-- An event the indexer fails to handle holds its module's cursor and is
+-- retried with backoff; the row tracks the attempts. After too many it is
+-- marked dead, kept here with its payload and skipped so later events can
+-- be indexed. `suiter-indexer replay-events` retries the dead ones.
+
+CREATE TABLE IF NOT EXISTS indexer_event_failures (
+    tx_digest VARCHAR(100) NOT NULL,
+    event_seq VARCHAR(20) NOT NULL,
+    module VARCHAR(50) NOT NULL,
+    event_type TEXT NOT NULL,
+    -- parsedJson of the event
+    payload TEXT NOT NULL,
+    timestamp_ms VARCHAR(20),
+    -- 'retrying' or 'dead'
+    status VARCHAR(16) NOT NULL DEFAULT 'retrying',
+    attempts INTEGER NOT NULL DEFAULT 0,
+    last_error TEXT,
+    next_attempt_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    first_failed_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (tx_digest, event_seq)
+);
+
+CREATE INDEX IF NOT EXISTS idx_indexer_event_failures_status ON indexer_event_failures(status, module);
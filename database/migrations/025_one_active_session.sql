@@ -0,0 +1,18 @@
+-- One active attention session per reader
+-- start_session checks for an active session before starting another; this
+-- index settles two starts that race past the check. Readers already
+-- holding several keep their newest, and the rest end as parallel sessions.
+
+UPDATE attention_sessions
+SET status = 'ended', validity = 'invalid', rejection_reason = 'parallel_session', ended_at = last_heartbeat_at
+WHERE status = 'active'
+  AND id NOT IN (
+      SELECT id FROM (
+          SELECT id, ROW_NUMBER() OVER (PARTITION BY reader ORDER BY started_at DESC, id DESC) as rn
+          FROM attention_sessions
+          WHERE status = 'active'
+      )
+      WHERE rn = 1
+  );
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_attention_one_active ON attention_sessions(reader) WHERE status = 'active';
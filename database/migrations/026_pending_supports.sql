@@ -0,0 +1,38 @@
+-- Pending lifeline supports
+-- Supports sent through the API moved no funds, yet were counted like chain
+-- payments. They are now intents: held here until the indexer sees a
+-- SupportSent event from the same supporter for the same lifeline and
+-- amount, so lifeline_supports only records payments that happened on chain.
+
+CREATE TABLE IF NOT EXISTS pending_supports (
+    id VARCHAR(100) PRIMARY KEY,
+    lifeline_id VARCHAR(100) NOT NULL REFERENCES creator_lifelines(id),
+    supporter VARCHAR(100) NOT NULL REFERENCES profiles(address),
+    recipient VARCHAR(100) NOT NULL REFERENCES profiles(address),
+    -- MIST
+    amount BIGINT NOT NULL,
+    -- Pending supports count against the supporter's MAX_DAILY_SUPPORT
+    support_day DATE NOT NULL,
+    -- 'pending' until matched, then 'confirmed'
+    status VARCHAR(16) NOT NULL DEFAULT 'pending',
+    -- The lifeline_supports row of the matching chain payment
+    support_id VARCHAR(150),
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    confirmed_at TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_pending_supports_match ON pending_supports(lifeline_id, supporter, amount, status);
+CREATE INDEX IF NOT EXISTS idx_pending_supports_supporter_day ON pending_supports(supporter, support_day, status);
+
+-- Supports already recorded from the API were never paid; move them out and
+-- recount the lifelines they inflated
+INSERT OR IGNORE INTO pending_supports(id, lifeline_id, supporter, recipient, amount, support_day, status, created_at)
+SELECT id, lifeline_id, supporter, recipient, amount, support_day, 'pending', created_at
+FROM lifeline_supports WHERE source = 'api';
+
+DELETE FROM lifeline_supports WHERE source = 'api';
+
+UPDATE creator_lifelines SET
+    total_received = (SELECT COALESCE(SUM(amount), 0) FROM lifeline_supports s WHERE s.lifeline_id = creator_lifelines.id),
+    supporter_count = (SELECT COUNT(DISTINCT supporter) FROM lifeline_supports s WHERE s.lifeline_id = creator_lifelines.id),
+    last_support_at = (SELECT MAX(created_at) FROM lifeline_supports s WHERE s.lifeline_id = creator_lifelines.id);
@@ -0,0 +1,13 @@
+-- Sponsorship usage counts submitted transactions only
+-- Anyone can ask the sponsor to co-sign a transaction for any sender, so
+-- counting every co-signature let one caller use up another address's quota
+-- and the sponsor's daily spend. A sponsorship now counts once it's
+-- submitted through the API or its events are indexed.
+
+ALTER TABLE sponsored_transactions ADD COLUMN submitted_at TIMESTAMP;
+
+UPDATE sponsored_transactions SET submitted_at = created_at
+WHERE digest IN (SELECT digest FROM pending_transactions);
+
+CREATE INDEX IF NOT EXISTS idx_sponsored_sender_submitted ON sponsored_transactions(sender, submitted_at);
+CREATE INDEX IF NOT EXISTS idx_sponsored_submitted ON sponsored_transactions(submitted_at);
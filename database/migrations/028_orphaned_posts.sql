@@ -0,0 +1,8 @@
+-- Orphaned provisional posts
+-- Provisional posts used to be deleted an hour after creation whether or
+-- not their transaction had failed, losing posts whose transaction was
+-- merely slow or submitted elsewhere. Only posts whose submitted
+-- transaction failed are deleted now; the rest are marked orphaned and
+-- reported by `check-sync`, and still link if their event arrives.
+
+ALTER TABLE posts ADD COLUMN orphaned_at TIMESTAMP;
@@ -0,0 +1,10 @@
+-- Signed notification webhooks
+-- Notification webhooks were posted unsigned, so receivers couldn't tell
+-- them from forgeries. Each one now has a secret and is signed like the
+-- outgoing webhooks: X-Suiter-Signature over "<X-Suiter-Timestamp>.<body>".
+-- Webhooks registered before this get a fresh secret; their owners see it
+-- by removing the URL and registering it again.
+
+ALTER TABLE notification_webhooks ADD COLUMN secret VARCHAR(100);
+
+UPDATE notification_webhooks SET secret = 'whsec_' || lower(hex(randomblob(32))) WHERE secret IS NULL;
@@ -0,0 +1,9 @@
+-- Webhook management tokens
+-- Anyone who knew a webhook's owner address could change it, read its
+-- deliveries or rotate its secret and be handed the new one. Creating a
+-- webhook now returns a management token, which every call that changes or
+-- inspects it must present as `Authorization: Bearer <token>`. Only a hash
+-- of the token is kept. Webhooks registered before this have no token;
+-- they keep delivering but can only be managed by an operator.
+
+ALTER TABLE webhooks ADD COLUMN token_hash VARCHAR(64);
@@ -0,0 +1,41 @@
+-- Feed rankings in the shape FeedRanker writes
+-- 001_init.sql keyed feed_rankings by post_id with *_weight columns, but the
+-- ranker upserts on an `id` of 'fr_' || post_id with per-component scores
+-- and a unix calculated_at, as feed_ranking_history does. On a database
+-- built from these migrations every recompute failed, and so did the
+-- ranking endpoint. The table is rebuilt in the ranker's shape; existing
+-- rows keep their score and the next sweep refreshes the components.
+
+-- The view reads feed_rankings, so it is dropped for the rebuild
+DROP VIEW IF EXISTS posts_with_ranking;
+
+CREATE TABLE feed_rankings_new (
+    id VARCHAR(110) PRIMARY KEY,
+    post_id VARCHAR(100) NOT NULL UNIQUE REFERENCES posts(id),
+    score DECIMAL(10,6) NOT NULL,
+    level_score DECIMAL(10,6),
+    reputation_score DECIMAL(10,6),
+    attention_score DECIMAL(10,6),
+    time_score DECIMAL(10,6),
+    calculated_at BIGINT NOT NULL
+);
+
+INSERT INTO feed_rankings_new(id, post_id, score, level_score, reputation_score, attention_score, time_score, calculated_at)
+SELECT 'fr_' || post_id, post_id, score, level_weight, reputation_weight, attention_weight, trend_weight,
+    CAST(strftime('%s', computed_at) AS INTEGER)
+FROM feed_rankings;
+
+DROP TABLE feed_rankings;
+ALTER TABLE feed_rankings_new RENAME TO feed_rankings;
+
+CREATE INDEX IF NOT EXISTS idx_feed_score ON feed_rankings(score DESC);
+
+CREATE VIEW IF NOT EXISTS posts_with_ranking AS
+SELECT
+    p.*,
+    fr.score as ranking_score,
+    a.reputation as author_reputation
+FROM posts p
+LEFT JOIN feed_rankings fr ON p.id = fr.post_id
+LEFT JOIN profiles a ON p.author = a.address
+ORDER BY COALESCE(fr.score, 0) DESC;
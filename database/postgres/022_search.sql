@@ -0,0 +1,52 @@
+-- Full-text search (Postgres)
+-- The Postgres counterpart of migrations/022_search.sql. Each searchable
+-- table carries a generated tsvector, so every write keeps the index
+-- current without the re-indexing the SQLite build does. search() answers
+-- the same question as GET /api/search: matches ranked by text relevance
+-- blended with feed_rankings.score, with highlighted snippets.
+
+ALTER TABLE profiles ADD COLUMN IF NOT EXISTS display_name VARCHAR(50);
+
+ALTER TABLE posts ADD COLUMN IF NOT EXISTS search_vector tsvector
+    GENERATED ALWAYS AS (to_tsvector('simple', COALESCE(content_hash, ''))) STORED;
+ALTER TABLE truth_claims ADD COLUMN IF NOT EXISTS search_vector tsvector
+    GENERATED ALWAYS AS (to_tsvector('simple', COALESCE(claim_text, ''))) STORED;
+ALTER TABLE profiles ADD COLUMN IF NOT EXISTS search_vector tsvector
+    GENERATED ALWAYS AS (to_tsvector('simple', COALESCE(display_name, ''))) STORED;
+
+CREATE INDEX IF NOT EXISTS idx_posts_search ON posts USING GIN (search_vector);
+CREATE INDEX IF NOT EXISTS idx_truth_claims_search ON truth_claims USING GIN (search_vector);
+CREATE INDEX IF NOT EXISTS idx_profiles_search ON profiles USING GIN (search_vector);
+
+-- kinds: any of 'post', 'claim', 'profile'
+-- A claim ranks with the feed score of the post it is about
+CREATE OR REPLACE FUNCTION search(q TEXT, kinds TEXT[], page_limit INT, page_offset INT)
+RETURNS TABLE (kind TEXT, ref_id VARCHAR, snippet TEXT, relevance REAL, feed_score REAL, score REAL)
+LANGUAGE sql STABLE AS $$
+    WITH query AS (
+        SELECT to_tsquery('simple', string_agg(quote_literal(term) || ':*', ' & ')) AS tsq
+        FROM regexp_split_to_table(lower(q), '[^[:alnum:]]+') AS term
+        WHERE term != ''
+    ),
+    docs AS (
+        SELECT 'post' AS kind, p.id AS ref_id, p.content_hash AS body, p.search_vector, p.id AS post_id FROM posts p
+        UNION ALL
+        SELECT 'claim', c.id, c.claim_text, c.search_vector, c.post_id FROM truth_claims c
+        UNION ALL
+        SELECT 'profile', pr.address, pr.display_name, pr.search_vector, NULL FROM profiles pr
+    ),
+    matched AS (
+        SELECT d.kind, d.ref_id, d.body, ts_rank_cd(d.search_vector, query.tsq) AS relevance,
+            COALESCE(fr.score, 0)::REAL AS feed_score, query.tsq
+        FROM docs d
+        CROSS JOIN query
+        LEFT JOIN feed_rankings fr ON fr.post_id = d.post_id
+        WHERE d.kind = ANY (kinds) AND d.search_vector @@ query.tsq
+    )
+    SELECT m.kind, m.ref_id,
+        ts_headline('simple', m.body, m.tsq, 'StartSel=<mark>, StopSel=</mark>, MaxWords=24, MinWords=8, MaxFragments=1, FragmentDelimiter=…'),
+        m.relevance, m.feed_score, (m.relevance * (1 + m.feed_score))::REAL
+    FROM matched m
+    ORDER BY 6 DESC, m.ref_id
+    LIMIT page_limit OFFSET page_offset
+$$;
@@ -0,0 +1,202 @@
+use anyhow::{anyhow, Result};
+use sqlx::SqlitePool;
+use std::collections::BTreeMap;
+use tracing::info;
+
+use suiter_core::{
+    address::SuiAddress,
+    ledger,
+    reputation::{Reason, ReputationLedger, STARTING_REPUTATION},
+    search::{self, Doc},
+};
+use suiter_ranker::FeedRanker;
+
+/// Columns holding addresses. Rows are moved with `UPDATE OR IGNORE`; ones
+/// that would collide with the canonical address's own (a vote on the same
+/// claim, the same reputation event) are duplicates and dropped.
+const ADDRESS_COLUMNS: &[(&str, &str)] = &[
+    ("posts", "author"),
+    ("attention_sessions", "reader"),
+    ("attention_rewards", "creator"),
+    ("attention_rewards", "reader"),
+    ("truth_claims", "claimer"),
+    ("claim_votes", "voter"),
+    ("risk_flags", "address"),
+    ("profile_objects", "owner"),
+    ("creator_lifelines", "recipient"),
+    ("lifeline_supports", "supporter"),
+    ("lifeline_supports", "recipient"),
+    ("pending_supports", "supporter"),
+    ("pending_supports", "recipient"),
+    ("lifeline_refunds", "supporter"),
+    ("lifeline_refunds", "recipient"),
+    ("reputation_events", "address"),
+    ("sponsored_transactions", "sender"),
+    ("sponsored_transactions", "sponsor"),
+    ("pending_transactions", "sender"),
+];
+
+/// `normalize-addresses [--dry-run]`: merge profiles and rows stored under
+/// unnormalized spellings of an address (`0xABC`, `0xabc`, `0x0abc`) into
+/// its canonical form. Stats are summed, reputation is the highest of the
+/// merged profiles, and ledger balances are combined.
+pub async fn normalize(pool: &SqlitePool, dry_run: bool) -> Result<()> {
+    let mut union = vec!["SELECT address FROM profiles".to_string()];
+    union.extend(ADDRESS_COLUMNS.iter().map(|(table, column)| format!("SELECT {} FROM {}", column, table)));
+    union.push("SELECT substr(account, instr(account, ':') + 1) FROM ledger_entries WHERE account LIKE 'user:%' OR account LIKE 'wallet:%'".to_string());
+    let raw: Vec<String> = sqlx::query_scalar(&union.join(" UNION ")).fetch_all(pool).await?;
+
+    let mut groups: BTreeMap<SuiAddress, Vec<String>> = BTreeMap::new();
+    let mut invalid = Vec::new();
+    for address in raw {
+        match SuiAddress::parse(&address) {
+            Ok(canonical) if canonical.as_str() != address => groups.entry(canonical).or_default().push(address),
+            Ok(_) => {}
+            Err(_) => invalid.push(address),
+        }
+    }
+
+    for (canonical, raws) in &groups {
+        if dry_run {
+            info!("would merge {} into {}", raws.join(", "), canonical);
+            continue;
+        }
+        merge(pool, canonical, raws).await?;
+        FeedRanker::mark_author_dirty(pool, canonical.as_str(), "reputation").await?;
+        for address in raws.iter().map(|r| r.as_str()).chain([canonical.as_str()]) {
+            search::index(pool, Doc::Profile, address).await?;
+        }
+        info!("Merged {} into {}", raws.join(", "), canonical);
+    }
+    info!("{} addresses {}normalized", groups.len(), if dry_run { "would be " } else { "" });
+
+    if invalid.is_empty() {
+        return Ok(());
+    }
+    for address in &invalid {
+        info!("invalid: {:?} is not a Sui address and was left as is", address);
+    }
+    Err(anyhow!("{} stored addresses are not valid Sui addresses", invalid.len()))
+}
+
+/// Merge every spelling in `raws` into `canonical` in one transaction
+async fn merge(pool: &SqlitePool, canonical: &SuiAddress, raws: &[String]) -> Result<()> {
+    let mut spellings: Vec<&str> = raws.iter().map(|r| r.as_str()).collect();
+    spellings.push(canonical.as_str());
+    let mut reputation: Option<i64> = None;
+    for address in &spellings {
+        let rep: Option<i64> = sqlx::query_scalar("SELECT reputation FROM profiles WHERE address = ?")
+            .bind(address)
+            .fetch_optional(pool)
+            .await?;
+        reputation = reputation.max(rep);
+    }
+
+    // The canonical profile goes in first so foreign keys hold throughout
+    let mut tx = pool.begin().await?;
+    sqlx::query("INSERT OR IGNORE INTO profiles(address, reputation, total_posts, total_attention_earned, joined_at, updated_at) VALUES (?, ?, 0, 0, CURRENT_TIMESTAMP, CURRENT_TIMESTAMP)")
+        .bind(canonical)
+        .bind(STARTING_REPUTATION)
+        .execute(&mut *tx)
+        .await?;
+
+    for raw in raws {
+        sqlx::query(
+            r#"
+            UPDATE profiles SET
+                total_posts = total_posts + (SELECT total_posts FROM profiles WHERE address = ?2),
+                total_attention_earned = total_attention_earned + (SELECT total_attention_earned FROM profiles WHERE address = ?2),
+                joined_at = MIN(joined_at, (SELECT joined_at FROM profiles WHERE address = ?2)),
+                display_name = COALESCE(display_name, (SELECT display_name FROM profiles WHERE address = ?2)),
+                updated_at = CURRENT_TIMESTAMP
+            WHERE address = ?1 AND EXISTS (SELECT 1 FROM profiles WHERE address = ?2)
+            "#
+        )
+        .bind(canonical)
+        .bind(raw)
+        .execute(&mut *tx)
+        .await?;
+
+        // Client nonces are unique per author; a retry under both spellings
+        // keeps the canonical post's
+        sqlx::query("UPDATE posts SET client_nonce = NULL WHERE author = ?2 AND client_nonce IN (SELECT client_nonce FROM posts WHERE author = ?1)")
+            .bind(canonical)
+            .bind(raw)
+            .execute(&mut *tx)
+            .await?;
+        // Refunds owed for the same lifeline add up rather than being dropped
+        sqlx::query(
+            r#"
+            UPDATE lifeline_refunds SET
+                amount = amount + (SELECT o.amount FROM lifeline_refunds o WHERE o.lifeline_id = lifeline_refunds.lifeline_id AND o.supporter = ?2),
+                supports = supports + (SELECT o.supports FROM lifeline_refunds o WHERE o.lifeline_id = lifeline_refunds.lifeline_id AND o.supporter = ?2)
+            WHERE supporter = ?1 AND lifeline_id IN (SELECT lifeline_id FROM lifeline_refunds WHERE supporter = ?2)
+            "#
+        )
+        .bind(canonical)
+        .bind(raw)
+        .execute(&mut *tx)
+        .await?;
+
+        for (table, column) in ADDRESS_COLUMNS {
+            sqlx::query(&format!("UPDATE OR IGNORE {0} SET {1} = ?1 WHERE {1} = ?2", table, column))
+                .bind(canonical)
+                .bind(raw)
+                .execute(&mut *tx)
+                .await?;
+            let dropped = sqlx::query(&format!("DELETE FROM {} WHERE {} = ?", table, column))
+                .bind(raw)
+                .execute(&mut *tx)
+                .await?
+                .rows_affected();
+            if dropped > 0 {
+                info!("Dropped {} {} rows of {} duplicating {}", dropped, table, raw, canonical);
+            }
+        }
+
+        // Entries of one ledger transaction are unique per account, so those
+        // are folded into the canonical account's entry to keep it balanced
+        for (from, to) in [(ledger::user(raw), ledger::user(canonical.as_str())), (ledger::wallet(raw), ledger::wallet(canonical.as_str()))] {
+            sqlx::query(
+                r#"
+                UPDATE ledger_entries SET
+                    amount = amount + (SELECT o.amount FROM ledger_entries o WHERE o.txn_id = ledger_entries.txn_id AND o.account = ?2)
+                WHERE account = ?1 AND txn_id IN (SELECT txn_id FROM ledger_entries WHERE account = ?2)
+                "#
+            )
+            .bind(&to)
+            .bind(&from)
+            .execute(&mut *tx)
+            .await?;
+            sqlx::query("UPDATE OR IGNORE ledger_entries SET account = ?1 WHERE account = ?2")
+                .bind(&to)
+                .bind(&from)
+                .execute(&mut *tx)
+                .await?;
+            sqlx::query("DELETE FROM ledger_entries WHERE account = ?")
+                .bind(&from)
+                .execute(&mut *tx)
+                .await?;
+        }
+
+        sqlx::query("DELETE FROM profiles WHERE address = ?")
+            .bind(raw)
+            .execute(&mut *tx)
+            .await?;
+    }
+
+    // The merged events now sum to something none of the profiles had;
+    // materialize that, then settle on the highest merged reputation
+    sqlx::query("UPDATE profiles SET reputation = ?2 + (SELECT COALESCE(SUM(delta), 0) FROM reputation_events WHERE address = ?1) WHERE address = ?1")
+        .bind(canonical)
+        .bind(STARTING_REPUTATION)
+        .execute(&mut *tx)
+        .await?;
+    if let Some(reputation) = reputation {
+        let source_ref = format!("merge:{}", raws.join(","));
+        ReputationLedger::set(&mut tx, canonical.as_str(), reputation, Reason::AdminAdjustment, &source_ref).await?;
+    }
+    tx.commit().await?;
+
+    Ok(())
+}
@@ -0,0 +1,89 @@
+use anyhow::Result;
+use sqlx::sqlite::SqlitePoolOptions;
+use std::{path::Path, time::Instant};
+use tracing::info;
+
+use suiter_core::schema;
+use suiter_ranker::FeedRanker;
+
+/// Ranking benchmark
+/// Seeds an in-memory database with synthetic posts and compares a full
+/// sweep against re-scoring only a dirty subset. The database is built from
+/// database/migrations, or from MIGRATIONS_DIR when set.
+/// Usage: suiter-indexer bench-ranking [posts] [dirty]
+pub async fn run(posts: i64, dirty: i64) -> Result<()> {
+    // A single connection, since every in-memory connection is its own database
+    let pool = SqlitePoolOptions::new()
+        .max_connections(1)
+        .connect("sqlite::memory:")
+        .await?;
+
+    let migrations = std::env::var("MIGRATIONS_DIR").unwrap_or_else(|_| schema::MIGRATIONS_DIR.to_string());
+    schema::apply(&pool, Path::new(&migrations)).await?;
+
+    info!("Seeding {} synthetic posts...", posts);
+    let authors = (posts / 100).max(1);
+    sqlx::query(
+        r#"
+        INSERT INTO profiles (address, reputation)
+        WITH RECURSIVE seq(n) AS (SELECT 1 UNION ALL SELECT n + 1 FROM seq WHERE n < ?)
+        SELECT 'author_' || n, 50 + (n * 7919) % 100000 FROM seq
+        "#
+    )
+    .bind(authors)
+    .execute(&pool)
+    .await?;
+
+    sqlx::query(
+        r#"
+        INSERT INTO posts (id, author, content_hash, level, attention_accumulated, created_at)
+        WITH RECURSIVE seq(n) AS (SELECT 1 UNION ALL SELECT n + 1 FROM seq WHERE n < ?1)
+        SELECT
+            'post_' || n,
+            'author_' || (n % ?2 + 1),
+            'hash_' || n,
+            1 + n % 5,
+            (n * 104729) % 100000,
+            datetime('now', '-' || (n % 7200) || ' seconds')
+        FROM seq
+        "#
+    )
+    .bind(posts)
+    .bind(authors)
+    .execute(&pool)
+    .await?;
+
+    let ranker = FeedRanker::new(pool.clone());
+
+    let started = Instant::now();
+    ranker.recompute_all().await?;
+    let full = started.elapsed();
+
+    let step = (posts / dirty.max(1)).max(1);
+    sqlx::query("INSERT INTO ranking_dirty (post_id, reason, marked_at) SELECT id, 'bench', 0 FROM posts WHERE rowid % ? = 0")
+        .bind(step)
+        .execute(&pool)
+        .await?;
+
+    let started = Instant::now();
+    let mut rescored = 0;
+    loop {
+        let n = ranker.rescore_dirty().await?;
+        if n == 0 {
+            break;
+        }
+        rescored += n;
+    }
+    let incremental = started.elapsed();
+
+    let top = ranker.get_top_posts(1).await?;
+
+    println!("posts:             {}", posts);
+    println!("full sweep:        {:?} ({:.2} us/post)", full, full.as_micros() as f64 / posts as f64);
+    println!("incremental:       {:?} for {} dirty posts ({:.2} us/post)", incremental, rescored, incremental.as_micros() as f64 / rescored.max(1) as f64);
+    if let Some((post_id, score)) = top.first() {
+        println!("top post:          {} ({:.6})", post_id, score);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,286 @@
+use anyhow::Result;
+use sqlx::{SqlitePool, Row};
+use std::time::Duration;
+use tokio::time::sleep;
+use tracing::info;
+
+use suiter_core::ledger::{self, LedgerTxn};
+
+/// How often lifeline deadlines are checked
+const CHECK_INTERVAL: Duration = Duration::from_secs(600);
+
+pub const REASON_MISSED_DEADLINE: &str = "missed_deadline";
+
+/// Active lifelines whose creator hasn't posted within `cadence_days` of the
+/// later of the lifeline's creation and their last post since then
+const OVERDUE_QUERY: &str = r#"
+WITH anchored AS (
+    SELECT l.id, l.recipient, l.cadence_days,
+        (SELECT MAX(p.created_at) FROM posts p WHERE p.author = l.recipient AND p.created_at >= l.created_at) as last_post_at,
+        l.created_at
+    FROM creator_lifelines l
+    WHERE l.active = TRUE
+)
+SELECT id, recipient, last_post_at,
+    COALESCE(last_post_at, created_at) as anchor,
+    datetime(COALESCE(last_post_at, created_at), '+' || cadence_days || ' days') as deadline
+FROM anchored
+WHERE datetime(COALESCE(last_post_at, created_at), '+' || cadence_days || ' days') < CURRENT_TIMESTAMP
+"#;
+
+/// Lifeline activity monitor
+/// Holds creators to the posting cadence they committed to. A lifeline that
+/// misses its deadline is deactivated, the miss is recorded, and supporters
+/// are owed back whatever they sent after the creator's last post. The debt
+/// is posted to the ledger from the creator's `user` account to each
+/// supporter's.
+pub struct LifelineMonitor {
+    pool: SqlitePool,
+}
+
+impl LifelineMonitor {
+    pub fn new(pool: SqlitePool) -> Self {
+        LifelineMonitor { pool }
+    }
+
+    pub async fn run(&self) -> Result<()> {
+        info!("Starting lifeline monitor loop...");
+
+        loop {
+            match self.check().await {
+                Ok(0) => {}
+                Ok(n) => info!("Deactivated {} lifelines for missed deadlines", n),
+                Err(e) => tracing::error!("Error checking lifeline deadlines: {}", e),
+            }
+
+            sleep(CHECK_INTERVAL).await;
+        }
+    }
+
+    pub async fn check(&self) -> Result<usize> {
+        let overdue = sqlx::query(OVERDUE_QUERY).fetch_all(&self.pool).await?;
+
+        let mut deactivated = 0;
+        for row in overdue {
+            let id = row.get::<String, _>("id");
+            if self
+                .deactivate(&id, &row.get::<String, _>("anchor"), &row.get::<String, _>("deadline"), row.get("last_post_at"))
+                .await?
+            {
+                tracing::warn!("Lifeline {} for {} missed its deadline", id, row.get::<String, _>("recipient"));
+                deactivated += 1;
+            }
+        }
+
+        Ok(deactivated)
+    }
+
+    /// Record the miss, deactivate and compute refunds in one transaction.
+    /// Returns false if the lifeline was already deactivated.
+    async fn deactivate(&self, id: &str, anchor: &str, deadline: &str, last_post_at: Option<String>) -> Result<bool> {
+        let mut tx = self.pool.begin().await?;
+
+        let updated = sqlx::query("UPDATE creator_lifelines SET active = FALSE, deactivated_at = CURRENT_TIMESTAMP, deactivation_reason = ? WHERE id = ? AND active = TRUE")
+            .bind(REASON_MISSED_DEADLINE)
+            .bind(id)
+            .execute(&mut *tx)
+            .await?;
+        if updated.rows_affected() == 0 {
+            return Ok(false);
+        }
+
+        sqlx::query("INSERT OR IGNORE INTO lifeline_missed_deadlines(lifeline_id, deadline, last_post_at, detected_at) VALUES (?, ?, ?, CURRENT_TIMESTAMP)")
+            .bind(id)
+            .bind(deadline)
+            .bind(&last_post_at)
+            .execute(&mut *tx)
+            .await?;
+
+        // Support that arrived after the last post paid for activity that never came
+        sqlx::query(
+            r#"
+            INSERT OR IGNORE INTO lifeline_refunds(lifeline_id, supporter, recipient, amount, supports, status, created_at)
+            SELECT lifeline_id, supporter, recipient, SUM(amount), COUNT(*), 'owed', CURRENT_TIMESTAMP
+            FROM lifeline_supports
+            WHERE lifeline_id = ? AND created_at >= ?
+            GROUP BY supporter, recipient
+            "#
+        )
+        .bind(id)
+        .bind(anchor)
+        .execute(&mut *tx)
+        .await?;
+
+        let refunds = sqlx::query("SELECT supporter, recipient, amount FROM lifeline_refunds WHERE lifeline_id = ?")
+            .bind(id)
+            .fetch_all(&mut *tx)
+            .await?;
+        let mut txn = LedgerTxn::new();
+        for r in &refunds {
+            txn.transfer(
+                &ledger::user(&r.get::<String, _>("recipient")),
+                &ledger::user(&r.get::<String, _>("supporter")),
+                r.get::<i64, _>("amount"),
+            );
+        }
+        txn.post(&mut tx, &format!("refund:lifeline:{}", id), "lifeline_refund", None).await?;
+
+        tx.commit().await?;
+        Ok(true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use suiter_core::testing::migrated_pool;
+
+    const CREATOR: &str = "0xcreator";
+
+    async fn lifeline(pool: &SqlitePool, id: &str, recipient: &str, age: &str, active: bool) {
+        sqlx::query("INSERT OR IGNORE INTO profiles(address) VALUES (?)")
+            .bind(recipient)
+            .execute(pool)
+            .await
+            .unwrap();
+        sqlx::query("INSERT INTO creator_lifelines(id, recipient, active, cadence_days, created_at) VALUES (?, ?, ?, 7, datetime('now', ?))")
+            .bind(id)
+            .bind(recipient)
+            .bind(active)
+            .bind(age)
+            .execute(pool)
+            .await
+            .unwrap();
+    }
+
+    async fn post(pool: &SqlitePool, id: &str, author: &str, age: &str) {
+        sqlx::query("INSERT INTO posts(id, author, content_hash, created_at) VALUES (?, ?, 'h', datetime('now', ?))")
+            .bind(id)
+            .bind(author)
+            .bind(age)
+            .execute(pool)
+            .await
+            .unwrap();
+    }
+
+    async fn support(pool: &SqlitePool, id: &str, lifeline: &str, supporter: &str, amount: i64, age: &str) {
+        sqlx::query("INSERT OR IGNORE INTO profiles(address) VALUES (?)")
+            .bind(supporter)
+            .execute(pool)
+            .await
+            .unwrap();
+        sqlx::query(
+            "INSERT INTO lifeline_supports(id, lifeline_id, supporter, recipient, amount, support_day, source, created_at) SELECT ?, id, ?, recipient, ?, date('now', ?4), 'chain', datetime('now', ?4) FROM creator_lifelines WHERE id = ?5"
+        )
+        .bind(id)
+        .bind(supporter)
+        .bind(amount)
+        .bind(age)
+        .bind(lifeline)
+        .execute(pool)
+        .await
+        .unwrap();
+    }
+
+    async fn balance(pool: &SqlitePool, account: &str) -> i64 {
+        sqlx::query_scalar("SELECT COALESCE(SUM(amount), 0) FROM ledger_entries WHERE account = ?")
+            .bind(account)
+            .fetch_one(pool)
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn only_lifelines_past_their_cadence_are_overdue() {
+        let pool = migrated_pool().await;
+
+        lifeline(&pool, "never_posted", "0xa", "-10 days", true).await;
+        lifeline(&pool, "posted_recently", "0xb", "-10 days", true).await;
+        post(&pool, "0xpb", "0xb", "-2 days").await;
+        lifeline(&pool, "posted_long_ago", "0xc", "-30 days", true).await;
+        post(&pool, "0xpc", "0xc", "-20 days").await;
+        lifeline(&pool, "young", "0xd", "-3 days", true).await;
+        // Posts from before the lifeline don't count towards it
+        lifeline(&pool, "posted_before", "0xe", "-10 days", true).await;
+        post(&pool, "0xpe", "0xe", "-12 days").await;
+        lifeline(&pool, "inactive", "0xf", "-30 days", false).await;
+
+        let mut overdue: Vec<(String, Option<String>)> = sqlx::query(OVERDUE_QUERY)
+            .fetch_all(&pool)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|r| (r.get("id"), r.get("last_post_at")))
+            .collect();
+        overdue.sort();
+        let ids: Vec<&str> = overdue.iter().map(|(id, _)| id.as_str()).collect();
+        assert_eq!(ids, ["never_posted", "posted_before", "posted_long_ago"]);
+        assert_eq!(overdue[0].1, None);
+        assert!(overdue[2].1.is_some());
+    }
+
+    #[tokio::test]
+    async fn missed_deadlines_owe_back_support_sent_since_the_last_post() {
+        let pool = migrated_pool().await;
+        let monitor = LifelineMonitor::new(pool.clone());
+
+        lifeline(&pool, "life", CREATOR, "-30 days", true).await;
+        post(&pool, "0xp", CREATOR, "-20 days").await;
+        // Paid for the post the creator did make
+        support(&pool, "s1", "life", "0xs1", 1_000, "-25 days").await;
+        // Paid for activity that never came
+        support(&pool, "s2", "life", "0xs1", 300, "-15 days").await;
+        support(&pool, "s3", "life", "0xs1", 200, "-10 days").await;
+        support(&pool, "s4", "life", "0xs2", 700, "-5 days").await;
+
+        assert_eq!(monitor.check().await.unwrap(), 1);
+
+        let (active, reason): (bool, String) = sqlx::query_as("SELECT active, deactivation_reason FROM creator_lifelines WHERE id = 'life'")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert!(!active);
+        assert_eq!(reason, REASON_MISSED_DEADLINE);
+        let missed: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM lifeline_missed_deadlines WHERE lifeline_id = 'life' AND last_post_at IS NOT NULL")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(missed, 1);
+
+        let refunds: Vec<(String, i64, i64, String)> = sqlx::query_as("SELECT supporter, amount, supports, status FROM lifeline_refunds ORDER BY supporter")
+            .fetch_all(&pool)
+            .await
+            .unwrap();
+        assert_eq!(refunds, [
+            ("0xs1".to_string(), 500, 2, "owed".to_string()),
+            ("0xs2".to_string(), 700, 1, "owed".to_string()),
+        ]);
+
+        assert_eq!(balance(&pool, &ledger::user("0xs1")).await, 500);
+        assert_eq!(balance(&pool, &ledger::user("0xs2")).await, 700);
+        assert_eq!(balance(&pool, &ledger::user(CREATOR)).await, -1_200);
+    }
+
+    #[tokio::test]
+    async fn checking_twice_changes_nothing() {
+        let pool = migrated_pool().await;
+        let monitor = LifelineMonitor::new(pool.clone());
+
+        lifeline(&pool, "life", CREATOR, "-10 days", true).await;
+        support(&pool, "s1", "life", "0xs1", 400, "-5 days").await;
+
+        assert_eq!(monitor.check().await.unwrap(), 1);
+        assert_eq!(monitor.check().await.unwrap(), 0);
+        // A stale read of the overdue list can't deactivate it again either
+        assert!(!monitor.deactivate("life", "2000-01-01 00:00:00", "2000-01-08 00:00:00", None).await.unwrap());
+
+        let counts: (i64, i64, i64) = sqlx::query_as(
+            "SELECT (SELECT COUNT(*) FROM lifeline_missed_deadlines), (SELECT COUNT(*) FROM lifeline_refunds), (SELECT COUNT(*) FROM ledger_entries)"
+        )
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        assert_eq!(counts, (1, 1, 2));
+        assert_eq!(balance(&pool, &ledger::user("0xs1")).await, 400);
+    }
+}
@@ -1,11 +1,21 @@
 use anyhow::Result;
-use sqlx::sqlite::SqlitePoolOptions;
-use sqlx::Row;
+use sqlx::{sqlite::SqlitePoolOptions, SqlitePool};
 use std::env;
+use suiter_core::address::SuiAddress;
 use tracing::{info, error};
 
+mod address_migration;
+mod bench;
+mod lifeline_monitor;
+mod notification_dispatcher;
+mod pool_tracker;
+mod post_sync;
+mod reputation_admin;
+mod reputation_decay;
 mod sui_indexer;
-mod feed_ranker;
+mod sybil_detector;
+mod tx_reconciler;
+mod webhook_dispatcher;
 
 #[tokio::main]
 async fn main() -> Result<()> {
@@ -14,6 +24,63 @@ async fn main() -> Result<()> {
         .with_env_filter(tracing_subscriber::EnvFilter::from_default_env())
         .init();
 
+    // Maintenance commands run instead of the indexer
+    let args: Vec<String> = env::args().skip(1).collect();
+    if let Some(command) = args.first() {
+        return match command.as_str() {
+            "bench-ranking" => {
+                let posts = args.get(1).map(|s| s.parse()).transpose()?.unwrap_or(1_000_000);
+                let dirty = args.get(2).map(|s| s.parse()).transpose()?.unwrap_or(1_000);
+                bench::run(posts, dirty).await
+            }
+            "decay-reputation" => {
+                let decayed = reputation_decay::ReputationDecay::new(connect().await?).apply().await?;
+                info!("Decayed {} profiles", decayed);
+                Ok(())
+            }
+            "check-reputation" => {
+                let rebuild = args.get(1).is_some_and(|a| a == "--rebuild");
+                reputation_admin::check(&connect().await?, rebuild).await
+            }
+            "fund-pool" => {
+                let (Some(amount), Some(note)) = (args.get(1), args.get(2)) else {
+                    return Err(anyhow::anyhow!("usage: fund-pool <amount_mist> <note>"));
+                };
+                pool_tracker::fund(&connect().await?, amount.parse()?, note).await
+            }
+            "check-sync" => post_sync::check(&connect().await?).await,
+            "replay-events" => {
+                let rpc_url = env::var("SUI_RPC_URL").unwrap_or_else(|_| "https://fullnode.testnet.sui.io:443".to_string());
+                let indexer = sui_indexer::SuiIndexer::new(rpc_url, env::var("PACKAGE_ID").ok(), connect().await?);
+                let (replayed, failed) = indexer.replay_dead().await?;
+                info!("Replayed {} dead-lettered events, {} still failing", replayed, failed);
+                Ok(())
+            }
+            "reindex-search" => {
+                let indexed = suiter_core::search::rebuild(&connect().await?).await?;
+                info!("Indexed {} search documents", indexed);
+                Ok(())
+            }
+            "reindex-tags" => {
+                let tagged = suiter_core::tags::rebuild(&connect().await?).await?;
+                info!("Stored {} post tags", tagged);
+                Ok(())
+            }
+            "normalize-addresses" => {
+                let dry_run = args.get(1).is_some_and(|a| a == "--dry-run");
+                address_migration::normalize(&connect().await?, dry_run).await
+            }
+            "adjust-reputation" => {
+                let (Some(address), Some(delta), Some(note)) = (args.get(1), args.get(2), args.get(3)) else {
+                    return Err(anyhow::anyhow!("usage: adjust-reputation <address> <delta> <note>"));
+                };
+                let address = SuiAddress::parse(address)?;
+                reputation_admin::adjust(&connect().await?, address.as_str(), delta.parse()?, note).await
+            }
+            other => Err(anyhow::anyhow!("unknown command: {}", other)),
+        };
+    }
+
     info!("Starting SUITER Indexer...");
 
     // Load environment variables
@@ -22,12 +89,11 @@ async fn main() -> Result<()> {
         .expect("DATABASE_URL must be set");
     let sui_rpc_url = env::var("SUI_RPC_URL")
         .unwrap_or_else(|_| "https://fullnode.testnet.sui.io:443".to_string());
+    let package_id = env::var("PACKAGE_ID").ok();
+    let attention_pool_id = env::var("ATTENTION_POOL_ID").ok();
 
     // Setup database connection pool
-    let pool = SqlitePoolOptions::new()
-        .max_connections(5)
-        .connect(&database_url)
-        .await?;
+    let pool = connect().await?;
 
     info!("Connected to SQLite");
 
@@ -46,8 +112,16 @@ async fn main() -> Result<()> {
     info!("Database initialized");
 
     // Initialize indexer components
-    let indexer = sui_indexer::SuiIndexer::new(sui_rpc_url.clone(), pool.clone());
-    let ranker = feed_ranker::FeedRanker::new(pool.clone());
+    let post_sync = post_sync::PostSync::new(package_id.is_some(), pool.clone());
+    let indexer = sui_indexer::SuiIndexer::new(sui_rpc_url.clone(), package_id, pool.clone());
+    let ranker = suiter_ranker::FeedRanker::new(pool.clone());
+    let sybil_detector = sybil_detector::SybilDetector::new(pool.clone());
+    let lifeline_monitor = lifeline_monitor::LifelineMonitor::new(pool.clone());
+    let reputation_decay = reputation_decay::ReputationDecay::new(pool.clone());
+    let pool_tracker = pool_tracker::PoolTracker::new(sui_rpc_url.clone(), attention_pool_id, pool.clone());
+    let tx_reconciler = tx_reconciler::TxReconciler::new(sui_rpc_url.clone(), pool.clone());
+    let notification_dispatcher = notification_dispatcher::NotificationDispatcher::new(pool.clone());
+    let webhook_dispatcher = webhook_dispatcher::WebhookDispatcher::new(pool.clone());
 
     // Start indexer task
     let indexer_handle = tokio::spawn(async move {
@@ -56,13 +130,69 @@ async fn main() -> Result<()> {
         }
     });
 
-    // Start feed ranker task (updates every 5 minutes)
+    // Start feed ranker task (dirty posts promptly, full sweep every 5 minutes)
     let ranker_handle = tokio::spawn(async move {
         if let Err(e) = ranker.run().await {
             error!("Feed ranker error: {}", e);
         }
     });
 
+    // Start Sybil detector task (rebuilds attention/vote graphs every 15 minutes)
+    let sybil_handle = tokio::spawn(async move {
+        if let Err(e) = sybil_detector.run().await {
+            error!("Sybil detector error: {}", e);
+        }
+    });
+
+    // Start lifeline monitor task (posting deadlines checked every 10 minutes)
+    let lifeline_handle = tokio::spawn(async move {
+        if let Err(e) = lifeline_monitor.run().await {
+            error!("Lifeline monitor error: {}", e);
+        }
+    });
+
+    // Start reputation decay task (inactive days applied once each, checked hourly)
+    let decay_handle = tokio::spawn(async move {
+        if let Err(e) = reputation_decay.run().await {
+            error!("Reputation decay error: {}", e);
+        }
+    });
+
+    // Start attention pool tracker task (on-chain pool polled every minute)
+    let pool_handle = tokio::spawn(async move {
+        if let Err(e) = pool_tracker.run().await {
+            error!("Attention pool tracker error: {}", e);
+        }
+    });
+
+    // Start transaction reconciler task (submitted transactions checked every 15 seconds)
+    let reconciler_handle = tokio::spawn(async move {
+        if let Err(e) = tx_reconciler.run().await {
+            error!("Transaction reconciler error: {}", e);
+        }
+    });
+
+    // Start provisional post sync task (failed API posts expired, stragglers marked orphaned, every 5 minutes)
+    let post_sync_handle = tokio::spawn(async move {
+        if let Err(e) = post_sync.run().await {
+            error!("Provisional post sync error: {}", e);
+        }
+    });
+
+    // Start notification dispatcher task (new notifications delivered every 2 seconds)
+    let notification_handle = tokio::spawn(async move {
+        if let Err(e) = notification_dispatcher.run().await {
+            error!("Notification dispatcher error: {}", e);
+        }
+    });
+
+    // Start webhook dispatcher task (due deliveries sent every 5 seconds, failures backed off)
+    let webhook_handle = tokio::spawn(async move {
+        if let Err(e) = webhook_dispatcher.run().await {
+            error!("Webhook dispatcher error: {}", e);
+        }
+    });
+
     info!("SUITER Indexer running!");
     info!("RPC: {}", sui_rpc_url);
     info!("Database: {}", database_url);
@@ -71,7 +201,26 @@ async fn main() -> Result<()> {
     tokio::select! {
         _ = indexer_handle => info!("Indexer exited"),
         _ = ranker_handle => info!("Feed ranker exited"),
+        _ = sybil_handle => info!("Sybil detector exited"),
+        _ = lifeline_handle => info!("Lifeline monitor exited"),
+        _ = decay_handle => info!("Reputation decay exited"),
+        _ = pool_handle => info!("Attention pool tracker exited"),
+        _ = reconciler_handle => info!("Transaction reconciler exited"),
+        _ = post_sync_handle => info!("Provisional post sync exited"),
+        _ = notification_handle => info!("Notification dispatcher exited"),
+        _ = webhook_handle => info!("Webhook dispatcher exited"),
     }
 
     Ok(())
 }
+
+async fn connect() -> Result<SqlitePool> {
+    dotenv::dotenv().ok();
+    let database_url = env::var("DATABASE_URL")
+        .expect("DATABASE_URL must be set");
+
+    Ok(SqlitePoolOptions::new()
+        .max_connections(5)
+        .connect(&database_url)
+        .await?)
+}
@@ -0,0 +1,436 @@
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use serde_json::{json, Value};
+use sqlx::{Row, SqlitePool};
+use std::time::Duration;
+use tokio::time::sleep;
+use tracing::info;
+
+use suiter_core::stream;
+
+use crate::webhook_dispatcher::{self, WebhookClient};
+
+/// How often undelivered notifications are picked up
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+/// Notifications dispatched per pass
+const BATCH_SIZE: i64 = 100;
+/// Attempts per sink before a notification is given up on; failed sinks
+/// back off like outgoing webhooks
+const MAX_ATTEMPTS: i64 = 5;
+
+/// One `notifications` row
+pub struct Notification {
+    pub id: i64,
+    pub recipient: String,
+    pub kind: String,
+    pub payload: Value,
+    pub in_app: bool,
+    pub created_at: String,
+}
+
+impl Notification {
+    fn to_json(&self) -> Value {
+        json!({
+            "id": self.id,
+            "recipient": self.recipient,
+            "kind": self.kind,
+            "payload": self.payload,
+            "created_at": self.created_at,
+        })
+    }
+}
+
+/// Somewhere notifications are delivered
+#[async_trait]
+pub trait NotificationSink: Send + Sync {
+    /// Recorded in `notification_deliveries`
+    fn name(&self) -> &'static str;
+
+    /// Deliver `n`; Ok(false) when the recipient doesn't want it here
+    async fn deliver(&self, pool: &SqlitePool, n: &Notification) -> Result<bool>;
+}
+
+/// Pushes notifications to the recipient's `profile:<address>` stream topic,
+/// so open clients see them without polling `GET /api/notifications`
+pub struct InAppSink;
+
+#[async_trait]
+impl NotificationSink for InAppSink {
+    fn name(&self) -> &'static str {
+        "in_app"
+    }
+
+    async fn deliver(&self, pool: &SqlitePool, n: &Notification) -> Result<bool> {
+        if !n.in_app {
+            return Ok(false);
+        }
+        stream::publish(pool, &stream::profile_topic(&n.recipient), "notification", &n.to_json()).await?;
+        Ok(true)
+    }
+}
+
+/// POSTs notifications as JSON to the recipient's registered webhook URL,
+/// signed with its secret the way outgoing webhooks are. The event header
+/// is `notification.<kind>` and the delivery header `notification:<id>`.
+pub struct WebhookSink {
+    client: WebhookClient,
+}
+
+impl WebhookSink {
+    pub fn new() -> Self {
+        WebhookSink { client: WebhookClient::from_env() }
+    }
+}
+
+#[async_trait]
+impl NotificationSink for WebhookSink {
+    fn name(&self) -> &'static str {
+        "webhook"
+    }
+
+    async fn deliver(&self, pool: &SqlitePool, n: &Notification) -> Result<bool> {
+        let webhook = sqlx::query(
+            r#"
+            SELECT w.url, w.secret FROM notification_webhooks w
+            LEFT JOIN notification_preferences p ON p.address = w.address AND p.kind = ?2
+            WHERE w.address = ?1 AND COALESCE(p.webhook, TRUE)
+            "#
+        )
+        .bind(&n.recipient)
+        .bind(&n.kind)
+        .fetch_optional(pool)
+        .await?;
+        let Some(webhook) = webhook else {
+            return Ok(false);
+        };
+
+        let (_, error) = self.client.send(
+            &webhook.get::<String, _>("url"),
+            &webhook.get::<String, _>("secret"),
+            &format!("notification.{}", n.kind),
+            &format!("notification:{}", n.id),
+            n.to_json().to_string(),
+        )
+        .await;
+        match error {
+            None => Ok(true),
+            Some(e) => Err(anyhow!(e)),
+        }
+    }
+}
+
+/// Notification dispatcher
+/// Hands every new notification to each sink. A sink that fails is retried
+/// on later passes with a growing delay, up to MAX_ATTEMPTS; the notification
+/// is marked dispatched once every sink has delivered, declined or given up.
+pub struct NotificationDispatcher {
+    pool: SqlitePool,
+    sinks: Vec<Box<dyn NotificationSink>>,
+}
+
+impl NotificationDispatcher {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self::with_sinks(pool, vec![Box::new(InAppSink), Box::new(WebhookSink::new())])
+    }
+
+    pub fn with_sinks(pool: SqlitePool, sinks: Vec<Box<dyn NotificationSink>>) -> Self {
+        NotificationDispatcher { pool, sinks }
+    }
+
+    pub async fn run(&self) -> Result<()> {
+        info!("Starting notification dispatcher loop...");
+
+        loop {
+            match self.dispatch().await {
+                Ok(0) => {}
+                Ok(n) => info!("Dispatched {} notifications", n),
+                Err(e) => tracing::error!("Error dispatching notifications: {}", e),
+            }
+
+            sleep(POLL_INTERVAL).await;
+        }
+    }
+
+    /// Dispatch one batch; returns how many notifications were finished
+    async fn dispatch(&self) -> Result<usize> {
+        let rows = sqlx::query(
+            r#"
+            SELECT id, recipient, kind, payload, in_app, created_at FROM notifications
+            WHERE dispatched_at IS NULL AND (next_attempt_at IS NULL OR next_attempt_at <= CURRENT_TIMESTAMP)
+            ORDER BY id
+            LIMIT ?
+            "#
+        )
+        .bind(BATCH_SIZE)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut finished = 0;
+        for r in rows {
+            let n = Notification {
+                id: r.get("id"),
+                recipient: r.get("recipient"),
+                kind: r.get("kind"),
+                payload: serde_json::from_str(&r.get::<String, _>("payload")).unwrap_or(Value::Null),
+                in_app: r.get("in_app"),
+                created_at: r.get("created_at"),
+            };
+
+            let mut retry_after: Option<i64> = None;
+            for sink in &self.sinks {
+                if let Some(attempts) = self.deliver(sink.as_ref(), &n).await? {
+                    let delay = webhook_dispatcher::backoff_secs(attempts);
+                    retry_after = Some(retry_after.map_or(delay, |d| d.min(delay)));
+                }
+            }
+
+            match retry_after {
+                Some(delay) => {
+                    sqlx::query("UPDATE notifications SET next_attempt_at = datetime('now', ?) WHERE id = ?")
+                        .bind(format!("+{} seconds", delay))
+                        .bind(n.id)
+                        .execute(&self.pool)
+                        .await?;
+                }
+                None => {
+                    sqlx::query("UPDATE notifications SET dispatched_at = CURRENT_TIMESTAMP WHERE id = ?")
+                        .bind(n.id)
+                        .execute(&self.pool)
+                        .await?;
+                    finished += 1;
+                }
+            }
+        }
+
+        Ok(finished)
+    }
+
+    /// Run one sink for `n` unless it already finished with it; returns the
+    /// attempts so far when it failed and should be retried
+    async fn deliver(&self, sink: &dyn NotificationSink, n: &Notification) -> Result<Option<i64>> {
+        let prior = sqlx::query("SELECT status, attempts FROM notification_deliveries WHERE notification_id = ? AND sink = ?")
+            .bind(n.id)
+            .bind(sink.name())
+            .fetch_optional(&self.pool)
+            .await?;
+        if let Some(prior) = prior {
+            let attempts: i64 = prior.get("attempts");
+            if prior.get::<String, _>("status") == "delivered" || attempts >= MAX_ATTEMPTS {
+                return Ok(None);
+            }
+        }
+
+        let (status, error) = match sink.deliver(&self.pool, n).await {
+            Ok(false) => return Ok(None),
+            Ok(true) => ("delivered", None),
+            Err(e) => ("failed", Some(e.to_string())),
+        };
+
+        let attempts: i64 = sqlx::query_scalar(
+            r#"
+            INSERT INTO notification_deliveries(notification_id, sink, status, attempts, error, updated_at)
+            VALUES (?, ?, ?, 1, ?, CURRENT_TIMESTAMP)
+            ON CONFLICT (notification_id, sink) DO UPDATE SET
+                status = excluded.status,
+                attempts = attempts + 1,
+                error = excluded.error,
+                updated_at = CURRENT_TIMESTAMP
+            RETURNING attempts
+            "#
+        )
+        .bind(n.id)
+        .bind(sink.name())
+        .bind(status)
+        .bind(&error)
+        .fetch_one(&self.pool)
+        .await?;
+
+        match error {
+            None => Ok(None),
+            Some(e) if attempts >= MAX_ATTEMPTS => {
+                tracing::warn!("Giving up on {} delivery of notification {} after {} attempts: {}", sink.name(), n.id, attempts, e);
+                Ok(None)
+            }
+            Some(e) => {
+                tracing::debug!("{} delivery of notification {} failed: {}", sink.name(), n.id, e);
+                Ok(Some(attempts))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use suiter_core::testing::migrated_pool;
+    use tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        net::TcpListener,
+        task::JoinHandle,
+    };
+
+    const SECRET: &str = "whsec_test";
+
+
+    fn notification(in_app: bool) -> Notification {
+        Notification {
+            id: 7,
+            recipient: "0xa".to_string(),
+            kind: "reply".to_string(),
+            payload: json!({ "post_id": "0xp", "reply_id": "0xr", "author": "0xb" }),
+            in_app,
+            created_at: "2026-01-01 00:00:00".to_string(),
+        }
+    }
+
+    /// Answer one HTTP request on a local port with `status`; resolves to
+    /// the request's head and body
+    async fn listener(status: u16) -> (String, JoinHandle<(String, String)>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/hook", listener.local_addr().unwrap());
+
+        let request = tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut buf = Vec::new();
+            let mut chunk = [0u8; 4096];
+            let (head, body) = loop {
+                let n = socket.read(&mut chunk).await.unwrap();
+                buf.extend_from_slice(&chunk[..n]);
+                let text = String::from_utf8_lossy(&buf).to_string();
+                if let Some((head, body)) = text.split_once("\r\n\r\n") {
+                    let length = head
+                        .lines()
+                        .find_map(|l| l.to_ascii_lowercase().strip_prefix("content-length:").map(|v| v.trim().parse::<usize>().unwrap()))
+                        .unwrap_or(0);
+                    if body.len() >= length || n == 0 {
+                        break (head.to_string(), body.to_string());
+                    }
+                }
+            };
+            let response = format!("HTTP/1.1 {} X\r\ncontent-length: 0\r\nconnection: close\r\n\r\n", status);
+            socket.write_all(response.as_bytes()).await.unwrap();
+            (head, body)
+        });
+
+        (url, request)
+    }
+
+    fn header<'a>(head: &'a str, name: &str) -> Option<&'a str> {
+        head.lines().find_map(|l| {
+            let (k, v) = l.split_once(':')?;
+            k.eq_ignore_ascii_case(name).then(|| v.trim())
+        })
+    }
+
+    /// A webhook sink allowed to reach the local listener
+    fn local_sink() -> WebhookSink {
+        WebhookSink { client: WebhookClient::new(true) }
+    }
+
+    async fn register_webhook(pool: &SqlitePool, url: &str) {
+        sqlx::query("INSERT INTO profiles(address) VALUES ('0xa')")
+            .execute(pool)
+            .await
+            .unwrap();
+        sqlx::query("INSERT INTO notification_webhooks(address, url, secret) VALUES ('0xa', ?, ?)")
+            .bind(url)
+            .bind(SECRET)
+            .execute(pool)
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn in_app_sink_publishes_to_the_recipient_topic() {
+        let pool = migrated_pool().await;
+
+        assert!(InAppSink.deliver(&pool, &notification(true)).await.unwrap());
+        let (topic, kind, payload): (String, String, String) = sqlx::query_as("SELECT topic, kind, payload FROM stream_events")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(topic, "profile:0xa");
+        assert_eq!(kind, "notification");
+        assert_eq!(serde_json::from_str::<Value>(&payload).unwrap()["payload"]["reply_id"], "0xr");
+
+        // Recipients who turned in-app off for the kind aren't pushed to
+        assert!(!InAppSink.deliver(&pool, &notification(false)).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn webhook_sink_posts_signed_notifications() {
+        let pool = migrated_pool().await;
+        let (url, request) = listener(200).await;
+        register_webhook(&pool, &url).await;
+
+        assert!(local_sink().deliver(&pool, &notification(true)).await.unwrap());
+        let (head, body) = request.await.unwrap();
+
+        assert!(head.starts_with("POST /hook "));
+        assert_eq!(header(&head, "x-suiter-event"), Some("notification.reply"));
+        assert_eq!(header(&head, "x-suiter-delivery"), Some("notification:7"));
+        let timestamp: i64 = header(&head, "x-suiter-timestamp").unwrap().parse().unwrap();
+        let expected = format!("sha256={}", webhook_dispatcher::sign(SECRET, timestamp, &body));
+        assert_eq!(header(&head, "x-suiter-signature"), Some(expected.as_str()));
+        let sent: Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(sent["id"], 7);
+        assert_eq!(sent["kind"], "reply");
+        assert_eq!(sent["payload"]["post_id"], "0xp");
+    }
+
+    #[tokio::test]
+    async fn webhook_sink_fails_on_error_answers() {
+        let pool = migrated_pool().await;
+        let (url, request) = listener(500).await;
+        register_webhook(&pool, &url).await;
+
+        assert!(local_sink().deliver(&pool, &notification(true)).await.is_err());
+        request.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn webhook_sink_refuses_private_addresses_by_default() {
+        let pool = migrated_pool().await;
+        register_webhook(&pool, "http://127.0.0.1:9/hook").await;
+
+        let sink = WebhookSink { client: WebhookClient::new(false) };
+        let error = sink.deliver(&pool, &notification(true)).await.unwrap_err();
+        assert!(error.to_string().contains("non-public address 127.0.0.1"), "{}", error);
+    }
+
+    #[tokio::test]
+    async fn webhook_sink_declines_without_a_webhook_or_when_muted() {
+        let pool = migrated_pool().await;
+        assert!(!local_sink().deliver(&pool, &notification(true)).await.unwrap());
+
+        register_webhook(&pool, "http://127.0.0.1:9/unused").await;
+        sqlx::query("INSERT INTO notification_preferences(address, kind, in_app, webhook) VALUES ('0xa', 'reply', TRUE, FALSE)")
+            .execute(&pool)
+            .await
+            .unwrap();
+        assert!(!local_sink().deliver(&pool, &notification(true)).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn dispatch_delivers_through_every_sink() {
+        let pool = migrated_pool().await;
+        let (url, request) = listener(200).await;
+        register_webhook(&pool, &url).await;
+        sqlx::query("INSERT INTO notifications(recipient, kind, source_ref, payload) VALUES ('0xa', 'reply', 'tx:0', '{}')")
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let dispatcher = NotificationDispatcher::with_sinks(pool.clone(), vec![Box::new(InAppSink), Box::new(local_sink())]);
+        assert_eq!(dispatcher.dispatch().await.unwrap(), 1);
+        request.await.unwrap();
+        let delivered: Vec<(String, String)> = sqlx::query_as("SELECT sink, status FROM notification_deliveries ORDER BY sink")
+            .fetch_all(&pool)
+            .await
+            .unwrap();
+        assert_eq!(delivered, vec![
+            ("in_app".to_string(), "delivered".to_string()),
+            ("webhook".to_string(), "delivered".to_string()),
+        ]);
+    }
+}
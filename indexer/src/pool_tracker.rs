@@ -0,0 +1,198 @@
+use anyhow::{anyhow, Result};
+use serde_json::{json, Value};
+use sqlx::{Row, SqlitePool};
+use std::time::Duration;
+use tokio::time::sleep;
+use tracing::info;
+
+use suiter_core::ledger::{self, LedgerTxn};
+
+/// How often the on-chain AttentionPool is polled
+const POLL_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Attention pool tracker
+/// Snapshots the on-chain AttentionPool and posts any funding it received to
+/// the ledger. Rewards only ever leave the pool, so whatever the balance grew
+/// by beyond what `total_rewarded` paid out must have been funded.
+pub struct PoolTracker {
+    rpc_url: String,
+    pool_id: Option<String>,
+    pool: SqlitePool,
+    http: reqwest::Client,
+}
+
+impl PoolTracker {
+    pub fn new(rpc_url: String, pool_id: Option<String>, pool: SqlitePool) -> Self {
+        PoolTracker {
+            rpc_url,
+            pool_id,
+            pool,
+            http: reqwest::Client::new(),
+        }
+    }
+
+    pub async fn run(&self) -> Result<()> {
+        info!("Starting attention pool tracker loop...");
+
+        if self.pool_id.is_none() {
+            info!("ATTENTION_POOL_ID not set - pool funding will not be tracked");
+        }
+
+        loop {
+            if let Some(pool_id) = &self.pool_id {
+                if let Err(e) = self.snapshot(pool_id).await {
+                    tracing::error!("Error tracking attention pool: {}", e);
+                }
+            }
+
+            sleep(POLL_INTERVAL).await;
+        }
+    }
+
+    async fn snapshot(&self, pool_id: &str) -> Result<()> {
+        let fields = self.fetch_pool(pool_id).await?;
+        let balance = field_u64(&fields, "balance")? as i64;
+        let total_rewarded = field_u64(&fields, "total_rewarded")? as i64;
+        let session_count = field_u64(&fields, "session_count")? as i64;
+
+        let funded = record(&self.pool, pool_id, balance, total_rewarded, session_count).await?;
+        if funded > 0 {
+            info!("Attention pool funded with {} MIST (balance {})", funded, balance);
+        }
+        Ok(())
+    }
+
+    async fn fetch_pool(&self, pool_id: &str) -> Result<Value> {
+        let body = json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "sui_getObject",
+            "params": [pool_id, { "showContent": true }]
+        });
+
+        let resp: Value = self.http.post(&self.rpc_url).json(&body).send().await?.json().await?;
+        if let Some(err) = resp.get("error") {
+            return Err(anyhow!("sui_getObject failed: {}", err));
+        }
+
+        resp["result"]["data"]["content"]
+            .get("fields")
+            .cloned()
+            .ok_or_else(|| anyhow!("attention pool {} has no content", pool_id))
+    }
+}
+
+/// Fund the pool by hand, e.g. for deployments whose pool isn't on chain
+pub async fn fund(pool: &SqlitePool, amount: i64, note: &str) -> Result<()> {
+    if amount <= 0 {
+        return Err(anyhow!("amount must be positive"));
+    }
+
+    let mut tx = pool.begin().await?;
+    LedgerTxn::new()
+        .transfer(ledger::POOL_FUNDING, ledger::ATTENTION_POOL, amount)
+        .post(&mut tx, &format!("fund:attention:manual:{}:{}", chrono::Utc::now().timestamp_millis(), note), "pool_funding", None)
+        .await?;
+    tx.commit().await?;
+
+    info!("Funded attention pool with {} MIST", amount);
+    Ok(())
+}
+
+/// Record a snapshot of the pool and post the funding it implies, returning
+/// how much that was. Unchanged pools record nothing.
+async fn record(pool: &SqlitePool, pool_id: &str, balance: i64, total_rewarded: i64, session_count: i64) -> Result<i64> {
+    let mut tx = pool.begin().await?;
+
+    let previous = sqlx::query("SELECT balance, total_rewarded FROM attention_pool_snapshots WHERE pool_id = ? ORDER BY id DESC LIMIT 1")
+        .bind(pool_id)
+        .fetch_optional(&mut *tx)
+        .await?;
+    let previous = previous.map(|r| (r.get::<i64, _>("balance"), r.get::<i64, _>("total_rewarded")));
+    // Only record snapshots when the pool moved
+    if previous == Some((balance, total_rewarded)) {
+        return Ok(0);
+    }
+    let (prev_balance, prev_rewarded) = previous.unwrap_or((0, 0));
+
+    let funded = ((balance - prev_balance) + (total_rewarded - prev_rewarded)).max(0);
+    let id: i64 = sqlx::query_scalar(
+        "INSERT INTO attention_pool_snapshots(pool_id, balance, total_rewarded, session_count, funded, observed_at) VALUES (?, ?, ?, ?, ?, CURRENT_TIMESTAMP) RETURNING id"
+    )
+    .bind(pool_id)
+    .bind(balance)
+    .bind(total_rewarded)
+    .bind(session_count)
+    .bind(funded)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    LedgerTxn::new()
+        .transfer(ledger::POOL_FUNDING, ledger::ATTENTION_POOL, funded)
+        .post(&mut tx, &format!("fund:attention:{}", id), "pool_funding", None)
+        .await?;
+
+    tx.commit().await?;
+    Ok(funded)
+}
+
+/// Move u64 fields arrive as strings
+fn field_u64(fields: &Value, key: &str) -> Result<u64> {
+    match fields.get(key) {
+        Some(Value::String(s)) => Ok(s.parse()?),
+        Some(Value::Number(n)) => n.as_u64().ok_or_else(|| anyhow!("pool field `{}` is not a u64", key)),
+        _ => Err(anyhow!("pool field `{}` missing", key)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use suiter_core::testing::migrated_pool;
+
+    async fn pool_balance(pool: &SqlitePool) -> i64 {
+        sqlx::query_scalar("SELECT COALESCE(SUM(amount), 0) FROM ledger_entries WHERE account = ?")
+            .bind(ledger::ATTENTION_POOL)
+            .fetch_one(pool)
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn snapshots_post_what_the_pool_grew_by_beyond_its_rewards() {
+        let pool = migrated_pool().await;
+
+        // The first snapshot funds everything the pool has held
+        assert_eq!(record(&pool, "0xpool", 1_000, 200, 3).await.unwrap(), 1_200);
+        // Rewards paid out on chain move balance and total_rewarded in step
+        assert_eq!(record(&pool, "0xpool", 700, 500, 5).await.unwrap(), 0);
+        // Funding landing alongside rewards is still picked up
+        assert_eq!(record(&pool, "0xpool", 1_100, 600, 6).await.unwrap(), 500);
+        // A balance that shrank by more than was rewarded funds nothing
+        assert_eq!(record(&pool, "0xpool", 900, 650, 7).await.unwrap(), 0);
+
+        let funded: Vec<i64> = sqlx::query_scalar("SELECT funded FROM attention_pool_snapshots ORDER BY id")
+            .fetch_all(&pool)
+            .await
+            .unwrap();
+        assert_eq!(funded, vec![1_200, 0, 500, 0]);
+        assert_eq!(pool_balance(&pool).await, 1_700);
+    }
+
+    #[tokio::test]
+    async fn unchanged_pools_record_nothing() {
+        let pool = migrated_pool().await;
+
+        record(&pool, "0xpool", 1_000, 0, 1).await.unwrap();
+        assert_eq!(record(&pool, "0xpool", 1_000, 0, 1).await.unwrap(), 0);
+        // Pools are tracked independently
+        assert_eq!(record(&pool, "0xother", 1_000, 0, 1).await.unwrap(), 1_000);
+
+        let snapshots: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM attention_pool_snapshots")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(snapshots, 2);
+        assert_eq!(pool_balance(&pool).await, 2_000);
+    }
+}
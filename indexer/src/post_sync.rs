@@ -0,0 +1,290 @@
+use anyhow::{anyhow, Result};
+use sqlx::{Row, Sqlite, SqlitePool, Transaction};
+use std::time::Duration;
+use tokio::time::sleep;
+use tracing::info;
+
+use suiter_core::search::{self, Doc};
+
+/// How often provisional posts are expired and orphans marked
+const EXPIRY_INTERVAL: Duration = Duration::from_secs(300);
+/// Provisional posts whose transaction hasn't landed after this are orphaned
+const PROVISIONAL_TTL: &str = "-1 hour";
+
+/// Tables whose rows follow a post when it is re-keyed
+const POST_CHILD_TABLES: &[&str] = &[
+    "attention_sessions",
+    "attention_rewards",
+    "truth_claims",
+    "ranking_dirty",
+    "feed_ranking_history",
+    "post_tags",
+];
+
+/// Provisional post sync
+/// Posts created through the API are provisional. `link` re-keys one to its
+/// on-chain object id when the PostCreated event arrives; this worker
+/// deletes the ones whose transaction failed and marks the ones that never
+/// landed as orphaned.
+pub struct PostSync {
+    tracking_chain: bool,
+    pool: SqlitePool,
+}
+
+impl PostSync {
+    pub fn new(tracking_chain: bool, pool: SqlitePool) -> Self {
+        PostSync { tracking_chain, pool }
+    }
+
+    pub async fn run(&self) -> Result<()> {
+        info!("Starting provisional post sync loop...");
+
+        // Without chain events nothing is ever linked, so nothing may expire
+        if !self.tracking_chain {
+            info!("PACKAGE_ID not set - provisional posts will not expire");
+        }
+
+        loop {
+            if self.tracking_chain {
+                match self.expire().await {
+                    Ok(0) => {}
+                    Ok(expired) => info!("Expired {} provisional posts whose transaction failed", expired),
+                    Err(e) => tracing::error!("Error expiring provisional posts: {}", e),
+                }
+                match self.mark_orphaned().await {
+                    Ok(0) => {}
+                    Ok(orphaned) => tracing::warn!("{} provisional posts never landed; see check-sync", orphaned),
+                    Err(e) => tracing::error!("Error marking orphaned posts: {}", e),
+                }
+            }
+
+            sleep(EXPIRY_INTERVAL).await;
+        }
+    }
+
+    /// Delete provisional posts whose submitted transaction failed. Posts
+    /// that already gathered sessions or claims are left for `check-sync` to
+    /// report rather than deleted with their activity.
+    pub async fn expire(&self) -> Result<u64> {
+        let ids: Vec<String> = sqlx::query_scalar(
+            r#"
+            SELECT p.id FROM posts p
+            WHERE p.provisional = TRUE
+              AND EXISTS (SELECT 1 FROM pending_transactions t
+                          WHERE t.sender = p.author AND t.client_nonce = p.client_nonce AND t.status = 'failed')
+              AND NOT EXISTS (SELECT 1 FROM attention_sessions s WHERE s.post_id = p.id)
+              AND NOT EXISTS (SELECT 1 FROM truth_claims c WHERE c.post_id = p.id)
+            "#
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut expired = 0;
+        for id in &ids {
+            let mut tx = self.pool.begin().await?;
+            for table in ["ranking_dirty", "feed_ranking_history", "feed_rankings", "post_tags"] {
+                sqlx::query(&format!("DELETE FROM {} WHERE post_id = ?", table))
+                    .bind(id)
+                    .execute(&mut *tx)
+                    .await?;
+            }
+            let author: Option<String> = sqlx::query_scalar("DELETE FROM posts WHERE id = ? AND provisional = TRUE RETURNING author")
+                .bind(id)
+                .fetch_optional(&mut *tx)
+                .await?;
+            if let Some(author) = &author {
+                sqlx::query("UPDATE profiles SET total_posts = MAX(total_posts - 1, 0), updated_at = CURRENT_TIMESTAMP WHERE address = ?")
+                    .bind(author)
+                    .execute(&mut *tx)
+                    .await?;
+                expired += 1;
+            }
+            tx.commit().await?;
+            if author.is_some() {
+                search::index(&self.pool, Doc::Post, id).await?;
+            }
+        }
+
+        Ok(expired)
+    }
+
+    /// Mark provisional posts past the TTL whose transaction hasn't failed
+    /// as orphaned. Nothing proves they won't land, so they are kept and
+    /// still link if their event arrives. Returns how many were marked.
+    pub async fn mark_orphaned(&self) -> Result<u64> {
+        let marked = sqlx::query("UPDATE posts SET orphaned_at = CURRENT_TIMESTAMP WHERE provisional = TRUE AND orphaned_at IS NULL AND created_at <= datetime('now', ?)")
+            .bind(PROVISIONAL_TTL)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(marked.rows_affected())
+    }
+}
+
+/// Link the provisional post a PostCreated event confirms, re-keying it and
+/// everything that refers to it to `post_id`. The post is matched by the
+/// nonce its transaction was submitted with, else by author and content
+/// hash. Runs in the caller's transaction, with the rest of the event.
+/// Returns the provisional id that was linked.
+pub async fn link(tx: &mut Transaction<'_, Sqlite>, post_id: &str, author: &str, content_hash: &str, digest: &str) -> Result<Option<String>> {
+    let exists: bool = sqlx::query_scalar("SELECT EXISTS (SELECT 1 FROM posts WHERE id = ?)")
+        .bind(post_id)
+        .fetch_one(&mut **tx)
+        .await?;
+    if exists {
+        return Ok(None);
+    }
+
+    let provisional: Option<String> = sqlx::query_scalar(
+        r#"
+        SELECT id FROM posts
+        WHERE provisional = TRUE AND author = ?1
+          AND (client_nonce = (SELECT client_nonce FROM pending_transactions WHERE digest = ?2) OR content_hash = ?3)
+        ORDER BY client_nonce IS (SELECT client_nonce FROM pending_transactions WHERE digest = ?2) DESC, created_at
+        LIMIT 1
+        "#
+    )
+    .bind(author)
+    .bind(digest)
+    .bind(content_hash)
+    .fetch_optional(&mut **tx)
+    .await?;
+    let Some(provisional) = provisional else {
+        return Ok(None);
+    };
+
+    // Copy, move children, then delete, so foreign keys hold throughout;
+    // the nonce moves last because it's unique per author
+    sqlx::query(
+        r#"
+        INSERT INTO posts(id, author, content_hash, attention_accumulated, level, created_at, updated_at, provisional, provisional_id, linked_at)
+        SELECT ?1, author, content_hash, attention_accumulated, level, created_at, CURRENT_TIMESTAMP, FALSE, id, CURRENT_TIMESTAMP
+        FROM posts WHERE id = ?2
+        "#
+    )
+    .bind(post_id)
+    .bind(&provisional)
+    .execute(&mut **tx)
+    .await?;
+
+    for table in POST_CHILD_TABLES {
+        sqlx::query(&format!("UPDATE {} SET post_id = ?1 WHERE post_id = ?2", table))
+            .bind(post_id)
+            .bind(&provisional)
+            .execute(&mut **tx)
+            .await?;
+    }
+    sqlx::query("UPDATE feed_rankings SET id = 'fr_' || ?1, post_id = ?1 WHERE post_id = ?2")
+        .bind(post_id)
+        .bind(&provisional)
+        .execute(&mut **tx)
+        .await?;
+    // reputation_events is append-only and keeps the provisional id; the
+    // post's provisional_id is how penalties recorded under it are found
+
+    let nonce: Option<String> = sqlx::query_scalar("DELETE FROM posts WHERE id = ? RETURNING client_nonce")
+        .bind(&provisional)
+        .fetch_one(&mut **tx)
+        .await?;
+    sqlx::query("UPDATE posts SET client_nonce = ? WHERE id = ?")
+        .bind(nonce)
+        .bind(post_id)
+        .execute(&mut **tx)
+        .await?;
+
+    Ok(Some(provisional))
+}
+
+/// `check-sync`: report where API-written posts and indexed chain state
+/// disagree
+pub async fn check(pool: &SqlitePool) -> Result<()> {
+    let mut divergent = 0usize;
+
+    // Provisional posts still within their TTL are expected, not divergent
+    let waiting: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM posts WHERE provisional = TRUE AND created_at > datetime('now', ?)")
+        .bind(PROVISIONAL_TTL)
+        .fetch_one(pool)
+        .await?;
+    info!("{} provisional posts awaiting their chain event", waiting);
+
+    let orphaned = sqlx::query(
+        r#"
+        SELECT p.id, p.author, p.created_at, p.orphaned_at,
+            (SELECT COUNT(*) FROM attention_sessions s WHERE s.post_id = p.id) as sessions,
+            (SELECT COUNT(*) FROM truth_claims c WHERE c.post_id = p.id) as claims
+        FROM posts p
+        WHERE p.provisional = TRUE AND p.created_at <= datetime('now', ?)
+        ORDER BY p.created_at
+        "#
+    )
+    .bind(PROVISIONAL_TTL)
+    .fetch_all(pool)
+    .await?;
+    for r in &orphaned {
+        info!(
+            "orphaned: provisional post {} by {} from {} never landed (marked {}, {} sessions, {} claims)",
+            r.get::<String, _>("id"),
+            r.get::<String, _>("author"),
+            r.get::<String, _>("created_at"),
+            r.get::<Option<String>, _>("orphaned_at").as_deref().unwrap_or("not yet"),
+            r.get::<i64, _>("sessions"),
+            r.get::<i64, _>("claims")
+        );
+    }
+    divergent += orphaned.len();
+
+    // The same content under an API id and a chain id
+    let duplicates = sqlx::query(
+        r#"
+        SELECT a.id as api_id, c.id as chain_id, a.author
+        FROM posts a
+        JOIN posts c ON c.author = a.author AND c.content_hash = a.content_hash AND c.id != a.id
+        WHERE c.id LIKE '0x%' AND a.id NOT LIKE '0x%'
+        "#
+    )
+    .fetch_all(pool)
+    .await?;
+    for r in &duplicates {
+        info!(
+            "duplicate: post {} by {} is also indexed as {}",
+            r.get::<String, _>("api_id"),
+            r.get::<String, _>("author"),
+            r.get::<String, _>("chain_id")
+        );
+    }
+    divergent += duplicates.len();
+
+    // Confirmed on chain but the indexed post is missing
+    let missing = sqlx::query(
+        r#"
+        SELECT t.digest, t.object_id FROM pending_transactions t
+        WHERE t.function = 'post::create_post' AND t.status = 'confirmed'
+          AND t.object_id IS NOT NULL
+          AND NOT EXISTS (SELECT 1 FROM posts p WHERE p.id = t.object_id)
+        "#
+    )
+    .fetch_all(pool)
+    .await?;
+    for r in &missing {
+        info!(
+            "missing: transaction {} created post {} but it isn't indexed",
+            r.get::<String, _>("digest"),
+            r.get::<String, _>("object_id")
+        );
+    }
+    divergent += missing.len();
+
+    // Written by the API before posts were provisional; can't be matched
+    let legacy: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM posts WHERE provisional = FALSE AND id NOT LIKE '0x%'")
+        .fetch_one(pool)
+        .await?;
+    if legacy > 0 {
+        info!("{} posts predate provisional tracking and have no chain id", legacy);
+    }
+
+    if divergent == 0 {
+        info!("API posts and chain state agree");
+        return Ok(());
+    }
+    Err(anyhow!("{} posts diverge from chain state", divergent))
+}
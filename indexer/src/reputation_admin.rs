@@ -0,0 +1,59 @@
+use anyhow::{anyhow, Result};
+use sqlx::SqlitePool;
+use tracing::info;
+
+use suiter_core::{
+    reputation::{Reason, ReputationLedger},
+};
+use suiter_ranker::FeedRanker;
+
+/// `check-reputation [--rebuild]`: compare every profile's reputation with
+/// its event ledger, optionally rewriting the materialized column
+pub async fn check(pool: &SqlitePool, rebuild: bool) -> Result<()> {
+    let inconsistent = ReputationLedger::check(pool).await?;
+
+    for i in &inconsistent {
+        info!(
+            "{}: profile has {}, ledger sums to {}, last event recorded {}",
+            i.address,
+            i.materialized,
+            i.ledger,
+            i.last_balance.map(|b| b.to_string()).unwrap_or_else(|| "-".to_string())
+        );
+    }
+
+    if inconsistent.is_empty() {
+        info!("Reputation ledger is consistent");
+        return Ok(());
+    }
+
+    if !rebuild {
+        return Err(anyhow!("{} profiles disagree with the reputation ledger", inconsistent.len()));
+    }
+
+    let fixed = ReputationLedger::rebuild(pool).await?;
+    for i in &inconsistent {
+        FeedRanker::mark_author_dirty(pool, &i.address, "reputation").await?;
+    }
+    info!("Rebuilt reputation of {} profiles from the ledger", fixed);
+    Ok(())
+}
+
+/// `adjust-reputation <address> <delta> <note>`: manual correction, recorded
+/// as an admin adjustment with the note as its source reference
+pub async fn adjust(pool: &SqlitePool, address: &str, delta: i64, note: &str) -> Result<()> {
+    let source_ref = format!("admin:{}:{}", chrono::Utc::now().timestamp_millis(), note);
+
+    let mut tx = pool.begin().await?;
+    let balance = ReputationLedger::apply(&mut tx, address, delta, Reason::AdminAdjustment, &source_ref).await?;
+    tx.commit().await?;
+
+    match balance {
+        Some(balance) => {
+            FeedRanker::mark_author_dirty(pool, address, "reputation").await?;
+            info!("Reputation of {} is now {}", address, balance);
+        }
+        None => info!("Reputation of {} unchanged", address),
+    }
+    Ok(())
+}
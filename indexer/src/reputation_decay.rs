@@ -0,0 +1,334 @@
+use anyhow::Result;
+use sqlx::{SqlitePool, Row};
+use std::time::Duration;
+use tokio::time::sleep;
+use tracing::info;
+
+use suiter_core::{
+    reputation::{abandonment_loss, Reason, ReputationLedger, ABANDONMENT_DAYS, REPUTATION_FLOOR},
+};
+use suiter_ranker::FeedRanker;
+
+// ============ CONSTANTS (mirrors suiter::profile) ============
+
+/// 95% retention per inactive day, in basis points. `profile::apply_decay`
+/// passes 95 to a 10000-scaled pow, which would keep 0.95% after one day;
+/// the documented intent is rep₀ × 0.95^days.
+const DECAY_RATE_BP: i64 = 9_500;
+const BP: i64 = 10_000;
+
+/// Decay is applied per whole inactive day, so checking hourly is enough to
+/// apply each day promptly; checks that find nothing due are no-ops
+const CHECK_INTERVAL: Duration = Duration::from_secs(3600);
+
+/// Profiles above the floor with at least one whole inactive day not yet
+/// decayed. Inactivity runs from the latest post, attention session or join,
+/// and `last_decay_at` marks how far it has already been applied.
+const DUE_QUERY: &str = r#"
+WITH activity AS (
+    SELECT p.address, p.reputation, p.last_decay_at,
+        MAX(
+            p.joined_at,
+            COALESCE((SELECT MAX(created_at) FROM posts WHERE author = p.address), ''),
+            COALESCE((SELECT MAX(created_at) FROM attention_sessions WHERE reader = p.address), '')
+        ) as last_active
+    FROM profiles p
+    WHERE p.reputation > ?
+),
+anchored AS (
+    SELECT address, reputation, last_decay_at, MAX(last_active, COALESCE(last_decay_at, '')) as anchor
+    FROM activity
+)
+SELECT address, reputation, last_decay_at, anchor,
+    CAST(julianday(CURRENT_TIMESTAMP) - julianday(anchor) AS INTEGER) as days
+FROM anchored
+WHERE julianday(CURRENT_TIMESTAMP) - julianday(anchor) >= 1
+"#;
+
+/// Posts that reached ABANDONMENT_DAYS without a valid attention session and
+/// whose author hasn't been penalized for them yet. Only posts that crossed
+/// the line within the last ABANDONMENT_DAYS are considered, so the backlog
+/// from before the ledger existed isn't charged all at once. A penalty
+/// charged while the post was provisional is recorded under its API id.
+const ABANDONED_QUERY: &str = r#"
+SELECT p.id, p.author
+FROM posts p
+WHERE p.created_at <= datetime(CURRENT_TIMESTAMP, '-' || ?1 || ' days')
+  AND p.created_at > datetime(CURRENT_TIMESTAMP, '-' || (?1 * 2) || ' days')
+  AND NOT EXISTS (SELECT 1 FROM attention_sessions s WHERE s.post_id = p.id AND s.validity = 'valid')
+  AND NOT EXISTS (
+    SELECT 1 FROM reputation_events e
+    WHERE e.address = p.author AND e.reason = ?2
+      AND (e.source_ref = 'post:' || p.id OR e.source_ref = 'post:' || p.provisional_id)
+  )
+"#;
+
+/// Reputation decay scheduler
+/// rep(t) = rep₀ × 0.95^days_inactive, floored at 50. Each whole inactive day
+/// is applied once: the profile's `last_decay_at` advances by the days
+/// applied, so repeated runs the same day find nothing due, and a missed day
+/// is caught up on the next run.
+pub struct ReputationDecay {
+    pool: SqlitePool,
+}
+
+impl ReputationDecay {
+    pub fn new(pool: SqlitePool) -> Self {
+        ReputationDecay { pool }
+    }
+
+    pub async fn run(&self) -> Result<()> {
+        info!("Starting reputation decay loop...");
+
+        loop {
+            if let Err(e) = self.apply().await {
+                tracing::error!("Error applying reputation decay: {}", e);
+            }
+            if let Err(e) = self.penalize_abandoned().await {
+                tracing::error!("Error penalizing abandoned posts: {}", e);
+            }
+
+            sleep(CHECK_INTERVAL).await;
+        }
+    }
+
+    /// Decay every profile with inactive days due; returns how many changed
+    pub async fn apply(&self) -> Result<usize> {
+        let due = sqlx::query(DUE_QUERY)
+            .bind(REPUTATION_FLOOR)
+            .fetch_all(&self.pool)
+            .await?;
+
+        let mut decayed = 0;
+        for row in &due {
+            let address = row.get::<String, _>("address");
+            let days = row.get::<i64, _>("days");
+            if self
+                .decay_profile(&address, row.get("reputation"), row.get("last_decay_at"), &row.get::<String, _>("anchor"), days)
+                .await?
+            {
+                FeedRanker::mark_author_dirty(&self.pool, &address, "decay").await?;
+                decayed += 1;
+            }
+        }
+
+        if decayed > 0 {
+            info!("Decayed reputation of {} inactive profiles", decayed);
+        }
+        Ok(decayed)
+    }
+
+    /// Charge authors for posts nobody gave valid attention; returns how
+    /// many posts were penalized
+    pub async fn penalize_abandoned(&self) -> Result<usize> {
+        let abandoned = sqlx::query(ABANDONED_QUERY)
+            .bind(ABANDONMENT_DAYS)
+            .bind(Reason::AbandonmentLoss.as_str())
+            .fetch_all(&self.pool)
+            .await?;
+
+        let loss = abandonment_loss(ABANDONMENT_DAYS);
+        let mut penalized = 0;
+        for row in &abandoned {
+            let author = row.get::<String, _>("author");
+            let source_ref = format!("post:{}", row.get::<String, _>("id"));
+
+            let mut tx = self.pool.begin().await?;
+            let changed = ReputationLedger::apply(&mut tx, &author, -loss, Reason::AbandonmentLoss, &source_ref).await?;
+            tx.commit().await?;
+
+            if changed.is_some() {
+                FeedRanker::mark_author_dirty(&self.pool, &author, "abandonment").await?;
+                penalized += 1;
+            }
+        }
+
+        if penalized > 0 {
+            info!("Penalized {} abandoned posts", penalized);
+        }
+        Ok(penalized)
+    }
+
+    async fn decay_profile(
+        &self,
+        address: &str,
+        reputation: i64,
+        last_decay_at: Option<String>,
+        anchor: &str,
+        days: i64,
+    ) -> Result<bool> {
+        let new_rep = decayed(reputation, days);
+        let mut tx = self.pool.begin().await?;
+
+        // Guarded on the values read so a concurrent run or reputation change
+        // makes this a no-op instead of applying decay twice
+        let through: Option<String> = sqlx::query_scalar(
+            r#"
+            UPDATE profiles
+            SET last_decay_at = datetime(?1, '+' || ?2 || ' days')
+            WHERE address = ?3 AND reputation = ?4 AND last_decay_at IS ?5
+            RETURNING last_decay_at
+            "#
+        )
+        .bind(anchor)
+        .bind(days)
+        .bind(address)
+        .bind(reputation)
+        .bind(&last_decay_at)
+        .fetch_optional(&mut *tx)
+        .await?;
+        let Some(through) = through else {
+            return Ok(false);
+        };
+
+        let source_ref = format!("decay:{}:{}d", through, days);
+        ReputationLedger::apply(&mut tx, address, new_rep - reputation, Reason::Decay, &source_ref).await?;
+
+        tx.commit().await?;
+        Ok(true)
+    }
+}
+
+/// Reputation after `days` of decay, never below the floor
+fn decayed(reputation: i64, days: i64) -> i64 {
+    (reputation * pow_bp(DECAY_RATE_BP, days) / BP).max(REPUTATION_FLOOR)
+}
+
+/// base^exponent in basis points, by squaring as in `profile::pow_u64`
+fn pow_bp(base_bp: i64, exponent: i64) -> i64 {
+    let mut result = BP;
+    let mut base = base_bp;
+    let mut exp = exponent;
+
+    while exp > 0 {
+        if exp % 2 == 1 {
+            result = result * base / BP;
+        }
+        base = base * base / BP;
+        exp /= 2;
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use suiter_core::testing::migrated_pool;
+
+
+    async fn profile(pool: &SqlitePool, address: &str, reputation: i64, joined: &str) {
+        sqlx::query("INSERT INTO profiles(address, reputation, total_posts, total_attention_earned, joined_at, updated_at) VALUES (?, ?, 0, 0, datetime('now', ?), CURRENT_TIMESTAMP)")
+            .bind(address)
+            .bind(reputation)
+            .bind(joined)
+            .execute(pool)
+            .await
+            .unwrap();
+    }
+
+    async fn reputation(pool: &SqlitePool, address: &str) -> i64 {
+        sqlx::query_scalar("SELECT reputation FROM profiles WHERE address = ?")
+            .bind(address)
+            .fetch_one(pool)
+            .await
+            .unwrap()
+    }
+
+    #[test]
+    fn pow_bp_matches_repeated_multiplication() {
+        assert_eq!(pow_bp(DECAY_RATE_BP, 0), BP);
+        assert_eq!(pow_bp(DECAY_RATE_BP, 1), DECAY_RATE_BP);
+        assert_eq!(pow_bp(DECAY_RATE_BP, 2), 9_025);
+        // 0.95^10 = 0.5987; squaring truncates each step
+        assert!((5_980..=5_987).contains(&pow_bp(DECAY_RATE_BP, 10)));
+    }
+
+    #[test]
+    fn decayed_keeps_reputation_without_inactive_days() {
+        assert_eq!(decayed(1_000, 0), 1_000);
+    }
+
+    #[test]
+    fn decayed_applies_five_percent_per_day() {
+        assert_eq!(decayed(1_000, 1), 950);
+        assert_eq!(decayed(1_000, 2), 902);
+    }
+
+    #[test]
+    fn decayed_stops_at_the_floor() {
+        assert_eq!(decayed(1_000, 365), REPUTATION_FLOOR);
+        assert_eq!(decayed(REPUTATION_FLOOR + 1, 1), REPUTATION_FLOOR);
+        assert_eq!(decayed(REPUTATION_FLOOR, 30), REPUTATION_FLOOR);
+    }
+
+    #[tokio::test]
+    async fn apply_decays_each_inactive_day_once() {
+        let pool = migrated_pool().await;
+        profile(&pool, "0xa", 1_000, "-74 hours").await;
+        let decay = ReputationDecay::new(pool.clone());
+
+        assert_eq!(decay.apply().await.unwrap(), 1);
+        assert_eq!(reputation(&pool, "0xa").await, decayed(1_000, 3));
+
+        // last_decay_at now covers the three whole days; the partial one isn't due
+        assert_eq!(decay.apply().await.unwrap(), 0);
+        assert_eq!(reputation(&pool, "0xa").await, decayed(1_000, 3));
+        let events: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM reputation_events WHERE reason = ?")
+            .bind(Reason::Decay.as_str())
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(events, 1);
+    }
+
+    #[tokio::test]
+    async fn apply_skips_recently_active_and_floored_profiles() {
+        let pool = migrated_pool().await;
+        profile(&pool, "0xa", 1_000, "-10 days").await;
+        profile(&pool, "0xb", REPUTATION_FLOOR, "-10 days").await;
+        sqlx::query("INSERT INTO posts(id, author, content_hash, created_at) VALUES ('p1', '0xa', 'h', datetime('now', '-2 hours'))")
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        assert_eq!(ReputationDecay::new(pool.clone()).apply().await.unwrap(), 0);
+        assert_eq!(reputation(&pool, "0xa").await, 1_000);
+        assert_eq!(reputation(&pool, "0xb").await, REPUTATION_FLOOR);
+    }
+
+    #[tokio::test]
+    async fn decay_profile_is_a_no_op_on_stale_reads() {
+        let pool = migrated_pool().await;
+        profile(&pool, "0xa", 1_000, "-2 days").await;
+        let decay = ReputationDecay::new(pool.clone());
+        let anchor: String = sqlx::query_scalar("SELECT joined_at FROM profiles").fetch_one(&pool).await.unwrap();
+
+        assert!(decay.decay_profile("0xa", 1_000, None, &anchor, 2).await.unwrap());
+        // A second run that read the same values before the first committed
+        assert!(!decay.decay_profile("0xa", 1_000, None, &anchor, 2).await.unwrap());
+        assert_eq!(reputation(&pool, "0xa").await, decayed(1_000, 2));
+    }
+
+    #[tokio::test]
+    async fn penalize_abandoned_matches_penalties_under_the_provisional_id() {
+        let pool = migrated_pool().await;
+        profile(&pool, "0xa", 1_000, "-30 days").await;
+        sqlx::query("INSERT INTO posts(id, author, content_hash, provisional, created_at) VALUES ('api_1', '0xa', 'h', TRUE, datetime('now', ?))")
+            .bind(format!("-{} hours", ABANDONMENT_DAYS * 24 + 1))
+            .execute(&pool)
+            .await
+            .unwrap();
+        let decay = ReputationDecay::new(pool.clone());
+
+        assert_eq!(decay.penalize_abandoned().await.unwrap(), 1);
+        let charged = reputation(&pool, "0xa").await;
+
+        // The chain event re-keys the post; the penalty keeps its API id
+        let mut tx = pool.begin().await.unwrap();
+        crate::post_sync::link(&mut tx, "0xpost", "0xa", "h", "0xdigest").await.unwrap();
+        tx.commit().await.unwrap();
+        assert_eq!(decay.penalize_abandoned().await.unwrap(), 0);
+        assert_eq!(reputation(&pool, "0xa").await, charged);
+    }
+}
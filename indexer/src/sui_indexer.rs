@@ -1,24 +1,84 @@
-use anyhow::Result;
-use sqlx::SqlitePool;
+use anyhow::{anyhow, Result};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use sqlx::{Executor, Row, Sqlite, SqlitePool, Transaction};
 use tracing::info;
 use std::time::Duration;
 use tokio::time::sleep;
 
+use crate::post_sync;
+use suiter_core::{
+    address::SuiAddress,
+    notifications::{self, Kind},
+    reputation::{Reason, ReputationLedger},
+    search::{self, Doc},
+    stream, tags,
+    webhooks::{self, Event as WebhookEvent},
+};
+use suiter_ranker::FeedRanker;
+
+/// Move modules whose events are indexed
+pub const INDEXED_MODULES: &[&str] = &["post", "truth_claim", "profile", "creator_lifeline"];
+/// Events fetched per `suix_queryEvents` page
+const EVENT_PAGE_SIZE: u64 = 50;
+/// Attempts at handling an event before it is dead-lettered and skipped
+const MAX_EVENT_ATTEMPTS: i64 = 8;
+/// Retry delay after the first failure; doubles per attempt up to the max
+const EVENT_RETRY_BASE_SECS: i64 = 5;
+const EVENT_RETRY_MAX_SECS: i64 = 600;
+
+/// Event cursor as returned by the Sui RPC
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct EventId {
+    tx_digest: String,
+    event_seq: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SuiEvent {
+    id: EventId,
+    #[serde(rename = "type")]
+    event_type: String,
+    parsed_json: Value,
+    timestamp_ms: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct EventPage {
+    data: Vec<SuiEvent>,
+    next_cursor: Option<EventId>,
+    has_next_page: bool,
+}
+
 /// Sui blockchain indexer
 /// Listens to post creation, attention sessions, and reputation changes
 pub struct SuiIndexer {
     rpc_url: String,
+    package_id: Option<String>,
     pool: SqlitePool,
+    http: reqwest::Client,
 }
 
 impl SuiIndexer {
-    pub fn new(rpc_url: String, pool: SqlitePool) -> Self {
-        SuiIndexer { rpc_url, pool }
+    pub fn new(rpc_url: String, package_id: Option<String>, pool: SqlitePool) -> Self {
+        SuiIndexer {
+            rpc_url,
+            package_id,
+            pool,
+            http: reqwest::Client::new(),
+        }
     }
 
     pub async fn run(&self) -> Result<()> {
         info!("Starting Sui indexer loop...");
 
+        if self.package_id.is_none() {
+            info!("PACKAGE_ID not set - chain events will not be indexed");
+        }
+
         loop {
             // Poll blockchain for events every 2 seconds
             if let Err(e) = self.index_events().await {
@@ -30,13 +90,726 @@ impl SuiIndexer {
     }
 
     async fn index_events(&self) -> Result<()> {
-        // TODO: Query Sui RPC for events
-        // 1. sui_getEvents (post creation, attention, reputation changes)
-        // 2. Parse events
-        // 3. Store in PostgreSQL
-        // 4. Emit rankings update trigger
+        let Some(package_id) = &self.package_id else {
+            return Ok(());
+        };
+
+        for module in INDEXED_MODULES {
+            self.index_module(package_id, module).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Drain all new events emitted by one module. The cursor only moves
+    /// past an event once it's handled or dead-lettered, so a failure holds
+    /// the module at that event until its retry is due.
+    async fn index_module(&self, package_id: &str, module: &str) -> Result<()> {
+        let mut cursor = self.load_cursor(module).await?;
+
+        loop {
+            let page = self.query_events(package_id, module, cursor.as_ref()).await?;
+
+            for event in &page.data {
+                if !self.process(module, event).await? {
+                    return Ok(());
+                }
+            }
+
+            if let Some(next) = page.next_cursor {
+                self.save_cursor(&self.pool, module, &next).await?;
+                cursor = Some(next);
+            }
+
+            if !page.has_next_page {
+                return Ok(());
+            }
+        }
+    }
+
+    /// Handle one event, tracking failures in `indexer_event_failures`.
+    /// Returns whether the cursor moved past it: false while it waits for a
+    /// retry, true once handled or given up on.
+    async fn process(&self, module: &str, event: &SuiEvent) -> Result<bool> {
+        let due: Option<bool> = sqlx::query_scalar(
+            "SELECT next_attempt_at <= CURRENT_TIMESTAMP FROM indexer_event_failures WHERE tx_digest = ? AND event_seq = ? AND status = 'retrying'"
+        )
+        .bind(&event.id.tx_digest)
+        .bind(&event.id.event_seq)
+        .fetch_optional(&self.pool)
+        .await?;
+        if due == Some(false) {
+            return Ok(false);
+        }
+
+        match self.apply(event, Some(module)).await {
+            Ok(()) => {
+                if due.is_some() {
+                    info!("Indexed {} ({}) after retrying", event.event_type, event.id.tx_digest);
+                }
+                Ok(true)
+            }
+            Err(e) => {
+                let skipped = self.record_failure(module, event, &e).await?;
+                if skipped {
+                    self.save_cursor(&self.pool, module, &event.id).await?;
+                }
+                Ok(skipped)
+            }
+        }
+    }
+
+    /// Apply one event in a single transaction, so a failure leaves nothing
+    /// behind for the retry to trip over. With a module, that module's
+    /// cursor moves past the event in the same transaction.
+    async fn apply(&self, event: &SuiEvent, module: Option<&str>) -> Result<()> {
+        let mut tx = self.pool.begin().await?;
+        // Writing first takes SQLite's write lock up front, so the
+        // transaction never has to upgrade from a read
+        sqlx::query("DELETE FROM indexer_event_failures WHERE tx_digest = ? AND event_seq = ?")
+            .bind(&event.id.tx_digest)
+            .bind(&event.id.event_seq)
+            .execute(&mut *tx)
+            .await?;
+        self.handle_event(&mut tx, event).await?;
+        self.confirm_submitted(&mut tx, event).await?;
+        if let Some(module) = module {
+            self.save_cursor(&mut *tx, module, &event.id).await?;
+        }
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// Count a failed attempt; returns true when the event is given up on
+    async fn record_failure(&self, module: &str, event: &SuiEvent, error: &anyhow::Error) -> Result<bool> {
+        let attempts: i64 = sqlx::query_scalar(
+            r#"
+            INSERT INTO indexer_event_failures (tx_digest, event_seq, module, event_type, payload, timestamp_ms, attempts, last_error)
+            VALUES (?, ?, ?, ?, ?, ?, 1, ?)
+            ON CONFLICT (tx_digest, event_seq) DO UPDATE SET
+                attempts = attempts + 1,
+                last_error = EXCLUDED.last_error,
+                updated_at = CURRENT_TIMESTAMP
+            RETURNING attempts
+            "#
+        )
+        .bind(&event.id.tx_digest)
+        .bind(&event.id.event_seq)
+        .bind(module)
+        .bind(&event.event_type)
+        .bind(event.parsed_json.to_string())
+        .bind(&event.timestamp_ms)
+        .bind(error.to_string())
+        .fetch_one(&self.pool)
+        .await?;
+
+        if attempts >= MAX_EVENT_ATTEMPTS {
+            sqlx::query("UPDATE indexer_event_failures SET status = 'dead' WHERE tx_digest = ? AND event_seq = ?")
+                .bind(&event.id.tx_digest)
+                .bind(&event.id.event_seq)
+                .execute(&self.pool)
+                .await?;
+            tracing::error!(
+                "Giving up on {} ({}) after {} attempts, skipping it: {}",
+                event.event_type, event.id.tx_digest, attempts, error
+            );
+            return Ok(true);
+        }
+
+        let delay = event_retry_delay(attempts);
+        sqlx::query("UPDATE indexer_event_failures SET next_attempt_at = datetime('now', ?) WHERE tx_digest = ? AND event_seq = ?")
+            .bind(format!("+{} seconds", delay))
+            .bind(&event.id.tx_digest)
+            .bind(&event.id.event_seq)
+            .execute(&self.pool)
+            .await?;
+        tracing::error!(
+            "Failed to handle {} ({}), attempt {}, retrying in {}s: {}",
+            event.event_type, event.id.tx_digest, attempts, delay, error
+        );
+        Ok(false)
+    }
+
+    /// `replay-events`: handle dead-lettered events again, oldest first.
+    /// Returns (replayed, still failing).
+    pub async fn replay_dead(&self) -> Result<(usize, usize)> {
+        let rows = sqlx::query(
+            "SELECT tx_digest, event_seq, event_type, payload, timestamp_ms FROM indexer_event_failures WHERE status = 'dead' ORDER BY first_failed_at, tx_digest, event_seq"
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        let (mut replayed, mut failed) = (0, 0);
+        for r in rows {
+            let event = SuiEvent {
+                id: EventId {
+                    tx_digest: r.get("tx_digest"),
+                    event_seq: r.get("event_seq"),
+                },
+                event_type: r.get("event_type"),
+                parsed_json: serde_json::from_str(&r.get::<String, _>("payload"))?,
+                timestamp_ms: r.get("timestamp_ms"),
+            };
+
+            match self.apply(&event, None).await {
+                Ok(()) => replayed += 1,
+                Err(e) => {
+                    sqlx::query("UPDATE indexer_event_failures SET attempts = attempts + 1, last_error = ?, updated_at = CURRENT_TIMESTAMP WHERE tx_digest = ? AND event_seq = ?")
+                        .bind(e.to_string())
+                        .bind(&event.id.tx_digest)
+                        .bind(&event.id.event_seq)
+                        .execute(&self.pool)
+                        .await?;
+                    tracing::error!("Replay of {} ({}) failed: {}", event.event_type, event.id.tx_digest, e);
+                    failed += 1;
+                }
+            }
+        }
+
+        Ok((replayed, failed))
+    }
+
+    async fn query_events(&self, package_id: &str, module: &str, cursor: Option<&EventId>) -> Result<EventPage> {
+        let cursor = cursor.map(|c| json!({ "txDigest": c.tx_digest, "eventSeq": c.event_seq }));
+        let body = json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "suix_queryEvents",
+            "params": [
+                { "MoveEventModule": { "package": package_id, "module": module } },
+                cursor,
+                EVENT_PAGE_SIZE,
+                false
+            ]
+        });
+
+        let resp: Value = self.http.post(&self.rpc_url).json(&body).send().await?.json().await?;
+        if let Some(err) = resp.get("error") {
+            return Err(anyhow!("suix_queryEvents failed: {}", err));
+        }
+
+        Ok(serde_json::from_value(resp["result"].clone())?)
+    }
+
+    async fn load_cursor(&self, module: &str) -> Result<Option<EventId>> {
+        let row = sqlx::query("SELECT tx_digest, event_seq FROM indexer_cursors WHERE module = ?")
+            .bind(module)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        Ok(row.map(|r| EventId {
+            tx_digest: r.get("tx_digest"),
+            event_seq: r.get("event_seq"),
+        }))
+    }
+
+    async fn save_cursor<'e, E>(&self, executor: E, module: &str, cursor: &EventId) -> Result<()>
+    where
+        E: Executor<'e, Database = Sqlite>,
+    {
+        sqlx::query(
+            r#"
+            INSERT INTO indexer_cursors (module, tx_digest, event_seq, updated_at)
+            VALUES (?, ?, ?, CURRENT_TIMESTAMP)
+            ON CONFLICT (module) DO UPDATE SET
+                tx_digest = EXCLUDED.tx_digest,
+                event_seq = EXCLUDED.event_seq,
+                updated_at = EXCLUDED.updated_at
+            "#
+        )
+        .bind(module)
+        .bind(&cursor.tx_digest)
+        .bind(&cursor.event_seq)
+        .execute(executor)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Apply one event to the database and queue affected posts for re-ranking
+    async fn handle_event(&self, tx: &mut Transaction<'_, Sqlite>, event: &SuiEvent) -> Result<()> {
+        let name = event.event_type.rsplit("::").next().unwrap_or_default();
+        let data = &event.parsed_json;
+
+        match name {
+            "PostCreated" => {
+                let post_id = json_str(data, "post_id")?;
+                let author = json_address(data, "author")?;
+                let content_hash = json_bytes(data, "content_hash");
+                let created_at = event.timestamp_ms.as_deref().and_then(|t| t.parse::<i64>().ok()).unwrap_or(0) / 1000;
+
+                // Adopt the provisional row the API wrote for this post, if any
+                let linked = post_sync::link(tx, &post_id, author.as_str(), &content_hash, &event.id.tx_digest).await?;
+                if let Some(provisional) = &linked {
+                    info!("Linked provisional post {} to {}", provisional, post_id);
+                    // Clients following the provisional id learn the chain id
+                    stream::publish(&mut **tx, &stream::post_topic(provisional), "linked", &json!({ "post_id": post_id, "provisional_id": provisional })).await?;
+                }
+
+                sqlx::query("INSERT OR IGNORE INTO profiles(address, reputation, total_posts, total_attention_earned, joined_at, updated_at) VALUES (?, 50, 0, 0, CURRENT_TIMESTAMP, CURRENT_TIMESTAMP)")
+                    .bind(&author)
+                    .execute(&mut **tx)
+                    .await?;
+                let inserted = sqlx::query("INSERT OR IGNORE INTO posts(id, author, content_hash, attention_accumulated, level, created_at, updated_at) VALUES (?, ?, ?, 0, 1, datetime(?, 'unixepoch'), CURRENT_TIMESTAMP)")
+                    .bind(&post_id)
+                    .bind(&author)
+                    .bind(&content_hash)
+                    .bind(created_at)
+                    .execute(&mut **tx)
+                    .await?;
+                if inserted.rows_affected() > 0 {
+                    let created = json!({ "post_id": post_id, "author": author, "provisional": false });
+                    stream::publish(&mut **tx, stream::FEED, "post_created", &created).await?;
+                    stream::publish(&mut **tx, &stream::profile_topic(author.as_str()), "post_created", &created).await?;
+                }
+                // Integrators only hear about posts once they're on chain
+                if inserted.rows_affected() > 0 || linked.is_some() {
+                    search::index_in(tx, Doc::Post, &post_id).await?;
+                    if let Some(provisional) = &linked {
+                        search::index_in(tx, Doc::Post, provisional).await?;
+                    }
+                    tags::tag_post_in(tx, &post_id).await?;
+                    let created = json!({ "post_id": post_id, "author": author, "content_hash": content_hash, "provisional_id": linked, "tx_digest": event.id.tx_digest });
+                    webhooks::enqueue(&mut **tx, WebhookEvent::PostCreated, &post_id, &created).await?;
+                }
+                FeedRanker::mark_dirty(&mut **tx, &post_id, "created").await?;
+            }
+            "AttentionAdded" => {
+                let post_id = json_str(data, "post_id")?;
+                let total = json_u64(data, "new_total")? as i64;
+                sqlx::query("UPDATE posts SET attention_accumulated = ?, updated_at = CURRENT_TIMESTAMP WHERE id = ?")
+                    .bind(total)
+                    .bind(&post_id)
+                    .execute(&mut **tx)
+                    .await?;
+                stream::publish(&mut **tx, &stream::post_topic(&post_id), "attention", &json!({ "post_id": post_id, "attention_accumulated": total })).await?;
+                FeedRanker::mark_dirty(&mut **tx, &post_id, "attention").await?;
+            }
+            "PostLeveledUp" => {
+                let post_id = json_str(data, "post_id")?;
+                let level = json_u64(data, "new_level")? as i64;
+                sqlx::query("UPDATE posts SET level = ?, updated_at = CURRENT_TIMESTAMP WHERE id = ?")
+                    .bind(level)
+                    .bind(&post_id)
+                    .execute(&mut **tx)
+                    .await?;
+                stream::publish(&mut **tx, &stream::post_topic(&post_id), "level", &json!({ "post_id": post_id, "level": level })).await?;
+
+                if let Some(author) = self.post_author(tx, &post_id).await? {
+                    let payload = json!({ "post_id": post_id, "old_level": json_u64(data, "old_level")?, "new_level": level });
+                    notifications::notify_in(tx, &author, Kind::LevelUp, &event_key(event), &payload).await?;
+                    let leveled = json!({ "post_id": post_id, "author": author, "old_level": json_u64(data, "old_level")?, "new_level": level });
+                    webhooks::enqueue(&mut **tx, WebhookEvent::PostLeveledUp, &event_key(event), &leveled).await?;
+                }
+                FeedRanker::mark_dirty(&mut **tx, &post_id, "level").await?;
+            }
+            "ReplyAdded" => {
+                let post_id = json_str(data, "post_id")?;
+                let reply_id = json_str(data, "reply_id")?;
+                let author = json_address(data, "author")?;
+
+                stream::publish(&mut **tx, &stream::post_topic(&post_id), "reply", &json!({ "post_id": post_id, "reply_id": reply_id })).await?;
+
+                if let Some(parent_author) = self.post_author(tx, &post_id).await?.filter(|a| a != author.as_str()) {
+                    let payload = json!({ "post_id": post_id, "reply_id": reply_id, "author": author });
+                    notifications::notify_in(tx, &parent_author, Kind::Reply, &event_key(event), &payload).await?;
+                }
+            }
+            "ClaimCreated" => {
+                let claim_id = json_str(data, "claim_id")?;
+                let post_id = json_str(data, "post_id")?;
+                let claimer = json_address(data, "claimer")?;
+                let voting_end = json_u64(data, "voting_end")? as i64;
+
+                // Votes and resolution refer to the claim; the event carries
+                // no text, so chain claims are stored without it
+                sqlx::query("INSERT OR IGNORE INTO profiles(address, reputation, total_posts, total_attention_earned, joined_at, updated_at) VALUES (?, 50, 0, 0, CURRENT_TIMESTAMP, CURRENT_TIMESTAMP)")
+                    .bind(&claimer)
+                    .execute(&mut **tx)
+                    .await?;
+                sqlx::query("INSERT OR IGNORE INTO truth_claims(id, post_id, claimer, claim_text, voting_end, created_at) VALUES (?, ?, ?, '', datetime(?, 'unixepoch'), CURRENT_TIMESTAMP)")
+                    .bind(&claim_id)
+                    .bind(&post_id)
+                    .bind(&claimer)
+                    .bind(voting_end)
+                    .execute(&mut **tx)
+                    .await?;
+
+                FeedRanker::mark_dirty(&mut **tx, &post_id, "claim").await?;
+                stream::publish(&mut **tx, &stream::post_topic(&post_id), "claim_created", &json!({ "post_id": post_id, "claim_id": claim_id })).await?;
+
+                if let Some(author) = self.post_author(tx, &post_id).await?.filter(|a| a != claimer.as_str()) {
+                    let payload = json!({ "post_id": post_id, "claim_id": claim_id, "claimer": claimer });
+                    notifications::notify_in(tx, &author, Kind::ClaimCreated, &event_key(event), &payload).await?;
+                }
+            }
+            "VoteCasted" | "ClaimResolved" => {
+                let claim_id = json_str(data, "claim_id")?;
+                if name == "VoteCasted" {
+                    let voter = json_address(data, "voter")?;
+                    sqlx::query("INSERT OR IGNORE INTO profiles(address, reputation, total_posts, total_attention_earned, joined_at, updated_at) VALUES (?, 50, 0, 0, CURRENT_TIMESTAMP, CURRENT_TIMESTAMP)")
+                        .bind(&voter)
+                        .execute(&mut **tx)
+                        .await?;
+                    // Snapshot the voter's reputation so later changes don't reweight the
+                    // vote. Votes on claims never indexed, or by the claimer (which
+                    // older package versions allowed), are not counted.
+                    sqlx::query(
+                        r#"
+                        INSERT OR IGNORE INTO claim_votes(claim_id, voter, vote, reputation_snapshot, created_at)
+                        SELECT c.id, p.address, ?, p.reputation, CURRENT_TIMESTAMP
+                        FROM truth_claims c, profiles p
+                        WHERE c.id = ? AND p.address = ? AND c.claimer != p.address
+                        "#
+                    )
+                        .bind(data.get("vote").and_then(|v| v.as_bool()).unwrap_or(false))
+                        .bind(&claim_id)
+                        .bind(&voter)
+                        .execute(&mut **tx)
+                        .await?;
+                }
+                let post_id: Option<String> = sqlx::query_scalar("SELECT post_id FROM truth_claims WHERE id = ?")
+                    .bind(&claim_id)
+                    .fetch_optional(&mut **tx)
+                    .await?;
+                if let Some(post_id) = &post_id {
+                    FeedRanker::mark_dirty(&mut **tx, post_id, "claim").await?;
+                }
+                let kind = if name == "VoteCasted" { "vote" } else { "resolved" };
+                stream::publish(&mut **tx, &stream::claim_topic(&claim_id), kind, data).await?;
+
+                if name == "ClaimResolved" {
+                    let resolved = json!({
+                        "claim_id": claim_id,
+                        "post_id": post_id,
+                        "accepted": data.get("accepted").and_then(|v| v.as_bool()).unwrap_or(false),
+                    });
+                    webhooks::enqueue(&mut **tx, WebhookEvent::ClaimResolved, &claim_id, &resolved).await?;
+
+                    // Only claims filed through the API are known here
+                    let parties = sqlx::query("SELECT c.claimer, c.post_id, p.author FROM truth_claims c LEFT JOIN posts p ON p.id = c.post_id WHERE c.id = ?")
+                        .bind(&claim_id)
+                        .fetch_optional(&mut **tx)
+                        .await?;
+                    if let Some(r) = parties {
+                        let payload = json!({
+                            "claim_id": claim_id,
+                            "post_id": r.get::<String, _>("post_id"),
+                            "accepted": data.get("accepted").and_then(|v| v.as_bool()).unwrap_or(false),
+                        });
+                        let mut recipients = vec![r.get::<String, _>("claimer")];
+                        recipients.extend(r.get::<Option<String>, _>("author"));
+                        recipients.dedup();
+                        for recipient in recipients {
+                            notifications::notify_in(tx, &recipient, Kind::ClaimResolved, &event_key(event), &payload).await?;
+                        }
+                    }
+                }
+            }
+            "ReputationUpdated" | "ReputationDecayed" => {
+                let profile_id = json_str(data, "profile_id")?;
+                let owner: Option<String> = sqlx::query_scalar("SELECT owner FROM profile_objects WHERE object_id = ?")
+                    .bind(&profile_id)
+                    .fetch_optional(&mut **tx)
+                    .await?;
+                if let Some(owner) = owner {
+                    let reason = if name == "ReputationDecayed" { Reason::Decay } else { Reason::ChainSync };
+                    // The chain's balance is authoritative, so off-chain gains it
+                    // hasn't seen are overwritten rather than added to
+                    let changed = ReputationLedger::set(tx, &owner, json_u64(data, "new_rep")? as i64, reason, &event_key(event)).await?;
+                    if let Some(reputation) = changed {
+                        FeedRanker::mark_author_dirty(&mut **tx, &owner, "reputation").await?;
+                        stream::publish(&mut **tx, &stream::profile_topic(&owner), "reputation", &json!({ "address": owner, "reputation": reputation })).await?;
+                    }
+                }
+            }
+            "ProfileCreated" => {
+                let owner = json_address(data, "owner")?;
+                sqlx::query("INSERT OR IGNORE INTO profiles(address, reputation, total_posts, total_attention_earned, joined_at, updated_at) VALUES (?, 50, 0, 0, CURRENT_TIMESTAMP, CURRENT_TIMESTAMP)")
+                    .bind(&owner)
+                    .execute(&mut **tx)
+                    .await?;
+                sqlx::query("INSERT OR IGNORE INTO profile_objects(object_id, owner) VALUES (?, ?)")
+                    .bind(json_str(data, "profile_id")?)
+                    .bind(&owner)
+                    .execute(&mut **tx)
+                    .await?;
+            }
+            "LifelineCreated" => {
+                let recipient = json_address(data, "recipient")?;
+                sqlx::query("INSERT OR IGNORE INTO profiles(address, reputation, total_posts, total_attention_earned, joined_at, updated_at) VALUES (?, 50, 0, 0, CURRENT_TIMESTAMP, CURRENT_TIMESTAMP)")
+                    .bind(&recipient)
+                    .execute(&mut **tx)
+                    .await?;
+                // The event carries no object id, so lifelines are matched by
+                // recipient; one created through the API already covers it
+                sqlx::query("INSERT OR IGNORE INTO creator_lifelines(id, recipient, total_received, supporter_count, active, created_at) SELECT ?1, ?2, 0, 0, TRUE, datetime(?3, 'unixepoch') WHERE NOT EXISTS (SELECT 1 FROM creator_lifelines WHERE recipient = ?2 AND active = TRUE)")
+                    .bind(event_key(event))
+                    .bind(&recipient)
+                    .bind(json_u64(data, "created_at")? as i64)
+                    .execute(&mut **tx)
+                    .await?;
+            }
+            "SupportSent" => {
+                let supporter = json_address(data, "supporter")?;
+                let recipient = json_address(data, "recipient")?;
+                let amount = json_u64(data, "amount")? as i64;
+
+                let lifeline_id: Option<String> = sqlx::query_scalar("SELECT id FROM creator_lifelines WHERE recipient = ? ORDER BY active DESC, created_at DESC LIMIT 1")
+                    .bind(&recipient)
+                    .fetch_optional(&mut **tx)
+                    .await?;
+                let Some(lifeline_id) = lifeline_id else {
+                    tracing::warn!("SupportSent for {} without a known lifeline", recipient);
+                    return Ok(());
+                };
+
+                sqlx::query("INSERT OR IGNORE INTO profiles(address, reputation, total_posts, total_attention_earned, joined_at, updated_at) VALUES (?, 50, 0, 0, CURRENT_TIMESTAMP, CURRENT_TIMESTAMP)")
+                    .bind(&supporter)
+                    .execute(&mut **tx)
+                    .await?;
+                // The contract only enforces MIN_SUPPORT_AMOUNT; MAX_DAILY_SUPPORT
+                // is checked when supports are requested through the API. Funds
+                // that moved on chain are recorded whatever the amount.
+                let inserted = sqlx::query("INSERT OR IGNORE INTO lifeline_supports(id, lifeline_id, supporter, recipient, amount, support_day, source, created_at) VALUES (?1, ?2, ?3, ?4, ?5, date(?6, 'unixepoch'), 'chain', datetime(?6, 'unixepoch'))")
+                    .bind(event_key(event))
+                    .bind(&lifeline_id)
+                    .bind(&supporter)
+                    .bind(&recipient)
+                    .bind(amount)
+                    .bind(json_u64(data, "timestamp")? as i64)
+                    .execute(&mut **tx)
+                    .await?;
+                if inserted.rows_affected() > 0 {
+                    sqlx::query("UPDATE creator_lifelines SET total_received = total_received + ?1, supporter_count = (SELECT COUNT(DISTINCT supporter) FROM lifeline_supports WHERE lifeline_id = ?2), last_support_at = CURRENT_TIMESTAMP WHERE id = ?2")
+                        .bind(amount)
+                        .bind(&lifeline_id)
+                        .execute(&mut **tx)
+                        .await?;
+                    // Settle the oldest matching support requested through the API
+                    sqlx::query(
+                        r#"
+                        UPDATE pending_supports SET status = 'confirmed', support_id = ?1, confirmed_at = CURRENT_TIMESTAMP
+                        WHERE id = (
+                            SELECT id FROM pending_supports
+                            WHERE lifeline_id = ?2 AND supporter = ?3 AND amount = ?4 AND status = 'pending'
+                            ORDER BY created_at, id LIMIT 1
+                        )
+                        "#
+                    )
+                        .bind(event_key(event))
+                        .bind(&lifeline_id)
+                        .bind(&supporter)
+                        .bind(amount)
+                        .execute(&mut **tx)
+                        .await?;
+                    stream::publish(&mut **tx, &stream::profile_topic(recipient.as_str()), "support", &json!({ "lifeline_id": lifeline_id, "supporter": supporter, "amount": amount })).await?;
+                    let payload = json!({ "lifeline_id": lifeline_id, "supporter": supporter, "amount": amount });
+                    notifications::notify_in(tx, recipient.as_str(), Kind::SupportReceived, &event_key(event), &payload).await?;
+                    let sent = json!({ "support_id": event_key(event), "lifeline_id": lifeline_id, "supporter": supporter, "recipient": recipient, "amount": amount });
+                    webhooks::enqueue(&mut **tx, WebhookEvent::SupportSent, &event_key(event), &sent).await?;
+                }
+            }
+            _ => {}
+        }
+
+        Ok(())
+    }
+
+    async fn post_author(&self, tx: &mut Transaction<'_, Sqlite>, post_id: &str) -> Result<Option<String>> {
+        Ok(sqlx::query_scalar("SELECT author FROM posts WHERE id = ?")
+            .bind(post_id)
+            .fetch_optional(&mut **tx)
+            .await?)
+    }
+
+    /// Confirm the submitted transaction that emitted this event, recording
+    /// the object it created
+    async fn confirm_submitted(&self, tx: &mut Transaction<'_, Sqlite>, event: &SuiEvent) -> Result<()> {
+        let created = match event.event_type.rsplit("::").next().unwrap_or_default() {
+            "PostCreated" => Some("post_id"),
+            "ClaimCreated" => Some("claim_id"),
+            "ProfileCreated" => Some("profile_id"),
+            _ => None,
+        };
+        let object_id = created.and_then(|key| json_str(&event.parsed_json, key).ok());
+
+        sqlx::query(
+            r#"
+            UPDATE pending_transactions
+            SET object_id = COALESCE(?1, object_id), error = NULL,
+                resolved_at = CASE WHEN status = 'confirmed' THEN resolved_at ELSE CURRENT_TIMESTAMP END,
+                status = 'confirmed'
+            WHERE digest = ?2 AND (status != 'confirmed' OR (object_id IS NULL AND ?1 IS NOT NULL))
+            "#
+        )
+        .bind(object_id)
+        .bind(&event.id.tx_digest)
+        .execute(&mut **tx)
+        .await?;
+
+        // Sponsored transactions sent straight to a node still use up quota
+        sqlx::query("UPDATE sponsored_transactions SET submitted_at = CURRENT_TIMESTAMP WHERE digest = ? AND submitted_at IS NULL")
+            .bind(&event.id.tx_digest)
+            .execute(&mut **tx)
+            .await?;
 
-        // For now, this is a placeholder
         Ok(())
     }
 }
+
+/// Stable id for rows created from a single event
+fn event_key(event: &SuiEvent) -> String {
+    format!("{}:{}", event.id.tx_digest, event.id.event_seq)
+}
+
+/// Delay before retrying an event that has failed `attempts` times
+fn event_retry_delay(attempts: i64) -> i64 {
+    let doublings = (attempts - 1).clamp(0, 20) as u32;
+    (EVENT_RETRY_BASE_SECS << doublings).min(EVENT_RETRY_MAX_SECS)
+}
+
+fn json_str(data: &Value, key: &str) -> Result<String> {
+    data.get(key)
+        .and_then(|v| v.as_str())
+        .map(|s| s.to_string())
+        .ok_or_else(|| anyhow!("event field `{}` missing", key))
+}
+
+/// Address fields, normalized so they match the API's spelling
+fn json_address(data: &Value, key: &str) -> Result<SuiAddress> {
+    Ok(SuiAddress::parse(&json_str(data, key)?)?)
+}
+
+/// Sui serializes u64 fields as strings and smaller integers as numbers
+fn json_u64(data: &Value, key: &str) -> Result<u64> {
+    match data.get(key) {
+        Some(Value::String(s)) => Ok(s.parse()?),
+        Some(Value::Number(n)) => n.as_u64().ok_or_else(|| anyhow!("event field `{}` is not a u64", key)),
+        _ => Err(anyhow!("event field `{}` missing", key)),
+    }
+}
+
+/// `vector<u8>` fields arrive as arrays of numbers; decode as UTF-8 text
+fn json_bytes(data: &Value, key: &str) -> String {
+    let bytes: Vec<u8> = data
+        .get(key)
+        .and_then(|v| v.as_array())
+        .map(|a| a.iter().filter_map(|b| b.as_u64()).map(|b| b as u8).collect())
+        .unwrap_or_default();
+    String::from_utf8_lossy(&bytes).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use suiter_core::testing::migrated_pool;
+
+    const SUPPORTER: &str = "0x00000000000000000000000000000000000000000000000000000000000000a1";
+    const RECIPIENT: &str = "0x00000000000000000000000000000000000000000000000000000000000000b2";
+
+    fn support_sent() -> SuiEvent {
+        SuiEvent {
+            id: EventId { tx_digest: "0xdigest".to_string(), event_seq: "0".to_string() },
+            event_type: "0xpkg::creator_lifeline::SupportSent".to_string(),
+            parsed_json: json!({ "supporter": SUPPORTER, "recipient": RECIPIENT, "amount": "1000", "timestamp": "1700000000" }),
+            timestamp_ms: Some("1700000000000".to_string()),
+        }
+    }
+
+    async fn indexer_with_lifeline() -> SuiIndexer {
+        let pool = migrated_pool().await;
+        sqlx::query("INSERT INTO profiles(address) VALUES (?)")
+            .bind(RECIPIENT)
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query("INSERT INTO creator_lifelines(id, recipient) VALUES ('lifeline', ?)")
+            .bind(RECIPIENT)
+            .execute(&pool)
+            .await
+            .unwrap();
+        SuiIndexer::new("http://127.0.0.1:9".to_string(), None, pool)
+    }
+
+    #[tokio::test]
+    async fn failed_events_leave_nothing_behind_and_retry_in_full() {
+        let indexer = indexer_with_lifeline().await;
+        let pool = &indexer.pool;
+        // Fail the event after its support row is written
+        sqlx::query("CREATE TRIGGER fail_notify BEFORE INSERT ON notifications BEGIN SELECT RAISE(ABORT, 'notify failed'); END")
+            .execute(pool)
+            .await
+            .unwrap();
+
+        assert!(!indexer.process("creator_lifeline", &support_sent()).await.unwrap());
+        let supports: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM lifeline_supports").fetch_one(pool).await.unwrap();
+        let cursors: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM indexer_cursors").fetch_one(pool).await.unwrap();
+        assert_eq!((supports, cursors), (0, 0));
+
+        sqlx::query("DROP TRIGGER fail_notify").execute(pool).await.unwrap();
+        sqlx::query("UPDATE indexer_event_failures SET next_attempt_at = CURRENT_TIMESTAMP").execute(pool).await.unwrap();
+
+        assert!(indexer.process("creator_lifeline", &support_sent()).await.unwrap());
+        let total: i64 = sqlx::query_scalar("SELECT total_received FROM creator_lifelines").fetch_one(pool).await.unwrap();
+        let notified: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM notifications WHERE kind = 'support_received'").fetch_one(pool).await.unwrap();
+        let failures: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM indexer_event_failures").fetch_one(pool).await.unwrap();
+        let cursor: String = sqlx::query_scalar("SELECT tx_digest FROM indexer_cursors WHERE module = 'creator_lifeline'").fetch_one(pool).await.unwrap();
+        assert_eq!((total, notified, failures), (1000, 1, 0));
+        assert_eq!(cursor, "0xdigest");
+    }
+
+    #[tokio::test]
+    async fn handled_events_are_not_applied_twice() {
+        let indexer = indexer_with_lifeline().await;
+
+        indexer.apply(&support_sent(), None).await.unwrap();
+        indexer.apply(&support_sent(), None).await.unwrap();
+        let total: i64 = sqlx::query_scalar("SELECT total_received FROM creator_lifelines").fetch_one(&indexer.pool).await.unwrap();
+        assert_eq!(total, 1000);
+    }
+
+    #[tokio::test]
+    async fn chain_reputation_overwrites_off_chain_gains() {
+        let indexer = indexer_with_lifeline().await;
+        let pool = &indexer.pool;
+        sqlx::query("INSERT INTO profile_objects(object_id, owner) VALUES ('0xprofile', ?)")
+            .bind(RECIPIENT)
+            .execute(pool)
+            .await
+            .unwrap();
+        let mut tx = pool.begin().await.unwrap();
+        ReputationLedger::apply(&mut tx, RECIPIENT, 40, Reason::AttentionGain, "session:s1").await.unwrap();
+        tx.commit().await.unwrap();
+
+        let updated = SuiEvent {
+            id: EventId { tx_digest: "0xrep".to_string(), event_seq: "0".to_string() },
+            event_type: "0xpkg::profile::ReputationUpdated".to_string(),
+            parsed_json: json!({ "profile_id": "0xprofile", "old_rep": "50", "new_rep": "60", "reason": [] }),
+            timestamp_ms: Some("1700000000000".to_string()),
+        };
+        indexer.apply(&updated, None).await.unwrap();
+        indexer.apply(&updated, None).await.unwrap();
+
+        let reputation: i64 = sqlx::query_scalar("SELECT reputation FROM profiles WHERE address = ?")
+            .bind(RECIPIENT)
+            .fetch_one(pool)
+            .await
+            .unwrap();
+        let synced: Vec<i64> = sqlx::query_scalar("SELECT delta FROM reputation_events WHERE reason = 'chain_sync'")
+            .fetch_all(pool)
+            .await
+            .unwrap();
+        assert_eq!(reputation, 60);
+        assert_eq!(synced, [-30]);
+    }
+
+    #[test]
+    fn event_retry_delay_doubles_up_to_the_cap() {
+        assert_eq!(event_retry_delay(1), EVENT_RETRY_BASE_SECS);
+        assert_eq!(event_retry_delay(2), EVENT_RETRY_BASE_SECS * 2);
+        assert_eq!(event_retry_delay(4), EVENT_RETRY_BASE_SECS * 8);
+        assert_eq!(event_retry_delay(MAX_EVENT_ATTEMPTS), EVENT_RETRY_MAX_SECS);
+        assert_eq!(event_retry_delay(1_000), EVENT_RETRY_MAX_SECS);
+    }
+}
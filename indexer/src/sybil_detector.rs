@@ -0,0 +1,621 @@
+use anyhow::Result;
+use serde_json::json;
+use sqlx::{SqlitePool, Row};
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+use std::time::Duration;
+use tokio::time::sleep;
+use tracing::info;
+
+use suiter_ranker::FeedRanker;
+
+/// How often the graphs are rebuilt
+const SCAN_INTERVAL: Duration = Duration::from_secs(900);
+/// Activity considered by each scan
+const WINDOW_DAYS: i64 = 7;
+
+/// Valid sessions needed in each direction for a mutual-attention edge
+const MIN_MUTUAL_SESSIONS: i64 = 3;
+/// Smallest group treated as a ring
+const MIN_RING_SIZE: usize = 3;
+
+/// Shared claims needed before two voters are compared
+const MIN_COVOTES: usize = 3;
+/// Share of shared claims voted identically
+const MIN_AGREEMENT: f64 = 0.9;
+/// Overlap of the two voters' claim sets (Jaccard)
+const MIN_VOTE_OVERLAP: f64 = 0.8;
+
+/// Width of the sliding account-creation window
+const BURST_WINDOW_SECS: i64 = 600;
+/// Accounts created within one window before it counts as a burst
+const BURST_MIN_ACCOUNTS: usize = 10;
+/// Share of a burst account's attention going to a single author
+const BURST_MIN_CONCENTRATION: f64 = 0.5;
+
+pub const FLAG_MUTUAL_ATTENTION: &str = "mutual_attention_ring";
+pub const FLAG_COORDINATED_VOTING: &str = "coordinated_voting";
+pub const FLAG_ACCOUNT_BURST: &str = "account_burst";
+
+/// A scored suspicion about one address
+#[derive(Debug, Clone)]
+pub struct RiskFlag {
+    pub address: String,
+    pub flag_type: &'static str,
+    pub score: f64,
+    pub evidence: serde_json::Value,
+}
+
+/// Sybil and collusion detector
+/// Periodically builds two graphs from recent activity:
+/// - reader → author attention (valid sessions), searched for mutual-attention cliques
+/// - voter co-occurrence over truth-claim votes, searched for voting blocs
+///
+/// and looks for bursts of account creation whose attention is funnelled to
+/// one author. Flags are written to `risk_flags`; the ranker penalizes flagged
+/// authors and the attention handlers cut rewards for flagged readers.
+pub struct SybilDetector {
+    pool: SqlitePool,
+}
+
+impl SybilDetector {
+    pub fn new(pool: SqlitePool) -> Self {
+        SybilDetector { pool }
+    }
+
+    pub async fn run(&self) -> Result<()> {
+        info!("Starting Sybil detector loop...");
+
+        loop {
+            if let Err(e) = self.scan().await {
+                tracing::error!("Error scanning for Sybil rings: {}", e);
+            }
+
+            sleep(SCAN_INTERVAL).await;
+        }
+    }
+
+    pub async fn scan(&self) -> Result<usize> {
+        let attention = self.load_attention_edges().await?;
+        let votes = self.load_votes().await?;
+        let joined = self.load_join_times().await?;
+
+        let mut flags = mutual_attention_rings(&attention);
+        flags.extend(coordinated_voting(&votes));
+        flags.extend(account_bursts(&joined, &attention));
+
+        self.store(&flags).await?;
+
+        info!("Sybil scan complete: {} flags", flags.len());
+        Ok(flags.len())
+    }
+
+    /// reader → author → number of valid sessions in the window
+    async fn load_attention_edges(&self) -> Result<BTreeMap<String, BTreeMap<String, i64>>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT a.reader, p.author, COUNT(*) as sessions
+            FROM attention_sessions a
+            JOIN posts p ON p.id = a.post_id
+            WHERE a.validity = 'valid'
+              AND a.reader != p.author
+              AND a.created_at >= datetime('now', ?)
+            GROUP BY a.reader, p.author
+            "#
+        )
+        .bind(format!("-{} days", WINDOW_DAYS))
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut edges: BTreeMap<String, BTreeMap<String, i64>> = BTreeMap::new();
+        for r in rows {
+            edges
+                .entry(r.get("reader"))
+                .or_default()
+                .insert(r.get("author"), r.get("sessions"));
+        }
+        Ok(edges)
+    }
+
+    /// voter → claim → vote
+    async fn load_votes(&self) -> Result<BTreeMap<String, BTreeMap<String, bool>>> {
+        let rows = sqlx::query("SELECT claim_id, voter, vote FROM claim_votes WHERE created_at >= datetime('now', ?)")
+            .bind(format!("-{} days", WINDOW_DAYS))
+            .fetch_all(&self.pool)
+            .await?;
+
+        let mut votes: BTreeMap<String, BTreeMap<String, bool>> = BTreeMap::new();
+        for r in rows {
+            votes
+                .entry(r.get("voter"))
+                .or_default()
+                .insert(r.get("claim_id"), r.get("vote"));
+        }
+        Ok(votes)
+    }
+
+    /// address → join time (unix seconds) for recently created profiles
+    async fn load_join_times(&self) -> Result<BTreeMap<String, i64>> {
+        let rows = sqlx::query(
+            "SELECT address, CAST(strftime('%s', joined_at) AS INTEGER) as joined FROM profiles WHERE joined_at >= datetime('now', ?)"
+        )
+        .bind(format!("-{} days", WINDOW_DAYS))
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .filter_map(|r| r.get::<Option<i64>, _>("joined").map(|j| (r.get("address"), j)))
+            .collect())
+    }
+
+    /// Replace the previous scan's flags and re-rank posts of every author
+    /// whose risk changed
+    async fn store(&self, flags: &[RiskFlag]) -> Result<()> {
+        let now = chrono::Utc::now().timestamp();
+        let mut tx = self.pool.begin().await?;
+
+        let previous: BTreeSet<String> = sqlx::query_scalar("SELECT DISTINCT address FROM risk_flags")
+            .fetch_all(&mut *tx)
+            .await?
+            .into_iter()
+            .collect();
+
+        for flag in flags {
+            sqlx::query(
+                r#"
+                INSERT INTO risk_flags (address, flag_type, score, evidence, detected_at)
+                VALUES (?, ?, ?, ?, ?)
+                ON CONFLICT (address, flag_type) DO UPDATE SET
+                    score = EXCLUDED.score,
+                    evidence = EXCLUDED.evidence,
+                    detected_at = EXCLUDED.detected_at
+                "#
+            )
+            .bind(&flag.address)
+            .bind(flag.flag_type)
+            .bind(flag.score)
+            .bind(flag.evidence.to_string())
+            .bind(now)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        // Flags that were not raised again have cleared
+        sqlx::query("DELETE FROM risk_flags WHERE detected_at < ?")
+            .bind(now)
+            .execute(&mut *tx)
+            .await?;
+
+        tx.commit().await?;
+
+        let current: BTreeSet<String> = flags.iter().map(|f| f.address.clone()).collect();
+        for address in previous.union(&current) {
+            FeedRanker::mark_author_dirty(&self.pool, address, "risk").await?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Cliques of readers who mostly read each other.
+/// Score is the share of a member's attention spent inside the clique.
+pub fn mutual_attention_rings(edges: &BTreeMap<String, BTreeMap<String, i64>>) -> Vec<RiskFlag> {
+    let weight = |a: &str, b: &str| edges.get(a).and_then(|m| m.get(b)).copied().unwrap_or(0);
+
+    let mut mutual: BTreeMap<String, BTreeSet<String>> = BTreeMap::new();
+    for (reader, authors) in edges {
+        for (author, &sessions) in authors {
+            if sessions >= MIN_MUTUAL_SESSIONS && weight(author, reader) >= MIN_MUTUAL_SESSIONS {
+                mutual.entry(reader.clone()).or_default().insert(author.clone());
+            }
+        }
+    }
+
+    let mut best: BTreeMap<String, RiskFlag> = BTreeMap::new();
+    for clique in maximal_cliques(&mutual) {
+        if clique.len() < MIN_RING_SIZE {
+            continue;
+        }
+
+        for member in &clique {
+            let total: i64 = edges.get(member).map(|m| m.values().sum()).unwrap_or(0);
+            let inside: i64 = clique.iter().filter(|o| *o != member).map(|o| weight(member, o)).sum();
+            if total == 0 {
+                continue;
+            }
+
+            let score = inside as f64 / total as f64;
+            if best.get(member).is_none_or(|f| f.score < score) {
+                best.insert(member.clone(), RiskFlag {
+                    address: member.clone(),
+                    flag_type: FLAG_MUTUAL_ATTENTION,
+                    score,
+                    evidence: json!({ "ring": clique, "sessions_inside": inside, "sessions_total": total }),
+                });
+            }
+        }
+    }
+
+    best.into_values().collect()
+}
+
+/// Groups of voters who vote on the same claims the same way.
+/// Score is the mean agreement-weighted overlap with the rest of the bloc.
+/// Only voters who share a claim are compared, so the work grows with the
+/// votes per claim rather than with every pair of voters.
+pub fn coordinated_voting(votes: &BTreeMap<String, BTreeMap<String, bool>>) -> Vec<RiskFlag> {
+    let mut by_claim: BTreeMap<&String, Vec<(&String, bool)>> = BTreeMap::new();
+    for (voter, claims) in votes {
+        for (claim, &vote) in claims {
+            by_claim.entry(claim).or_default().push((voter, vote));
+        }
+    }
+
+    // (voter, voter) → (shared claims, claims voted the same way)
+    let mut pairs: BTreeMap<(&String, &String), (usize, usize)> = BTreeMap::new();
+    for voters in by_claim.values() {
+        for (i, &(a, vote_a)) in voters.iter().enumerate() {
+            for &(b, vote_b) in &voters[i + 1..] {
+                let counts = pairs.entry((a.min(b), a.max(b))).or_default();
+                counts.0 += 1;
+                if vote_a == vote_b {
+                    counts.1 += 1;
+                }
+            }
+        }
+    }
+
+    let mut links: BTreeMap<String, BTreeMap<String, f64>> = BTreeMap::new();
+    for ((a, b), (shared, agreeing)) in pairs {
+        if shared < MIN_COVOTES {
+            continue;
+        }
+
+        let agreement = agreeing as f64 / shared as f64;
+        let union = votes[a].len() + votes[b].len() - shared;
+        let overlap = shared as f64 / union as f64;
+
+        if agreement >= MIN_AGREEMENT && overlap >= MIN_VOTE_OVERLAP {
+            let strength = agreement * overlap;
+            links.entry(a.clone()).or_default().insert(b.clone(), strength);
+            links.entry(b.clone()).or_default().insert(a.clone(), strength);
+        }
+    }
+
+    let mut flags = Vec::new();
+    for bloc in components(&links) {
+        if bloc.len() < MIN_RING_SIZE {
+            continue;
+        }
+        for member in &bloc {
+            let peers = &links[member];
+            let score = peers.values().sum::<f64>() / (bloc.len() - 1) as f64;
+            flags.push(RiskFlag {
+                address: member.clone(),
+                flag_type: FLAG_COORDINATED_VOTING,
+                score: score.min(1.0),
+                evidence: json!({ "bloc": bloc, "linked_voters": peers.len() }),
+            });
+        }
+    }
+    flags
+}
+
+/// Accounts created in the same short window whose attention is concentrated
+/// on one author. A burst alone is not suspicious (launch days happen); the
+/// score grows with how narrowly the burst's attention is aimed.
+pub fn account_bursts(
+    joined: &BTreeMap<String, i64>,
+    edges: &BTreeMap<String, BTreeMap<String, i64>>,
+) -> Vec<RiskFlag> {
+    let mut flags = Vec::new();
+    for (address, (window_start, accounts)) in burst_windows(joined) {
+        if accounts < BURST_MIN_ACCOUNTS {
+            continue;
+        }
+
+        let Some(authors) = edges.get(address) else { continue };
+        let total: i64 = authors.values().sum();
+        let Some((target, &top)) = authors.iter().max_by_key(|(_, s)| **s) else { continue };
+        if total <= 0 {
+            continue;
+        }
+
+        let concentration = top as f64 / total as f64;
+        if concentration < BURST_MIN_CONCENTRATION {
+            continue;
+        }
+
+        flags.push(RiskFlag {
+            address: address.clone(),
+            flag_type: FLAG_ACCOUNT_BURST,
+            score: 0.5 * concentration,
+            evidence: json!({
+                "window_start": window_start,
+                "accounts_in_window": accounts,
+                "target_author": target,
+                "concentration": concentration,
+            }),
+        });
+    }
+    flags
+}
+
+/// For each account, the busiest `BURST_WINDOW_SECS` window containing its
+/// creation: (window start, accounts created in it). Windows slide over the
+/// sorted join times, so bursts aren't split by fixed bucket boundaries.
+fn burst_windows(joined: &BTreeMap<String, i64>) -> BTreeMap<&String, (i64, usize)> {
+    let mut sorted: Vec<(i64, &String)> = joined.iter().map(|(a, &t)| (t, a)).collect();
+    sorted.sort();
+    let n = sorted.len();
+
+    // Accounts in the window starting at each account's join time
+    let mut counts = vec![0; n];
+    let mut end = 0;
+    for (i, count) in counts.iter_mut().enumerate() {
+        end = end.max(i);
+        while end < n && sorted[end].0 < sorted[i].0 + BURST_WINDOW_SECS {
+            end += 1;
+        }
+        *count = end - i;
+    }
+
+    // A window starting at i contains k when i <= k and it reaches k's time;
+    // keep the largest such window with a monotonic deque over starts
+    let mut best = BTreeMap::new();
+    let mut starts: VecDeque<usize> = VecDeque::new();
+    for k in 0..n {
+        while starts.back().is_some_and(|&i| counts[i] <= counts[k]) {
+            starts.pop_back();
+        }
+        starts.push_back(k);
+        while starts.front().is_some_and(|&i| sorted[i].0 + BURST_WINDOW_SECS <= sorted[k].0) {
+            starts.pop_front();
+        }
+
+        let top = starts[0];
+        best.insert(sorted[k].1, (sorted[top].0, counts[top]));
+    }
+    best
+}
+
+/// Bron–Kerbosch with pivoting over an undirected adjacency map
+fn maximal_cliques(graph: &BTreeMap<String, BTreeSet<String>>) -> Vec<Vec<String>> {
+    fn expand(
+        graph: &BTreeMap<String, BTreeSet<String>>,
+        r: &mut Vec<String>,
+        mut p: BTreeSet<String>,
+        mut x: BTreeSet<String>,
+        out: &mut Vec<Vec<String>>,
+    ) {
+        if p.is_empty() && x.is_empty() {
+            out.push(r.clone());
+            return;
+        }
+
+        let empty = BTreeSet::new();
+        let neighbours = |v: &String| graph.get(v).unwrap_or(&empty);
+        let pivot = p.union(&x).max_by_key(|v| neighbours(v).intersection(&p).count()).cloned();
+        let candidates: Vec<String> = match &pivot {
+            Some(u) => p.difference(neighbours(u)).cloned().collect(),
+            None => p.iter().cloned().collect(),
+        };
+
+        for v in candidates {
+            let n = neighbours(&v);
+            r.push(v.clone());
+            expand(
+                graph,
+                r,
+                p.intersection(n).cloned().collect(),
+                x.intersection(n).cloned().collect(),
+                out,
+            );
+            r.pop();
+            p.remove(&v);
+            x.insert(v);
+        }
+    }
+
+    // Only keep edges present in both directions
+    let mut undirected: BTreeMap<String, BTreeSet<String>> = BTreeMap::new();
+    for (a, ns) in graph {
+        for b in ns {
+            if graph.get(b).is_some_and(|m| m.contains(a)) {
+                undirected.entry(a.clone()).or_default().insert(b.clone());
+            }
+        }
+    }
+
+    let mut out = Vec::new();
+    let all: BTreeSet<String> = undirected.keys().cloned().collect();
+    expand(&undirected, &mut Vec::new(), all, BTreeSet::new(), &mut out);
+    out
+}
+
+/// Connected components of an undirected weighted graph
+fn components(graph: &BTreeMap<String, BTreeMap<String, f64>>) -> Vec<Vec<String>> {
+    let mut seen: BTreeSet<&String> = BTreeSet::new();
+    let mut out = Vec::new();
+
+    for start in graph.keys() {
+        if !seen.insert(start) {
+            continue;
+        }
+        let mut stack = vec![start];
+        let mut component = Vec::new();
+        while let Some(v) = stack.pop() {
+            component.push(v.clone());
+            for n in graph[v].keys() {
+                if seen.insert(n) {
+                    stack.push(n);
+                }
+            }
+        }
+        component.sort();
+        out.push(component);
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn edges(list: &[(&str, &str, i64)]) -> BTreeMap<String, BTreeMap<String, i64>> {
+        let mut edges: BTreeMap<String, BTreeMap<String, i64>> = BTreeMap::new();
+        for &(reader, author, sessions) in list {
+            edges.entry(reader.to_string()).or_default().insert(author.to_string(), sessions);
+        }
+        edges
+    }
+
+    fn flagged(flags: &[RiskFlag]) -> Vec<&str> {
+        let mut addresses: Vec<&str> = flags.iter().map(|f| f.address.as_str()).collect();
+        addresses.sort();
+        addresses
+    }
+
+    #[test]
+    fn maximal_cliques_finds_overlapping_cliques() {
+        let mut graph: BTreeMap<String, BTreeSet<String>> = BTreeMap::new();
+        let mut link = |a: &str, b: &str| {
+            graph.entry(a.to_string()).or_default().insert(b.to_string());
+            graph.entry(b.to_string()).or_default().insert(a.to_string());
+        };
+        // a-b-c-d fully connected, d-e-f a triangle sharing d
+        for (a, b) in [("a", "b"), ("a", "c"), ("a", "d"), ("b", "c"), ("b", "d"), ("c", "d"), ("d", "e"), ("d", "f"), ("e", "f")] {
+            link(a, b);
+        }
+        // One-way edges don't count
+        graph.entry("g".to_string()).or_default().insert("a".to_string());
+
+        let mut cliques: Vec<Vec<String>> = maximal_cliques(&graph)
+            .into_iter()
+            .map(|mut c| {
+                c.sort();
+                c
+            })
+            .collect();
+        cliques.sort();
+
+        assert_eq!(cliques, vec![vec!["a", "b", "c", "d"], vec!["d", "e", "f"]]);
+    }
+
+    #[test]
+    fn mutual_attention_ring_is_flagged() {
+        let s = MIN_MUTUAL_SESSIONS;
+        let graph = edges(&[
+            ("a", "b", s), ("b", "a", s),
+            ("b", "c", s), ("c", "b", s),
+            ("a", "c", s), ("c", "a", s),
+            // a also reads an outsider; d reads a without being read back
+            ("a", "x", s * 2),
+            ("d", "a", s * 5),
+        ]);
+
+        let flags = mutual_attention_rings(&graph);
+
+        assert_eq!(flagged(&flags), vec!["a", "b", "c"]);
+        let a = flags.iter().find(|f| f.address == "a").unwrap();
+        assert_eq!(a.flag_type, FLAG_MUTUAL_ATTENTION);
+        assert!((a.score - 0.5).abs() < 1e-9);
+        let b = flags.iter().find(|f| f.address == "b").unwrap();
+        assert!((b.score - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn pairs_and_weak_edges_are_not_rings() {
+        let s = MIN_MUTUAL_SESSIONS;
+        let pair = edges(&[("a", "b", s), ("b", "a", s)]);
+        assert!(mutual_attention_rings(&pair).is_empty());
+
+        let weak = edges(&[("a", "b", s), ("b", "a", s), ("b", "c", s), ("c", "b", s), ("a", "c", s - 1), ("c", "a", s)]);
+        assert!(mutual_attention_rings(&weak).is_empty());
+    }
+
+    #[test]
+    fn voting_bloc_is_flagged() {
+        let mut votes: BTreeMap<String, BTreeMap<String, bool>> = BTreeMap::new();
+        let claims = ["c1", "c2", "c3", "c4"];
+        for voter in ["v1", "v2", "v3"] {
+            for (i, claim) in claims.iter().enumerate() {
+                votes.entry(voter.to_string()).or_default().insert(claim.to_string(), i % 2 == 0);
+            }
+        }
+        // Votes on the same claims but disagrees on most
+        for (i, claim) in claims.iter().enumerate() {
+            votes.entry("honest".to_string()).or_default().insert(claim.to_string(), i == 0);
+        }
+        // Agrees, but on too few shared claims
+        votes.entry("lurker".to_string()).or_default().insert("c1".to_string(), true);
+
+        let flags = coordinated_voting(&votes);
+
+        assert_eq!(flagged(&flags), vec!["v1", "v2", "v3"]);
+        assert!(flags.iter().all(|f| f.flag_type == FLAG_COORDINATED_VOTING && (f.score - 1.0).abs() < 1e-9));
+    }
+
+    #[test]
+    fn burst_straddling_a_window_boundary_is_flagged() {
+        // Ten accounts within 10 seconds, across a multiple of the window
+        let start = BURST_WINDOW_SECS * 1_000 - 5;
+        let mut joined = BTreeMap::new();
+        let mut list = Vec::new();
+        let names: Vec<String> = (0..BURST_MIN_ACCOUNTS).map(|i| format!("bot{}", i)).collect();
+        for (i, name) in names.iter().enumerate() {
+            joined.insert(name.clone(), start + i as i64);
+            list.push((name.as_str(), "target", 8));
+            list.push((name.as_str(), "other", 2));
+        }
+        joined.insert("early".to_string(), start - BURST_WINDOW_SECS * 3);
+        list.push(("early", "target", 10));
+
+        let flags = account_bursts(&joined, &edges(&list));
+
+        assert_eq!(flags.len(), BURST_MIN_ACCOUNTS);
+        assert!(flags.iter().all(|f| f.address.starts_with("bot")));
+        let flag = &flags[0];
+        assert_eq!(flag.evidence["window_start"], start);
+        assert_eq!(flag.evidence["accounts_in_window"], BURST_MIN_ACCOUNTS);
+        assert_eq!(flag.evidence["target_author"], "target");
+        assert!((flag.score - 0.4).abs() < 1e-9);
+    }
+
+    #[test]
+    fn spread_out_or_unfocused_accounts_are_not_bursts() {
+        // One account short of a burst in any window
+        let spread: BTreeMap<String, i64> = (0..BURST_MIN_ACCOUNTS * 2)
+            .map(|i| (format!("a{}", i), i as i64 * (BURST_WINDOW_SECS / (BURST_MIN_ACCOUNTS as i64 - 1) + 1)))
+            .collect();
+        let aimed = edges(&spread.keys().map(|a| (a.as_str(), "target", 5)).collect::<Vec<_>>());
+        assert!(account_bursts(&spread, &aimed).is_empty());
+
+        // A real burst whose attention is spread across authors
+        let burst: BTreeMap<String, i64> = (0..BURST_MIN_ACCOUNTS).map(|i| (format!("b{}", i), 100 + i as i64)).collect();
+        let mut list = Vec::new();
+        for a in burst.keys() {
+            for author in ["x", "y", "z"] {
+                list.push((a.as_str(), author, 3));
+            }
+        }
+        assert!(account_bursts(&burst, &edges(&list)).is_empty());
+    }
+
+    #[test]
+    fn burst_windows_reports_the_busiest_window_per_account() {
+        let joined: BTreeMap<String, i64> = [("a", 0), ("b", 10), ("c", BURST_WINDOW_SECS - 1), ("d", BURST_WINDOW_SECS), ("e", BURST_WINDOW_SECS + 5)]
+            .into_iter()
+            .map(|(a, t)| (a.to_string(), t))
+            .collect();
+
+        let windows = burst_windows(&joined);
+        let get = |a: &str| windows[&a.to_string()];
+
+        assert_eq!(get("a"), (0, 3));
+        assert_eq!(get("b"), (10, 4));
+        assert_eq!(get("c"), (10, 4));
+        assert_eq!(get("d"), (10, 4));
+        assert_eq!(get("e"), (10, 4));
+    }
+}
@@ -0,0 +1,129 @@
+use anyhow::{anyhow, Result};
+use serde_json::{json, Value};
+use sqlx::{Row, SqlitePool};
+use std::time::Duration;
+use tokio::time::sleep;
+use tracing::info;
+
+use crate::sui_indexer::INDEXED_MODULES;
+
+/// How often pending transactions are checked
+const POLL_INTERVAL: Duration = Duration::from_secs(15);
+/// How long the event indexer gets to confirm a transaction first
+const CONFIRMATION_GRACE_SECS: i64 = 30;
+/// Pending transactions the chain still doesn't know after this are dropped
+const PENDING_EXPIRY_SECS: i64 = 600;
+/// Pending transactions checked per pass
+const BATCH_SIZE: i64 = 100;
+
+/// Transaction reconciler
+/// Settles `pending_transactions` the event indexer can't: aborted
+/// transactions, calls into modules whose events aren't indexed, and
+/// submissions that never landed.
+pub struct TxReconciler {
+    rpc_url: String,
+    pool: SqlitePool,
+    http: reqwest::Client,
+}
+
+/// What the chain says about a submitted transaction
+enum ChainStatus {
+    Unknown,
+    Success,
+    Failure(String),
+}
+
+impl TxReconciler {
+    pub fn new(rpc_url: String, pool: SqlitePool) -> Self {
+        TxReconciler {
+            rpc_url,
+            pool,
+            http: reqwest::Client::new(),
+        }
+    }
+
+    pub async fn run(&self) -> Result<()> {
+        info!("Starting transaction reconciler loop...");
+
+        loop {
+            if let Err(e) = self.reconcile().await {
+                tracing::error!("Error reconciling transactions: {}", e);
+            }
+
+            sleep(POLL_INTERVAL).await;
+        }
+    }
+
+    async fn reconcile(&self) -> Result<()> {
+        let rows = sqlx::query(
+            r#"
+            SELECT digest, function,
+                CAST(strftime('%s', 'now') - strftime('%s', submitted_at) AS INTEGER) as age
+            FROM pending_transactions
+            WHERE status = 'pending' AND submitted_at <= datetime('now', ?)
+            ORDER BY submitted_at
+            LIMIT ?
+            "#
+        )
+        .bind(format!("-{} seconds", CONFIRMATION_GRACE_SECS))
+        .bind(BATCH_SIZE)
+        .fetch_all(&self.pool)
+        .await?;
+
+        for row in rows {
+            let digest: String = row.get("digest");
+            let function: String = row.get("function");
+            let expired = row.get::<i64, _>("age") >= PENDING_EXPIRY_SECS;
+            let module = function.split("::").next().unwrap_or_default();
+
+            let resolution = match self.chain_status(&digest).await? {
+                ChainStatus::Failure(error) => Some(("failed", Some(error))),
+                // Indexed calls wait for their events so the rows they
+                // create exist before the client sees them confirmed
+                ChainStatus::Success if expired || !INDEXED_MODULES.contains(&module) => Some(("confirmed", None)),
+                ChainStatus::Success => None,
+                ChainStatus::Unknown if expired => Some(("failed", Some("transaction never landed".to_string()))),
+                ChainStatus::Unknown => None,
+            };
+            let Some((status, error)) = resolution else {
+                continue;
+            };
+
+            sqlx::query("UPDATE pending_transactions SET status = ?, error = ?, resolved_at = CURRENT_TIMESTAMP WHERE digest = ? AND status = 'pending'")
+                .bind(status)
+                .bind(&error)
+                .bind(&digest)
+                .execute(&self.pool)
+                .await?;
+            info!("Transaction {} ({}) {}", digest, function, status);
+        }
+
+        Ok(())
+    }
+
+    async fn chain_status(&self, digest: &str) -> Result<ChainStatus> {
+        let body = json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "sui_getTransactionBlock",
+            "params": [digest, { "showEffects": true }]
+        });
+
+        let resp: Value = self.http.post(&self.rpc_url).json(&body).send().await?.json().await?;
+        if let Some(err) = resp.get("error") {
+            // The node answers unknown digests with an error rather than null
+            let message = err["message"].as_str().unwrap_or_default();
+            if message.contains("Could not find") || message.contains("not found") {
+                return Ok(ChainStatus::Unknown);
+            }
+            return Err(anyhow!("sui_getTransactionBlock failed: {}", err));
+        }
+
+        let status = &resp["result"]["effects"]["status"];
+        Ok(match status["status"].as_str() {
+            Some("success") => ChainStatus::Success,
+            Some(_) => ChainStatus::Failure(status["error"].as_str().unwrap_or("execution failed").to_string()),
+            None => ChainStatus::Unknown,
+        })
+    }
+}
@@ -0,0 +1,383 @@
+use anyhow::Result;
+use hmac::{Hmac, Mac};
+use serde_json::{json, Value};
+use sha2::Sha256;
+use sqlx::{Row, SqlitePool};
+use std::{
+    collections::HashMap,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+use tokio::{sync::Semaphore, task::JoinSet, time::sleep};
+use tracing::info;
+
+use suiter_core::webhooks;
+
+/// How often due deliveries are picked up
+const POLL_INTERVAL: Duration = Duration::from_secs(5);
+/// Deliveries attempted per pass
+const BATCH_SIZE: i64 = 50;
+/// Requests in flight to one webhook at a time; the batch is otherwise sent
+/// concurrently, so one slow receiver doesn't hold up the others
+const PER_WEBHOOK_CONCURRENCY: usize = 4;
+/// Attempts before a delivery is dead-lettered
+const MAX_ATTEMPTS: i64 = 8;
+/// Delay after the first failure; doubles with each further one
+const BACKOFF_BASE_SECS: i64 = 30;
+const BACKOFF_MAX_SECS: i64 = 3600;
+const DELIVERY_TIMEOUT: Duration = Duration::from_secs(10);
+/// Delivered rows are kept in the log this long
+const RETENTION: &str = "-30 days";
+const PRUNE_INTERVAL: Duration = Duration::from_secs(3600);
+/// Response bodies kept as the error of a failed attempt, in characters
+const MAX_ERROR_LEN: usize = 500;
+
+/// Webhook dispatcher
+/// Sends queued `webhook_deliveries` to their webhooks. Each request is
+/// signed with the webhook's secret:
+///
+///     X-Suiter-Signature: sha256=hex(HMAC-SHA256(secret, "<X-Suiter-Timestamp>.<body>"))
+///
+/// Anything but a 2xx answer is retried with exponential backoff; after
+/// MAX_ATTEMPTS the delivery is dead-lettered. Deliveries of deactivated
+/// webhooks wait until they're reactivated.
+pub struct WebhookDispatcher {
+    pool: SqlitePool,
+    client: WebhookClient,
+}
+
+impl WebhookDispatcher {
+    pub fn new(pool: SqlitePool) -> Self {
+        WebhookDispatcher { pool, client: WebhookClient::from_env() }
+    }
+
+    pub async fn run(&self) -> Result<()> {
+        info!("Starting webhook dispatcher loop...");
+
+        let mut last_prune: Option<Instant> = None;
+        loop {
+            match self.dispatch().await {
+                Ok((0, 0)) => {}
+                Ok((delivered, failed)) => info!("Webhooks: {} delivered, {} failed", delivered, failed),
+                Err(e) => tracing::error!("Error dispatching webhooks: {}", e),
+            }
+
+            if last_prune.is_none_or(|t| t.elapsed() >= PRUNE_INTERVAL) {
+                last_prune = Some(Instant::now());
+                match sqlx::query("DELETE FROM webhook_deliveries WHERE status = 'delivered' AND delivered_at < datetime('now', ?)").bind(RETENTION).execute(&self.pool).await {
+                    Ok(r) if r.rows_affected() > 0 => info!("Pruned {} webhook deliveries", r.rows_affected()),
+                    Ok(_) => {}
+                    Err(e) => tracing::error!("Failed to prune webhook deliveries: {}", e),
+                }
+            }
+
+            sleep(POLL_INTERVAL).await;
+        }
+    }
+
+    /// Attempt one batch of due deliveries concurrently, at most
+    /// PER_WEBHOOK_CONCURRENCY to each webhook; returns (delivered, failed)
+    async fn dispatch(&self) -> Result<(usize, usize)> {
+        let rows = sqlx::query(
+            r#"
+            SELECT d.id, d.webhook_id, d.event, d.payload, d.attempts, d.created_at, w.url, w.secret
+            FROM webhook_deliveries d
+            JOIN webhooks w ON w.id = d.webhook_id
+            WHERE d.status = 'pending' AND d.next_attempt_at <= CURRENT_TIMESTAMP AND w.active
+            ORDER BY d.next_attempt_at, d.id
+            LIMIT ?
+            "#
+        )
+        .bind(BATCH_SIZE)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut limits: HashMap<String, Arc<Semaphore>> = HashMap::new();
+        let mut sends = JoinSet::new();
+        for r in rows {
+            let id: i64 = r.get("id");
+            let event: String = r.get("event");
+            let body = json!({
+                "id": id,
+                "event": event,
+                "created_at": r.get::<String, _>("created_at"),
+                "data": serde_json::from_str::<Value>(&r.get::<String, _>("payload")).unwrap_or(Value::Null),
+            })
+            .to_string();
+            let (url, secret): (String, String) = (r.get("url"), r.get("secret"));
+            let attempts = r.get::<i64, _>("attempts") + 1;
+
+            let limit = limits
+                .entry(r.get("webhook_id"))
+                .or_insert_with(|| Arc::new(Semaphore::new(PER_WEBHOOK_CONCURRENCY)))
+                .clone();
+            let client = self.client;
+            sends.spawn(async move {
+                let _permit = limit.acquire_owned().await.expect("webhook limits are never closed");
+                let (status, error) = client.send(&url, &secret, &event, &id.to_string(), body).await;
+                (id, attempts, status, error)
+            });
+        }
+
+        let (mut delivered, mut failed) = (0, 0);
+        while let Some(sent) = sends.join_next().await {
+            let (id, attempts, status, error) = sent?;
+            match error {
+                None => {
+                    sqlx::query("UPDATE webhook_deliveries SET status = 'delivered', attempts = ?, response_status = ?, last_error = NULL, delivered_at = CURRENT_TIMESTAMP WHERE id = ?")
+                        .bind(attempts)
+                        .bind(status)
+                        .bind(id)
+                        .execute(&self.pool)
+                        .await?;
+                    delivered += 1;
+                }
+                Some(error) => {
+                    self.record_failure(id, attempts, status, &error).await?;
+                    failed += 1;
+                }
+            }
+        }
+
+        Ok((delivered, failed))
+    }
+
+    /// Schedule the next attempt, or dead-letter the delivery once it's out
+    /// of attempts
+    async fn record_failure(&self, id: i64, attempts: i64, status: Option<u16>, error: &str) -> Result<()> {
+        if attempts < MAX_ATTEMPTS {
+            sqlx::query("UPDATE webhook_deliveries SET attempts = ?, response_status = ?, last_error = ?, next_attempt_at = datetime('now', ?) WHERE id = ?")
+                .bind(attempts)
+                .bind(status)
+                .bind(error)
+                .bind(format!("+{} seconds", backoff_secs(attempts)))
+                .bind(id)
+                .execute(&self.pool)
+                .await?;
+            return Ok(());
+        }
+
+        let mut tx = self.pool.begin().await?;
+        sqlx::query("UPDATE webhook_deliveries SET status = 'dead', attempts = ?, response_status = ?, last_error = ? WHERE id = ?")
+            .bind(attempts)
+            .bind(status)
+            .bind(error)
+            .bind(id)
+            .execute(&mut *tx)
+            .await?;
+        sqlx::query(
+            r#"
+            INSERT OR REPLACE INTO webhook_dead_letters(delivery_id, webhook_id, event, payload, attempts, last_error, dead_at)
+            SELECT id, webhook_id, event, payload, attempts, last_error, CURRENT_TIMESTAMP FROM webhook_deliveries WHERE id = ?
+            "#
+        )
+        .bind(id)
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await?;
+
+        tracing::warn!("Webhook delivery {} dead-lettered after {} attempts: {}", id, attempts, error);
+        Ok(())
+    }
+}
+
+/// Sends signed webhook requests; shared with the notification webhook sink
+#[derive(Clone, Copy)]
+pub(crate) struct WebhookClient {
+    /// See `webhooks::ALLOW_PRIVATE_NETWORKS_ENV`
+    allow_private: bool,
+}
+
+impl WebhookClient {
+    pub(crate) fn new(allow_private: bool) -> Self {
+        WebhookClient { allow_private }
+    }
+
+    pub(crate) fn from_env() -> Self {
+        Self::new(webhooks::allow_private_networks())
+    }
+
+    /// POST one signed delivery; returns the response status, if any, and
+    /// the error when it wasn't a success
+    pub(crate) async fn send(&self, url: &str, secret: &str, event: &str, delivery: &str, body: String) -> (Option<u16>, Option<String>) {
+        let http = match self.pinned(url).await {
+            Ok(h) => h,
+            Err(e) => return (None, Some(e)),
+        };
+        let timestamp = chrono::Utc::now().timestamp();
+        let signature = sign(secret, timestamp, &body);
+
+        let response = http
+            .post(url)
+            .header("Content-Type", "application/json")
+            .header("X-Suiter-Event", event)
+            .header("X-Suiter-Delivery", delivery)
+            .header("X-Suiter-Timestamp", timestamp.to_string())
+            .header("X-Suiter-Signature", format!("sha256={}", signature))
+            .body(body)
+            .send()
+            .await;
+
+        match response {
+            Ok(r) if r.status().is_success() => (Some(r.status().as_u16()), None),
+            Ok(r) => {
+                let status = r.status();
+                let text: String = r.text().await.unwrap_or_default().chars().take(MAX_ERROR_LEN).collect();
+                (Some(status.as_u16()), Some(format!("{}: {}", status, text)))
+            }
+            Err(e) => (None, Some(e.to_string())),
+        }
+    }
+
+    /// A client that only connects to the public addresses `url` resolves
+    /// to right now, checked again on every send since DNS can change after
+    /// registration. Redirects aren't followed, as they could lead anywhere.
+    async fn pinned(&self, url: &str) -> Result<reqwest::Client, String> {
+        let parsed = reqwest::Url::parse(url).map_err(|e| format!("invalid webhook URL: {}", e))?;
+        let host = parsed.host_str().ok_or("webhook URL has no host")?;
+        let port = parsed.port_or_known_default().ok_or("webhook URL has no port")?;
+        let addrs = webhooks::resolve_public(host, port, self.allow_private).await?;
+
+        // Overrides only apply to domain names; IP literals were checked as is
+        reqwest::Client::builder()
+            .timeout(DELIVERY_TIMEOUT)
+            .redirect(reqwest::redirect::Policy::none())
+            .resolve_to_addrs(host, &addrs)
+            .build()
+            .map_err(|e| e.to_string())
+    }
+}
+
+/// Delay before the attempt after `attempts` failures
+pub(crate) fn backoff_secs(attempts: i64) -> i64 {
+    let doublings = (attempts - 1).clamp(0, 20) as u32;
+    BACKOFF_BASE_SECS.saturating_mul(1 << doublings).min(BACKOFF_MAX_SECS)
+}
+
+/// Hex HMAC-SHA256 of `<timestamp>.<body>`; the timestamp is signed too so
+/// receivers can reject replays
+pub(crate) fn sign(secret: &str, timestamp: i64, body: &str) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any length");
+    mac.update(timestamp.to_string().as_bytes());
+    mac.update(b".");
+    mac.update(body.as_bytes());
+    hex::encode(mac.finalize().into_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use suiter_core::testing::migrated_pool;
+    use std::sync::Mutex;
+    use tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        net::TcpListener,
+    };
+
+    /// How long the listener holds each request, so overlapping ones show
+    const HOLD: Duration = Duration::from_millis(300);
+
+
+    /// Most requests in flight at once, per path
+    type Peaks = Arc<Mutex<HashMap<String, (usize, usize)>>>;
+
+    /// Answer every request with 200 after HOLD, tracking concurrency per path
+    async fn listener() -> (String, Peaks) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base = format!("http://{}", listener.local_addr().unwrap());
+        let peaks: Peaks = Arc::default();
+
+        let tracked = peaks.clone();
+        tokio::spawn(async move {
+            loop {
+                let (mut socket, _) = listener.accept().await.unwrap();
+                let peaks = tracked.clone();
+                tokio::spawn(async move {
+                    let mut buf = [0u8; 8192];
+                    let n = socket.read(&mut buf).await.unwrap();
+                    let request = String::from_utf8_lossy(&buf[..n]).to_string();
+                    let path = request.split_whitespace().nth(1).unwrap_or_default().to_string();
+
+                    {
+                        let mut peaks = peaks.lock().unwrap();
+                        let (active, peak) = peaks.entry(path.clone()).or_default();
+                        *active += 1;
+                        *peak = (*peak).max(*active);
+                    }
+                    sleep(HOLD).await;
+                    peaks.lock().unwrap().get_mut(&path).unwrap().0 -= 1;
+
+                    let _ = socket.write_all(b"HTTP/1.1 200 OK\r\ncontent-length: 0\r\nconnection: close\r\n\r\n").await;
+                });
+            }
+        });
+
+        (base, peaks)
+    }
+
+    async fn webhook(pool: &SqlitePool, id: &str, url: &str, deliveries: usize) {
+        sqlx::query("INSERT OR IGNORE INTO profiles(address) VALUES ('0xowner')")
+            .execute(pool)
+            .await
+            .unwrap();
+        sqlx::query("INSERT INTO webhooks(id, owner, url, secret, events) VALUES (?, '0xowner', ?, 'whsec_test', 'post.created')")
+            .bind(id)
+            .bind(url)
+            .execute(pool)
+            .await
+            .unwrap();
+        for i in 0..deliveries {
+            sqlx::query("INSERT INTO webhook_deliveries(webhook_id, event, source_ref, payload) VALUES (?, 'post.created', ?, '{}')")
+                .bind(id)
+                .bind(i.to_string())
+                .execute(pool)
+                .await
+                .unwrap();
+        }
+    }
+
+    #[tokio::test]
+    async fn dispatch_sends_concurrently_within_the_per_webhook_cap() {
+        let pool = migrated_pool().await;
+        let (base, peaks) = listener().await;
+        webhook(&pool, "a", &format!("{}/a", base), PER_WEBHOOK_CONCURRENCY + 2).await;
+        webhook(&pool, "b", &format!("{}/b", base), 2).await;
+        let dispatcher = WebhookDispatcher { pool: pool.clone(), client: WebhookClient::new(true) };
+
+        assert_eq!(dispatcher.dispatch().await.unwrap(), (PER_WEBHOOK_CONCURRENCY + 4, 0));
+
+        let peaks = peaks.lock().unwrap().clone();
+        assert_eq!(peaks["/a"].1, PER_WEBHOOK_CONCURRENCY);
+        assert_eq!(peaks["/b"].1, 2);
+        let delivered: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM webhook_deliveries WHERE status = 'delivered' AND attempts = 1")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(delivered, PER_WEBHOOK_CONCURRENCY as i64 + 4);
+    }
+
+    #[tokio::test]
+    async fn dispatch_refuses_private_addresses_at_send_time() {
+        let pool = migrated_pool().await;
+        let (base, peaks) = listener().await;
+        webhook(&pool, "a", &format!("{}/a", base), 1).await;
+        let dispatcher = WebhookDispatcher { pool: pool.clone(), client: WebhookClient::new(false) };
+
+        assert_eq!(dispatcher.dispatch().await.unwrap(), (0, 1));
+        assert!(peaks.lock().unwrap().is_empty());
+        let (status, error): (String, String) = sqlx::query_as("SELECT status, last_error FROM webhook_deliveries")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(status, "pending");
+        assert!(error.contains("non-public address 127.0.0.1"), "{}", error);
+    }
+
+    #[test]
+    fn backoff_doubles_up_to_the_cap() {
+        assert_eq!(backoff_secs(1), BACKOFF_BASE_SECS);
+        assert_eq!(backoff_secs(2), BACKOFF_BASE_SECS * 2);
+        assert_eq!(backoff_secs(3), BACKOFF_BASE_SECS * 4);
+        assert_eq!(backoff_secs(40), BACKOFF_MAX_SECS);
+    }
+}
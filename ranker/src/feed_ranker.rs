@@ -0,0 +1,535 @@
+use anyhow::Result;
+use serde_json::json;
+use sqlx::{Executor, Row, Sqlite, SqlitePool, Transaction};
+use tracing::info;
+use std::time::{Duration, Instant};
+use tokio::time::sleep;
+
+use suiter_core::stream;
+
+// ============ SCORING CONSTANTS ============
+
+pub const LEVEL_WEIGHT: f64 = 0.3;
+pub const REPUTATION_WEIGHT: f64 = 0.2;
+pub const ATTENTION_WEIGHT: f64 = 0.3;
+pub const TIME_WEIGHT: f64 = 0.2;
+
+pub const MAX_LEVEL: f64 = 5.0;
+pub const MAX_REPUTATION: f64 = 100_000.0;
+pub const ATTENTION_SCALE: f64 = 100_000.0;
+/// Reputation assumed for authors without a profile row
+pub const DEFAULT_REPUTATION: i64 = 50;
+/// Trend factor for posts younger than an hour, and for older ones
+pub const FRESH_TREND: f64 = 1.0;
+pub const STALE_TREND: f64 = 0.5;
+/// Share of the score removed for an author with risk 1.0 (see `risk_flags`)
+pub const RISK_PENALTY: f64 = 0.5;
+
+// ============ SCHEDULING ============
+
+/// How often the dirty set is drained
+const DIRTY_POLL_INTERVAL: Duration = Duration::from_secs(2);
+/// How often every post is re-scored so time decay is applied
+const FULL_SWEEP_INTERVAL: Duration = Duration::from_secs(300);
+/// Maximum number of dirty posts re-scored per batch
+const DIRTY_BATCH_SIZE: i64 = 500;
+
+/// Shared scoring statement; `{filter}` restricts which posts are re-scored.
+/// The SELECT always carries a WHERE clause so SQLite can parse the upsert.
+fn score_query(filter: &str) -> String {
+    let level = format!("{} * (CAST(p.level AS REAL) / {:.1})", LEVEL_WEIGHT, MAX_LEVEL);
+    let reputation = format!(
+        "{} * (CAST(COALESCE(author_rep.reputation, {}) AS REAL) / {:.1})",
+        REPUTATION_WEIGHT, DEFAULT_REPUTATION, MAX_REPUTATION
+    );
+    let attention = format!("{} * (CAST(p.attention_accumulated AS REAL) / {:.1})", ATTENTION_WEIGHT, ATTENTION_SCALE);
+    let time = format!(
+        "{} * (CASE WHEN p.created_at > datetime('now', '-1 hour') THEN {:.1} ELSE {:.1} END)",
+        TIME_WEIGHT, FRESH_TREND, STALE_TREND
+    );
+    let penalty = RISK_PENALTY;
+
+    format!(
+        r#"
+        INSERT INTO feed_rankings (id, post_id, score, level_score, reputation_score, attention_score, time_score, calculated_at)
+        SELECT
+            'fr_' || p.id,
+            p.id,
+            ({level} + {reputation} + {attention} + {time}) * (1.0 - {penalty} * COALESCE(risk.score, 0.0)) as final_score,
+            {level},
+            {reputation},
+            {attention},
+            {time},
+            CAST(strftime('%s', 'now') AS INTEGER)
+        FROM posts p
+        LEFT JOIN profiles author_rep ON p.author = author_rep.address
+        LEFT JOIN (SELECT address, MAX(score) as score FROM risk_flags GROUP BY address) risk ON p.author = risk.address
+        WHERE {filter}
+        ON CONFLICT (id) DO UPDATE SET
+            score = EXCLUDED.score,
+            level_score = EXCLUDED.level_score,
+            reputation_score = EXCLUDED.reputation_score,
+            attention_score = EXCLUDED.attention_score,
+            time_score = EXCLUDED.time_score,
+            calculated_at = EXCLUDED.calculated_at
+        "#
+    )
+}
+
+/// Appends a history row for every post whose score differs from its last
+/// recorded one; `{filter}` restricts which rankings are considered.
+const HISTORY_QUERY: &str = r#"
+    INSERT INTO feed_ranking_history (post_id, score, level_score, reputation_score, attention_score, time_score, calculated_at)
+    SELECT fr.post_id, fr.score, fr.level_score, fr.reputation_score, fr.attention_score, fr.time_score, fr.calculated_at
+    FROM feed_rankings fr
+    WHERE {filter}
+      AND fr.score IS NOT (
+          SELECT h.score FROM feed_ranking_history h
+          WHERE h.post_id = fr.post_id
+          ORDER BY h.id DESC
+          LIMIT 1
+      )
+"#;
+
+/// Score of a single post, broken down by component
+#[derive(Debug, Clone, PartialEq)]
+pub struct PostScore {
+    pub score: f64,
+    pub level_score: f64,
+    pub reputation_score: f64,
+    pub attention_score: f64,
+    pub time_score: f64,
+}
+
+impl PostScore {
+    /// Same formula as the bulk SQL, for callers that have the inputs in hand.
+    /// `risk` is the author's highest Sybil risk score; it scales down the
+    /// total but not the individual components.
+    pub fn compute(level: i64, reputation: i64, attention: i64, fresh: bool, risk: f64) -> Self {
+        let level_score = LEVEL_WEIGHT * (level as f64 / MAX_LEVEL);
+        let reputation_score = REPUTATION_WEIGHT * (reputation as f64 / MAX_REPUTATION);
+        let attention_score = ATTENTION_WEIGHT * (attention as f64 / ATTENTION_SCALE);
+        let time_score = TIME_WEIGHT * if fresh { FRESH_TREND } else { STALE_TREND };
+
+        PostScore {
+            score: (level_score + reputation_score + attention_score + time_score) * (1.0 - RISK_PENALTY * risk),
+            level_score,
+            reputation_score,
+            attention_score,
+            time_score,
+        }
+    }
+}
+
+/// Feed ranking engine
+/// Re-scores posts from the `ranking_dirty` set as soon as they change,
+/// and sweeps every post every 5 minutes so time decay is applied, using:
+/// score = 0.3*L + 0.2*R + 0.3*V + 0.2*T
+/// where:
+/// - L: Post level (1-5)
+/// - R: Author reputation weight
+/// - V: Attention velocity
+/// - T: Trend score (recent attention spike)
+///
+/// Posts by authors flagged in `risk_flags` lose up to `RISK_PENALTY` of their score.
+///
+/// The indexer drives `run`; the API calls `recompute` directly after its
+/// own writes so new rankings don't wait for the next poll.
+#[derive(Clone)]
+pub struct FeedRanker {
+    pool: SqlitePool,
+}
+
+impl FeedRanker {
+    pub fn new(pool: SqlitePool) -> Self {
+        FeedRanker { pool }
+    }
+
+    pub async fn run(&self) -> Result<()> {
+        info!("Starting feed ranker loop...");
+
+        let mut last_sweep: Option<Instant> = None;
+
+        loop {
+            if last_sweep.is_none_or(|t| t.elapsed() >= FULL_SWEEP_INTERVAL) {
+                if let Err(e) = self.recompute_all().await {
+                    tracing::error!("Error updating rankings: {}", e);
+                }
+                last_sweep = Some(Instant::now());
+            }
+
+            loop {
+                match self.rescore_dirty().await {
+                    Ok(n) if n as i64 == DIRTY_BATCH_SIZE => continue,
+                    Ok(_) => break,
+                    Err(e) => {
+                        tracing::error!("Error re-scoring dirty posts: {}", e);
+                        break;
+                    }
+                }
+            }
+
+            sleep(DIRTY_POLL_INTERVAL).await;
+        }
+    }
+
+    /// Full sweep over every post
+    pub async fn recompute_all(&self) -> Result<()> {
+        info!("Computing feed rankings...");
+
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query(&score_query("1 = 1")).execute(&mut *tx).await?;
+
+        let query = HISTORY_QUERY.replace("{filter}", "1 = 1");
+        sqlx::query(&query).execute(&mut *tx).await?;
+
+        // Everything is fresh now; the dirty set can be dropped in the same transaction
+        sqlx::query("DELETE FROM ranking_dirty").execute(&mut *tx).await?;
+
+        Self::bump_version(&mut tx).await?;
+        // No ids: every post was re-scored
+        stream::publish(&mut *tx, stream::FEED, "ranking_updated", &json!({ "post_ids": null })).await?;
+        tx.commit().await?;
+
+        info!("Rankings updated successfully");
+        Ok(())
+    }
+
+    /// Re-score the given posts right away and drop them from the dirty set
+    pub async fn recompute(&self, post_ids: &[String]) -> Result<()> {
+        if post_ids.is_empty() {
+            return Ok(());
+        }
+
+        let mut tx = self.pool.begin().await?;
+        Self::recompute_in(&mut tx, post_ids).await?;
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    /// Re-score one batch of posts from the dirty set.
+    /// Returns the number of posts re-scored.
+    pub async fn rescore_dirty(&self) -> Result<usize> {
+        let mut tx = self.pool.begin().await?;
+
+        let ids: Vec<String> = sqlx::query("SELECT post_id FROM ranking_dirty ORDER BY marked_at LIMIT ?")
+            .bind(DIRTY_BATCH_SIZE)
+            .fetch_all(&mut *tx)
+            .await?
+            .into_iter()
+            .map(|r| r.get::<String, _>("post_id"))
+            .collect();
+
+        if ids.is_empty() {
+            return Ok(0);
+        }
+
+        Self::recompute_in(&mut tx, &ids).await?;
+        tx.commit().await?;
+
+        tracing::debug!("Re-scored {} dirty posts", ids.len());
+        Ok(ids.len())
+    }
+
+    /// SQLite serializes writers, so nothing can re-mark these posts between
+    /// scoring and clearing them inside the caller's transaction
+    async fn recompute_in(tx: &mut Transaction<'_, Sqlite>, ids: &[String]) -> Result<()> {
+        let placeholders = vec!["?"; ids.len()].join(", ");
+
+        let query = score_query(&format!("p.id IN ({})", placeholders));
+        let mut score = sqlx::query(&query);
+        for id in ids {
+            score = score.bind(id);
+        }
+        score.execute(&mut **tx).await?;
+
+        let query = HISTORY_QUERY.replace("{filter}", &format!("fr.post_id IN ({})", placeholders));
+        let mut history = sqlx::query(&query);
+        for id in ids {
+            history = history.bind(id);
+        }
+        history.execute(&mut **tx).await?;
+
+        let query = format!("DELETE FROM ranking_dirty WHERE post_id IN ({})", placeholders);
+        let mut clear = sqlx::query(&query);
+        for id in ids {
+            clear = clear.bind(id);
+        }
+        clear.execute(&mut **tx).await?;
+
+        // Subscribers to a post see its new score
+        let query = format!("SELECT post_id, CAST(score AS REAL) FROM feed_rankings WHERE post_id IN ({})", placeholders);
+        let mut scored = sqlx::query(&query);
+        for id in ids {
+            scored = scored.bind(id);
+        }
+        for row in scored.fetch_all(&mut **tx).await? {
+            let post_id = row.get::<String, _>(0);
+            let score = row.get::<f64, _>(1);
+            stream::publish(&mut **tx, &stream::post_topic(&post_id), "ranking_updated", &json!({ "post_id": post_id, "score": score })).await?;
+        }
+
+        Self::bump_version(tx).await?;
+        stream::publish(&mut **tx, stream::FEED, "ranking_updated", &json!({ "post_ids": ids })).await?;
+
+        Ok(())
+    }
+
+    /// Signal readers (the API's feed cache) that rankings changed
+    async fn bump_version(tx: &mut Transaction<'_, Sqlite>) -> Result<()> {
+        sqlx::query("UPDATE ranking_state SET version = version + 1, updated_at = CURRENT_TIMESTAMP WHERE id = 1")
+            .execute(&mut **tx)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Compute a post's score from its current inputs without storing it
+    pub async fn score_post(&self, post_id: &str) -> Result<Option<PostScore>> {
+        let row = sqlx::query(
+            r#"
+            SELECT p.level, p.attention_accumulated,
+                   p.created_at > datetime('now', '-1 hour') as fresh,
+                   COALESCE(author_rep.reputation, ?) as reputation,
+                   (SELECT MAX(score) FROM risk_flags WHERE address = p.author) as risk
+            FROM posts p
+            LEFT JOIN profiles author_rep ON p.author = author_rep.address
+            WHERE p.id = ?
+            "#
+        )
+        .bind(DEFAULT_REPUTATION)
+        .bind(post_id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(row.map(|r| {
+            PostScore::compute(
+                r.get::<i64, _>("level"),
+                r.get::<i64, _>("reputation"),
+                r.get::<i64, _>("attention_accumulated"),
+                r.get::<bool, _>("fresh"),
+                r.get::<Option<f64>, _>("risk").unwrap_or(0.0),
+            )
+        }))
+    }
+
+    /// Queue a post for re-scoring
+    pub async fn mark_dirty<'e, E>(executor: E, post_id: &str, reason: &str) -> Result<()>
+    where
+        E: Executor<'e, Database = Sqlite>,
+    {
+        sqlx::query(
+            r#"
+            INSERT INTO ranking_dirty (post_id, reason, marked_at)
+            VALUES (?, ?, CAST(strftime('%s', 'now') AS INTEGER))
+            ON CONFLICT (post_id) DO UPDATE SET
+                reason = EXCLUDED.reason,
+                marked_at = EXCLUDED.marked_at
+            "#
+        )
+        .bind(post_id)
+        .bind(reason)
+        .execute(executor)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Queue every post of an author, e.g. after a reputation change
+    pub async fn mark_author_dirty<'e, E>(executor: E, author: &str, reason: &str) -> Result<()>
+    where
+        E: Executor<'e, Database = Sqlite>,
+    {
+        sqlx::query(
+            r#"
+            INSERT INTO ranking_dirty (post_id, reason, marked_at)
+            SELECT id, ?, CAST(strftime('%s', 'now') AS INTEGER)
+            FROM posts
+            WHERE author = ?
+            ON CONFLICT (post_id) DO UPDATE SET
+                reason = EXCLUDED.reason,
+                marked_at = EXCLUDED.marked_at
+            "#
+        )
+        .bind(reason)
+        .bind(author)
+        .execute(executor)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Get top N posts by ranking
+    pub async fn get_top_posts(&self, limit: i64) -> Result<Vec<(String, f64)>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT post_id, CAST(score AS REAL)
+            FROM feed_rankings
+            ORDER BY CAST(score AS REAL) DESC
+            LIMIT ?
+            "#
+        )
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows.into_iter().map(|row| {
+            (
+                row.get::<String, _>(0),
+                row.get::<f64, _>(1),
+            )
+        }).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use suiter_core::testing::migrated_pool;
+
+    const AUTHOR: &str = "0xauthor";
+    const RISKY: &str = "0xrisky";
+
+    async fn setup() -> SqlitePool {
+        let pool = migrated_pool().await;
+        sqlx::query("INSERT INTO profiles(address, reputation) VALUES (?, 40000), (?, 2000)")
+            .bind(AUTHOR)
+            .bind(RISKY)
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query("INSERT INTO risk_flags(address, flag_type, score, evidence, detected_at) VALUES (?, 'ring', 0.4, '{}', 0), (?, 'burst', 0.6, '{}', 0)")
+            .bind(RISKY)
+            .bind(RISKY)
+            .execute(&pool)
+            .await
+            .unwrap();
+        for (id, author, level, attention, fresh) in [
+            ("0xfresh", AUTHOR, 3, 25_000, true),
+            ("0xstale", AUTHOR, 5, 80_000, false),
+            ("0xrisky", RISKY, 4, 10_000, true),
+            // A fresh profile starts at the default reputation
+            ("0xanon", "0xnobody", 1, 0, false),
+        ] {
+            sqlx::query("INSERT OR IGNORE INTO profiles(address) VALUES (?)")
+                .bind(author)
+                .execute(&pool)
+                .await
+                .unwrap();
+            sqlx::query("INSERT INTO posts(id, author, content_hash, level, attention_accumulated, created_at) VALUES (?, ?, 'h', ?, ?, datetime('now', ?))")
+                .bind(id)
+                .bind(author)
+                .bind(level)
+                .bind(attention)
+                .bind(if fresh { "-5 minutes" } else { "-2 hours" })
+                .execute(&pool)
+                .await
+                .unwrap();
+        }
+        pool
+    }
+
+    async fn stored(pool: &SqlitePool, post_id: &str) -> PostScore {
+        let row = sqlx::query("SELECT CAST(score AS REAL), CAST(level_score AS REAL), CAST(reputation_score AS REAL), CAST(attention_score AS REAL), CAST(time_score AS REAL) FROM feed_rankings WHERE post_id = ?")
+            .bind(post_id)
+            .fetch_one(pool)
+            .await
+            .unwrap();
+        PostScore {
+            score: row.get(0),
+            level_score: row.get(1),
+            reputation_score: row.get(2),
+            attention_score: row.get(3),
+            time_score: row.get(4),
+        }
+    }
+
+    fn assert_close(a: &PostScore, b: &PostScore) {
+        for (x, y) in [
+            (a.score, b.score),
+            (a.level_score, b.level_score),
+            (a.reputation_score, b.reputation_score),
+            (a.attention_score, b.attention_score),
+            (a.time_score, b.time_score),
+        ] {
+            assert!((x - y).abs() < 1e-9, "{:?} != {:?}", a, b);
+        }
+    }
+
+    async fn dirty(pool: &SqlitePool) -> i64 {
+        sqlx::query_scalar("SELECT COUNT(*) FROM ranking_dirty").fetch_one(pool).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn compute_agrees_with_the_bulk_sql() {
+        let pool = setup().await;
+        let ranker = FeedRanker::new(pool.clone());
+        ranker.recompute_all().await.unwrap();
+
+        let expected = [
+            ("0xfresh", PostScore::compute(3, 40_000, 25_000, true, 0.0)),
+            ("0xstale", PostScore::compute(5, 40_000, 80_000, false, 0.0)),
+            // The author's highest risk flag counts
+            ("0xrisky", PostScore::compute(4, 2_000, 10_000, true, 0.6)),
+            ("0xanon", PostScore::compute(1, DEFAULT_REPUTATION, 0, false, 0.0)),
+        ];
+        for (post_id, score) in &expected {
+            assert_close(&stored(&pool, post_id).await, score);
+            assert_close(&ranker.score_post(post_id).await.unwrap().unwrap(), score);
+        }
+        assert_eq!(ranker.score_post("0xmissing").await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn top_posts_come_best_first() {
+        let pool = setup().await;
+        let ranker = FeedRanker::new(pool.clone());
+        assert!(ranker.get_top_posts(10).await.unwrap().is_empty());
+
+        ranker.recompute_all().await.unwrap();
+
+        let top = ranker.get_top_posts(10).await.unwrap();
+        let ids: Vec<&str> = top.iter().map(|(id, _)| id.as_str()).collect();
+        assert_eq!(ids, ["0xstale", "0xfresh", "0xrisky", "0xanon"]);
+        assert!(top.windows(2).all(|w| w[0].1 >= w[1].1));
+        assert!((top[0].1 - stored(&pool, "0xstale").await.score).abs() < 1e-9);
+
+        assert_eq!(ranker.get_top_posts(2).await.unwrap(), top[..2].to_vec());
+    }
+
+    #[tokio::test]
+    async fn recompute_clears_what_it_scored_and_publishes_per_post() {
+        let pool = setup().await;
+        let ranker = FeedRanker::new(pool.clone());
+
+        FeedRanker::mark_author_dirty(&pool, AUTHOR, "reputation").await.unwrap();
+        FeedRanker::mark_dirty(&pool, "0xrisky", "attention").await.unwrap();
+        assert_eq!(dirty(&pool).await, 3);
+
+        // Only the named posts are scored and cleared
+        ranker.recompute(&["0xrisky".to_string()]).await.unwrap();
+        assert_eq!(dirty(&pool).await, 2);
+        let ranked: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM feed_rankings").fetch_one(&pool).await.unwrap();
+        assert_eq!(ranked, 1);
+
+        assert_eq!(ranker.rescore_dirty().await.unwrap(), 2);
+        assert_eq!(dirty(&pool).await, 0);
+        assert_eq!(ranker.rescore_dirty().await.unwrap(), 0);
+
+        let events: Vec<(String, String)> = sqlx::query_as("SELECT topic, payload FROM stream_events WHERE topic LIKE 'post:%' ORDER BY id")
+            .fetch_all(&pool)
+            .await
+            .unwrap();
+        let topics: Vec<&str> = events.iter().map(|(t, _)| t.as_str()).collect();
+        assert_eq!(topics.len(), 3);
+        assert_eq!(topics[0], "post:0xrisky");
+        let payload: serde_json::Value = serde_json::from_str(&events[0].1).unwrap();
+        assert_eq!(payload["post_id"], "0xrisky");
+        assert!((payload["score"].as_f64().unwrap() - stored(&pool, "0xrisky").await.score).abs() < 1e-9);
+
+        let version: i64 = sqlx::query_scalar("SELECT version FROM ranking_state").fetch_one(&pool).await.unwrap();
+        assert_eq!(version, 2);
+    }
+}
@@ -0,0 +1,3 @@
+mod feed_ranker;
+
+pub use feed_ranker::*;
@@ -0,0 +1,147 @@
+#!/usr/bin/env python3
+"""
+Local stand-in for the Sui JSON-RPC endpoints suiter-api uses
+Run it and point the API at it:
+
+    python3 sui_rpc_stub.py 9000
+    SUI_RPC_URL=http://127.0.0.1:9000 PACKAGE_ID=0x1234 cargo run -p suiter-api
+
+Every address owns one 10 SUI gas coin, every other object is shared, and
+dry runs succeed with fixed gas unless the transaction bytes are malformed.
+Executed transactions are remembered so sui_getTransactionBlock can find
+them; no events are emitted.
+"""
+
+from http.server import HTTPServer, BaseHTTPRequestHandler
+import base64
+import hashlib
+import json
+import sys
+
+GAS_PRICE = 1000
+COIN_BALANCE = 10_000_000_000
+# base58 of 32 zero bytes
+ZERO_DIGEST = "1" * 32
+BASE58 = "123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz"
+
+# digest -> effects of every executed transaction
+EXECUTED = {}
+
+
+class RpcError(Exception):
+    pass
+
+
+def base58(raw):
+    n = int.from_bytes(raw, "big")
+    out = ""
+    while n:
+        n, r = divmod(n, 58)
+        out = BASE58[r] + out
+    return "1" * (len(raw) - len(raw.lstrip(b"\0"))) + out
+
+
+def tx_digest(raw):
+    return base58(hashlib.blake2b(b"TransactionData::" + raw, digest_size=32).digest())
+
+
+def get_coins(owner, *_):
+    return {
+        "data": [{
+            "coinType": "0x2::sui::SUI",
+            "coinObjectId": "0x" + owner[-62:].rjust(62, "0") + "c0",
+            "version": "7",
+            "digest": ZERO_DIGEST,
+            "balance": str(COIN_BALANCE),
+        }],
+        "nextCursor": None,
+        "hasNextPage": False,
+    }
+
+
+def get_object(object_id, *_):
+    return {
+        "data": {
+            "objectId": object_id,
+            "version": "3",
+            "digest": ZERO_DIGEST,
+            "owner": {"Shared": {"initial_shared_version": 3}},
+        }
+    }
+
+
+def dry_run(tx_bytes):
+    raw = base64.b64decode(tx_bytes)
+    # TransactionData::V1, TransactionKind::ProgrammableTransaction
+    if len(raw) < 2 or raw[0] != 0 or raw[1] != 0:
+        status = {"status": "failure", "error": "malformed transaction data"}
+    else:
+        status = {"status": "success"}
+    return {
+        "effects": {
+            "status": status,
+            "gasUsed": {
+                "computationCost": "1000000",
+                "storageCost": "2000000",
+                "storageRebate": "500000",
+                "nonRefundableStorageFee": "0",
+            },
+        },
+        "events": [],
+    }
+
+
+def execute(tx_bytes, signatures, *_):
+    if not signatures:
+        raise RpcError("Invalid user signature: no signatures")
+    effects = dry_run(tx_bytes)["effects"]
+    digest = tx_digest(base64.b64decode(tx_bytes))
+    EXECUTED[digest] = effects
+    return {"digest": digest, "effects": effects}
+
+
+def get_transaction(digest, *_):
+    if digest not in EXECUTED:
+        raise RpcError(f"Could not find the referenced transaction [TransactionDigest({digest})]")
+    return {"digest": digest, "effects": EXECUTED[digest]}
+
+
+METHODS = {
+    "suix_getReferenceGasPrice": lambda *_: str(GAS_PRICE),
+    "suix_getCoins": get_coins,
+    "sui_getObject": get_object,
+    "sui_dryRunTransactionBlock": dry_run,
+    "sui_executeTransactionBlock": execute,
+    "sui_getTransactionBlock": get_transaction,
+    "suix_queryEvents": lambda *_: {"data": [], "nextCursor": None, "hasNextPage": False},
+}
+
+
+class Handler(BaseHTTPRequestHandler):
+    def do_POST(self):
+        request = json.loads(self.rfile.read(int(self.headers["Content-Length"])))
+        method = METHODS.get(request.get("method"))
+        body = {"jsonrpc": "2.0", "id": request.get("id")}
+        if method is None:
+            body["error"] = {"code": -32601, "message": "method not found"}
+        else:
+            try:
+                body["result"] = method(*request.get("params", []))
+            except RpcError as e:
+                body["error"] = {"code": -32002, "message": str(e)}
+
+        out = json.dumps(body).encode()
+        self.send_response(200)
+        self.send_header("Content-Type", "application/json")
+        self.send_header("Content-Length", str(len(out)))
+        self.end_headers()
+        self.wfile.write(out)
+
+    def log_message(self, fmt, *args):
+        sys.stderr.write("sui-rpc-stub: " + fmt % args + "\n")
+
+
+if __name__ == "__main__":
+    port = int(sys.argv[1]) if len(sys.argv) > 1 else 9000
+    print(f"Sui RPC stub on http://127.0.0.1:{port}")
+    HTTPServer(("127.0.0.1", port), Handler).serve_forever()
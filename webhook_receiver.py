@@ -0,0 +1,79 @@
+#!/usr/bin/env python3
+"""
+Local receiver for testing webhook delivery from suiter-indexer
+Webhooks to loopback and private addresses are refused unless the API and
+the indexer both run with WEBHOOK_ALLOW_PRIVATE_NETWORKS=1. Run it and
+register it as a webhook URL:
+
+    python3 webhook_receiver.py 9200
+    curl -X PUT localhost:3000/api/notifications/preferences \\
+        -H 'content-type: application/json' \\
+        -d '{"address": "0x...", "webhook_url": "http://127.0.0.1:9200/hook"}'
+
+Every request is printed with its headers and JSON body. Pass a second
+argument to answer the first N requests with 500, to exercise retries:
+
+    python3 webhook_receiver.py 9200 2
+
+With WEBHOOK_SECRET set to the webhook's signing secret, each request's
+X-Suiter-Signature is checked and reported as valid or not.
+"""
+
+from http.server import HTTPServer, BaseHTTPRequestHandler
+import hashlib
+import hmac
+import json
+import os
+import sys
+
+SECRET = os.environ.get("WEBHOOK_SECRET")
+
+# Requests still to be failed
+FAIL_FIRST = [0]
+
+
+class Handler(BaseHTTPRequestHandler):
+    def do_POST(self):
+        length = int(self.headers.get("Content-Length", 0))
+        raw = self.rfile.read(length)
+        try:
+            body = json.dumps(json.loads(raw), indent=2)
+        except ValueError:
+            body = raw.decode(errors="replace")
+
+        failing = FAIL_FIRST[0] > 0
+        if failing:
+            FAIL_FIRST[0] -= 1
+        status = 500 if failing else 200
+
+        print(f"--- {self.command} {self.path} -> {status}")
+        for name, value in self.headers.items():
+            print(f"{name}: {value}")
+        if SECRET:
+            print(f"signature {'valid' if signature_valid(self.headers, raw) else 'INVALID'}")
+        print(body, flush=True)
+
+        self.send_response(status)
+        self.send_header("Content-Length", "0")
+        self.end_headers()
+
+    def log_message(self, format, *args):
+        pass
+
+
+def signature_valid(headers, raw):
+    """X-Suiter-Signature is sha256=HMAC(secret, "<timestamp>.<body>")"""
+    signed = headers.get("X-Suiter-Timestamp", "").encode() + b"." + raw
+    expected = "sha256=" + hmac.new(SECRET.encode(), signed, hashlib.sha256).hexdigest()
+    return hmac.compare_digest(expected, headers.get("X-Suiter-Signature", ""))
+
+
+def main():
+    port = int(sys.argv[1]) if len(sys.argv) > 1 else 9200
+    FAIL_FIRST[0] = int(sys.argv[2]) if len(sys.argv) > 2 else 0
+    print(f"Webhook receiver listening on http://127.0.0.1:{port}", flush=True)
+    HTTPServer(("127.0.0.1", port), Handler).serve_forever()
+
+
+if __name__ == "__main__":
+    main()